@@ -0,0 +1,475 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::alerts::rules::{ALERTS_FILE, AlertConfig, AlertRule, Condition};
+use crate::alerts::sinks::Sink;
+use crate::analysis::indicators::{self, CrossType};
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::CompanyMap;
+use crate::universe::watchlist;
+
+/// 觸發並送達任一輸出目標的警示都會附加寫入此檔
+pub const ALERTS_LOG: &str = "alerts.log";
+/// 已觸發警示 (與部分輸出目標已送達) 的紀錄，用來避免重複通知
+pub const ALERTS_FIRED_FILE: &str = "alerts_fired.json";
+
+/// 52 週約 240 個交易日
+const TRADING_DAYS_52W: usize = 240;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub stock_no: String,
+    pub name: String,
+    pub date: String,
+    pub rule: String,
+    pub close: f64,
+}
+
+/// 一次警示評估與送出的結果
+#[derive(Debug, Default)]
+pub struct AlertRun {
+    pub new_alerts: Vec<Alert>, // 尚未觸發過的警示
+    pub stdout: Vec<Alert>,     // 這次送到 Stdout 目標的警示，函式庫不輸出，由呼叫端印出
+    pub errors: Vec<String>,    // 送出失敗的目標，下次執行時重送
+}
+
+impl AlertRun {
+    /// 有目標送出失敗時的錯誤訊息
+    pub fn delivery_error(&self) -> Option<String> {
+        (!self.errors.is_empty())
+            .then(|| format!("警示輸出失敗，下次執行時重送: {}", self.errors.join("; ")))
+    }
+}
+
+impl Alert {
+    fn key(&self) -> String {
+        format!("{}|{}|{}", self.stock_no, self.date, self.rule)
+    }
+}
+
+impl Record for Alert {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("rule", "條件", ColumnKind::Text),
+            Column::new("close", "收盤價", ColumnKind::Price),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.date),
+            record::text(&self.stock_no),
+            record::text(&self.name),
+            record::text(&self.rule),
+            record::number(self.close),
+        ]
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} 收盤 {:.2}",
+            self.date, self.stock_no, self.name, self.rule, self.close
+        )
+    }
+}
+
+/// 以最後一個交易日判斷條件是否成立，daily_data 需依日期排序
+pub fn is_triggered(condition: &Condition, daily_data: &[DailyData]) -> bool {
+    let Some(last) = daily_data.last() else {
+        return false;
+    };
+    let i = daily_data.len() - 1;
+    let prev = if i > 0 { daily_data.get(i - 1) } else { None };
+
+    match condition {
+        Condition::PriceCrossAbove { price } => {
+            prev.is_some_and(|p| p.close < *price) && last.close >= *price
+        }
+        Condition::PriceCrossBelow { price } => {
+            prev.is_some_and(|p| p.close > *price) && last.close <= *price
+        }
+        Condition::ChangePercent { percent } => {
+            let prev_close = last.close - last.change;
+            prev_close > 0.0 && (last.change / prev_close * 100.0).abs() >= *percent
+        }
+        Condition::MacdGoldenCross | Condition::MacdDeathCross => {
+            if i == 0 {
+                return false;
+            }
+            let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+            let macd = indicators::macd(&closes);
+            let expected = if matches!(condition, Condition::MacdGoldenCross) {
+                CrossType::GoldenCross
+            } else {
+                CrossType::DeathCross
+            };
+            indicators::cross(
+                macd[i - 1].dif,
+                macd[i - 1].signal,
+                macd[i].dif,
+                macd[i].signal,
+            ) == Some(expected)
+        }
+        Condition::KdGoldenCross | Condition::KdDeathCross => {
+            if i == 0 {
+                return false;
+            }
+            let kd = indicators::kd(daily_data, 9);
+            let expected = if matches!(condition, Condition::KdGoldenCross) {
+                CrossType::GoldenCross
+            } else {
+                CrossType::DeathCross
+            };
+            indicators::cross(kd[i - 1].k, kd[i - 1].d, kd[i].k, kd[i].d) == Some(expected)
+        }
+        Condition::VolumeSpike { ratio, days } => {
+            if *days == 0 || i < *days {
+                return false;
+            }
+            let avg = daily_data[i - days..i]
+                .iter()
+                .map(|d| d.volume as f64)
+                .sum::<f64>()
+                / *days as f64;
+            avg > 0.0 && last.volume as f64 >= avg * ratio
+        }
+        Condition::NewHigh52w => {
+            if i == 0 {
+                return false;
+            }
+            let from = i.saturating_sub(TRADING_DAYS_52W);
+            let prev_high = daily_data[from..i]
+                .iter()
+                .map(|d| d.high)
+                .fold(f64::MIN, f64::max);
+            last.high > prev_high
+        }
+    }
+}
+
+/// 規則套用的股號
+fn rule_stock_nos(
+    rule: &AlertRule,
+    company_map: &CompanyMap,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if let Some(stock_no) = &rule.stock_no {
+        return Ok(vec![stock_no.clone()]);
+    }
+    if let Some(name) = &rule.watchlist {
+        return watchlist::load(name);
+    }
+    Ok(company_map
+        .stock_map
+        .iter()
+        .map(|company| company.stock_no.clone())
+        .collect())
+}
+
+/// 以本地資料評估所有規則在 year_month 最後一個交易日的警示
+pub fn evaluate(
+    company_map: &CompanyMap,
+    config: &AlertConfig,
+    year_month: &str,
+) -> Result<Vec<Alert>, Box<dyn Error>> {
+    evaluate_at(company_map, config, year_month, None)
+}
+
+/// 以本地資料評估所有規則在指定日期 (YYYYMMDD) 的警示，當日沒有資料的股票略過
+pub fn evaluate_date(
+    company_map: &CompanyMap,
+    config: &AlertConfig,
+    date: &str,
+) -> Result<Vec<Alert>, Box<dyn Error>> {
+    let (Some(year_month), Some(roc_date)) =
+        (common::year_month_of(date), common::to_roc_date(date))
+    else {
+        return Err(format!("無效的日期: {date}").into());
+    };
+    evaluate_at(company_map, config, year_month, Some(&roc_date))
+}
+
+/// roc_date 為 None 時評估 year_month 的最後一個交易日
+fn evaluate_at(
+    company_map: &CompanyMap,
+    config: &AlertConfig,
+    year_month: &str,
+    roc_date: Option<&str>,
+) -> Result<Vec<Alert>, Box<dyn Error>> {
+    let year_month_from = common::shift_year_month(year_month, -13)
+        .ok_or_else(|| format!("無效的月份: {year_month}"))?;
+
+    let mut rules_by_stock: HashMap<String, Vec<&AlertRule>> = HashMap::new();
+    for rule in &config.rules {
+        for stock_no in rule_stock_nos(rule, company_map)? {
+            rules_by_stock.entry(stock_no).or_default().push(rule);
+        }
+    }
+
+    let mut stock_nos: Vec<&String> = rules_by_stock.keys().collect();
+    stock_nos.sort();
+
+    let mut alerts = Vec::new();
+    for stock_no in stock_nos {
+        let mut daily_data =
+            monthly_data::load_daily_data_range(stock_no, &year_month_from, year_month);
+        if let Some(roc_date) = roc_date {
+            daily_data.retain(|daily| daily.date.as_str() <= roc_date);
+        }
+        let Some(last) = daily_data.last() else {
+            continue;
+        };
+        // 最後一筆不是指定日期或不在指定月份表示資料過舊，不發出警示
+        let is_current = match roc_date {
+            Some(roc_date) => last.date == roc_date,
+            None => common::roc_date_to_naive(&last.date)
+                .is_some_and(|date| date.format("%Y%m").to_string() == year_month),
+        };
+        if !is_current {
+            continue;
+        }
+
+        for rule in &rules_by_stock[stock_no] {
+            if is_triggered(&rule.condition, &daily_data) {
+                alerts.push(Alert {
+                    stock_no: stock_no.clone(),
+                    name: company_map.get_name(stock_no),
+                    date: last.date.clone(),
+                    rule: rule.condition.describe(),
+                    close: last.close,
+                });
+            }
+        }
+    }
+    Ok(alerts)
+}
+
+fn load_fired() -> HashSet<String> {
+    File::open(config::path(ALERTS_FIRED_FILE))
+        .ok()
+        .and_then(|file| serde_json::from_reader(std::io::BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+fn save_fired(fired: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+    let mut keys: Vec<&String> = fired.iter().collect();
+    keys.sort();
+    let writer = BufWriter::new(File::create(config::path(ALERTS_FIRED_FILE))?);
+    serde_json::to_writer_pretty(writer, &keys)?;
+    Ok(())
+}
+
+fn append_log(alerts: &[Alert]) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(config::path(ALERTS_LOG))?;
+    let now = Local::now().format("%Y-%m-%d %H:%M:%S");
+    for alert in alerts {
+        writeln!(file, "[{now}] {alert}")?;
+    }
+    Ok(())
+}
+
+/// 評估 year_month 最後一個交易日的警示、排除已觸發過的，寫入 log 並送到所有輸出目標
+/// 沒有規則檔時不做任何事；目標送出失敗記錄在 AlertRun::errors，不回傳 Err
+pub async fn run(company_map: &CompanyMap, year_month: &str) -> Result<AlertRun, Box<dyn Error>> {
+    let path = config::path(ALERTS_FILE);
+    if !path.exists() {
+        return Ok(AlertRun::default());
+    }
+    let config = AlertConfig::load(path)?;
+    let alerts = evaluate(company_map, &config, year_month)?;
+    notify(&config, alerts).await
+}
+
+/// 同 run，但評估指定日期 (YYYYMMDD)，補跑過去的交易日時使用
+pub async fn run_date(company_map: &CompanyMap, date: &str) -> Result<AlertRun, Box<dyn Error>> {
+    let path = config::path(ALERTS_FILE);
+    if !path.exists() {
+        return Ok(AlertRun::default());
+    }
+    let config = AlertConfig::load(path)?;
+    let alerts = evaluate_date(company_map, &config, date)?;
+    notify(&config, alerts).await
+}
+
+/// 排除已觸發過的警示後送出，第一次送達任一目標時寫入 log
+async fn notify(config: &AlertConfig, alerts: Vec<Alert>) -> Result<AlertRun, Box<dyn Error>> {
+    let mut fired = load_fired();
+    let new_alerts: Vec<Alert> = alerts
+        .into_iter()
+        .filter(|alert| !fired.contains(&alert.key()))
+        .collect();
+
+    if new_alerts.is_empty() {
+        return Ok(AlertRun::default());
+    }
+
+    let delivery = deliver(&config.sinks, &new_alerts, &mut fired).await;
+    if !delivery.first_sent.is_empty() {
+        append_log(&delivery.first_sent)?;
+    }
+    save_fired(&fired)?;
+
+    Ok(AlertRun {
+        new_alerts,
+        stdout: delivery.stdout,
+        errors: delivery.errors,
+    })
+}
+
+/// deliver 的結果
+struct Delivery {
+    first_sent: Vec<Alert>, // 這次第一次送達任一目標的警示
+    stdout: Vec<Alert>,
+    errors: Vec<String>,
+}
+
+/// 送到各輸出目標尚未送達的警示；每個目標送達後在 fired 記錄，失敗的下次執行時重送到該目標，
+/// 所有目標都送達才算觸發過，只留一筆紀錄
+async fn deliver(sinks: &[Sink], new_alerts: &[Alert], fired: &mut HashSet<String>) -> Delivery {
+    let is_delivered = |fired: &HashSet<String>, alert: &Alert, sink: &Sink| {
+        fired.contains(&alert.key()) || fired.contains(&sink_key(alert, sink))
+    };
+
+    let unsent: Vec<&Alert> = new_alerts
+        .iter()
+        .filter(|alert| !sinks.iter().any(|sink| is_delivered(fired, alert, sink)))
+        .collect();
+
+    let mut stdout = Vec::new();
+    let mut errors = Vec::new();
+    for sink in sinks {
+        let pending: Vec<Alert> = new_alerts
+            .iter()
+            .filter(|alert| !is_delivered(fired, alert, sink))
+            .cloned()
+            .collect();
+        if pending.is_empty() {
+            continue;
+        }
+        match sink.send(&pending).await {
+            Ok(()) => {
+                fired.extend(pending.iter().map(|alert| sink_key(alert, sink)));
+                if matches!(sink, Sink::Stdout) {
+                    stdout.extend(pending);
+                }
+            }
+            Err(e) => errors.push(format!("{sink:?}: {e}")),
+        }
+    }
+
+    let first_sent: Vec<Alert> = unsent
+        .into_iter()
+        .filter(|alert| {
+            sinks.is_empty() || sinks.iter().any(|sink| is_delivered(fired, alert, sink))
+        })
+        .cloned()
+        .collect();
+
+    for alert in new_alerts {
+        if sinks.iter().all(|sink| is_delivered(fired, alert, sink)) {
+            for sink in sinks {
+                fired.remove(&sink_key(alert, sink));
+            }
+            fired.insert(alert.key());
+        }
+    }
+
+    Delivery {
+        first_sent,
+        stdout,
+        errors,
+    }
+}
+
+/// 已送達某個輸出目標的紀錄
+fn sink_key(alert: &Alert, sink: &Sink) -> String {
+    format!("{}|{sink:?}", alert.key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(stock_no: &str) -> Alert {
+        Alert {
+            stock_no: stock_no.to_string(),
+            name: String::new(),
+            date: "115/01/05".to_string(),
+            rule: "新高".to_string(),
+            close: 100.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_only_failed_sinks() {
+        let dir = std::env::temp_dir().join(format!("alerts_deliver_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let json_file = Sink::JsonFile {
+            path: dir.join("alerts.json").to_string_lossy().to_string(),
+        };
+        let sinks = [Sink::Stdout, json_file.clone()];
+        let alerts = [alert("2330")];
+        let mut fired = HashSet::new();
+
+        // 目錄不存在，JSON 檔寫入失敗；stdout 已送達
+        let delivery = deliver(&sinks, &alerts, &mut fired).await;
+        assert_eq!(delivery.errors.len(), 1);
+        assert_eq!(delivery.stdout.len(), 1);
+        assert_eq!(delivery.first_sent.len(), 1);
+        assert_eq!(fired, HashSet::from([sink_key(&alerts[0], &Sink::Stdout)]));
+
+        // 重送時只送 JSON 檔，不再印出也不再寫 log；全部送達後只留一筆紀錄
+        std::fs::create_dir_all(&dir).unwrap();
+        let delivery = deliver(&sinks, &alerts, &mut fired).await;
+        assert!(delivery.errors.is_empty());
+        assert!(delivery.stdout.is_empty());
+        assert!(delivery.first_sent.is_empty());
+        assert_eq!(fired, HashSet::from([alerts[0].key()]));
+
+        // 已觸發過的不再送出
+        let delivery = deliver(&sinks, &alerts, &mut fired).await;
+        assert!(delivery.stdout.is_empty());
+        let written: Vec<Alert> =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("alerts.json")).unwrap())
+                .unwrap();
+        assert_eq!(written.len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn first_delivery_is_logged_once_per_alert() {
+        let mut fired = HashSet::from([alert("2317").key()]);
+        let alerts = [alert("2330")];
+        let delivery = deliver(&[Sink::Stdout], &alerts, &mut fired).await;
+        assert_eq!(delivery.first_sent.len(), 1);
+        assert_eq!(
+            fired,
+            HashSet::from([alert("2317").key(), alert("2330").key()])
+        );
+
+        // 沒有任何目標時仍記錄為已觸發並寫 log
+        let mut fired = HashSet::new();
+        let delivery = deliver(&[], &alerts, &mut fired).await;
+        assert_eq!(delivery.first_sent.len(), 1);
+        assert_eq!(fired, HashSet::from([alert("2330").key()]));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod evaluator;
+pub mod rules;
+pub mod sinks;
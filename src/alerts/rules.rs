@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::alerts::sinks::Sink;
+
+/// 警示規則檔，範例:
+/// {
+///   "sinks": [{ "type": "stdout" }, { "type": "webhook", "url": "http://127.0.0.1:8080/alerts" }],
+///   "rules": [
+///     { "stock_no": "2330", "condition": { "type": "price_cross_above", "price": 1200 } },
+///     { "watchlist": "core", "condition": { "type": "new_high52w" } },
+///     { "condition": { "type": "volume_spike", "ratio": 3, "days": 20 } }
+///   ]
+/// }
+/// stock_no 與 watchlist 都沒填時套用到全市場
+pub const ALERTS_FILE: &str = "alerts.json";
+
+#[derive(Debug, Deserialize)]
+pub struct AlertConfig {
+    #[serde(default = "default_sinks")]
+    pub sinks: Vec<Sink>,
+    pub rules: Vec<AlertRule>,
+}
+
+fn default_sinks() -> Vec<Sink> {
+    vec![Sink::Stdout]
+}
+
+impl AlertConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let file = File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    #[serde(default)]
+    pub stock_no: Option<String>,
+    #[serde(default)]
+    pub watchlist: Option<String>,
+    pub condition: Condition,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    /// 收盤價由下往上穿越 price
+    PriceCrossAbove {
+        price: f64,
+    },
+    /// 收盤價由上往下跌破 price
+    PriceCrossBelow {
+        price: f64,
+    },
+    /// 單日漲跌幅絕對值 >= percent (%)
+    ChangePercent {
+        percent: f64,
+    },
+    MacdGoldenCross,
+    MacdDeathCross,
+    KdGoldenCross,
+    KdDeathCross,
+    /// 成交量 >= 前 days 日均量的 ratio 倍
+    VolumeSpike {
+        ratio: f64,
+        days: usize,
+    },
+    /// 最高價創 52 週新高
+    NewHigh52w,
+}
+
+impl Condition {
+    pub fn describe(&self) -> String {
+        match self {
+            Condition::PriceCrossAbove { price } => format!("向上突破 {price}"),
+            Condition::PriceCrossBelow { price } => format!("向下跌破 {price}"),
+            Condition::ChangePercent { percent } => format!("漲跌幅超過 {percent}%"),
+            Condition::MacdGoldenCross => "MACD 黃金交叉".to_string(),
+            Condition::MacdDeathCross => "MACD 死亡交叉".to_string(),
+            Condition::KdGoldenCross => "KD 黃金交叉".to_string(),
+            Condition::KdDeathCross => "KD 死亡交叉".to_string(),
+            Condition::VolumeSpike { ratio, days } => {
+                format!("成交量大於 {days} 日均量 {ratio} 倍")
+            }
+            Condition::NewHigh52w => "創 52 週新高".to_string(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+
+use serde::Deserialize;
+use tokio::time::Duration;
+
+use crate::alerts::evaluator::Alert;
+
+/// 警示通知的輸出目標
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Sink {
+    /// 由呼叫端印出 AlertRun::stdout，函式庫本身不輸出
+    Stdout,
+    /// 累積寫入 JSON 陣列檔
+    JsonFile { path: String },
+    /// 以 POST 送出 JSON 陣列
+    Webhook { url: String },
+}
+
+impl Sink {
+    pub async fn send(&self, alerts: &[Alert]) -> Result<(), Box<dyn Error>> {
+        match self {
+            Sink::Stdout => {}
+            Sink::JsonFile { path } => {
+                let mut all: Vec<Alert> = File::open(path)
+                    .ok()
+                    .and_then(|file| serde_json::from_reader(std::io::BufReader::new(file)).ok())
+                    .unwrap_or_default();
+                all.extend_from_slice(alerts);
+
+                let writer = BufWriter::new(File::create(path)?);
+                serde_json::to_writer_pretty(writer, &all)?;
+            }
+            Sink::Webhook { url } => {
+                let client = reqwest::Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .build()?;
+                client
+                    .post(url)
+                    .json(alerts)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use serde_json::Value;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    fn alert() -> Alert {
+        Alert {
+            stock_no: "2330".to_string(),
+            name: "台積電".to_string(),
+            date: "115/01/05".to_string(),
+            rule: "收盤價突破 1000".to_string(),
+            close: 1010.0,
+        }
+    }
+
+    /// 在本機開一個 webhook，收到的內容送到回傳的 channel，回應 status
+    async fn webhook(status: StatusCode) -> (String, mpsc::UnboundedReceiver<Value>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/alerts",
+            post(move |axum::Json(body): axum::Json<Value>| async move {
+                sender.send(body).unwrap();
+                status
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, receiver)
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_alerts() {
+        let (url, mut receiver) = webhook(StatusCode::OK).await;
+        Sink::Webhook { url }.send(&[alert()]).await.unwrap();
+
+        let body = receiver.recv().await.unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!([{
+                "stock_no": "2330",
+                "name": "台積電",
+                "date": "115/01/05",
+                "rule": "收盤價突破 1000",
+                "close": 1010.0,
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_error_status_fails() {
+        let (url, mut receiver) = webhook(StatusCode::INTERNAL_SERVER_ERROR).await;
+        assert!(Sink::Webhook { url }.send(&[alert()]).await.is_err());
+        assert!(receiver.recv().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn json_file_appends() {
+        let path = std::env::temp_dir().join(format!("alerts_sink_{}.json", std::process::id()));
+        let sink = Sink::JsonFile {
+            path: path.to_string_lossy().to_string(),
+        };
+        sink.send(&[alert()]).await.unwrap();
+        sink.send(&[alert()]).await.unwrap();
+
+        let all: Vec<Alert> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(all.len(), 2);
+        std::fs::remove_file(path).unwrap();
+    }
+}
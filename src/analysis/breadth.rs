@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use ta::Next;
+use ta::indicators::ExponentialMovingAverage as Ema;
+
+use crate::analysis::indicators;
+use crate::common;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::CompanyMap;
+
+/// 往前多載入的月份數，讓 MA200、52 週新高/新低與 McClellan 在區間起點就有值
+pub const BREADTH_WARMUP_MONTHS: i32 = 12;
+/// 計算站上均線比例的均線天數
+pub const MA_PERIODS: [usize; 3] = [20, 60, 200];
+
+const MCCLELLAN_FAST: usize = 19;
+const MCCLELLAN_SLOW: usize = 39;
+
+/// 單一交易日的市場寬度
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BreadthPoint {
+    pub date: String, // 民國日期
+    pub advances: usize,
+    pub declines: usize,
+    pub unchanged: usize,
+    pub ad_line: i64,       // 騰落線：區間內 (上漲 - 下跌) 家數累計
+    pub mcclellan: f64,     // EMA19(上漲 - 下跌) - EMA39(上漲 - 下跌)
+    pub above_ma: [f64; 3], // 收盤價站上 MA20/MA60/MA200 的比例
+    pub new_highs: usize,   // 創 52 週新高家數
+    pub new_lows: usize,    // 創 52 週新低家數
+}
+
+impl BreadthPoint {
+    /// 站上 n 日均線的比例，n 不在 MA_PERIODS 時為 None
+    pub fn above_ma(&self, n: usize) -> Option<f64> {
+        MA_PERIODS
+            .iter()
+            .position(|period| *period == n)
+            .map(|i| self.above_ma[i])
+    }
+}
+
+impl Record for BreadthPoint {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("advances", "上漲", ColumnKind::Integer),
+            Column::new("declines", "下跌", ColumnKind::Integer),
+            Column::new("unchanged", "平盤", ColumnKind::Integer),
+            Column::new("ad_line", "騰落線", ColumnKind::Integer).colored(),
+            Column::new("mcclellan", "McClellan", ColumnKind::Price).colored(),
+            Column::new("above_ma20", "站上MA20", ColumnKind::Percent),
+            Column::new("above_ma60", "站上MA60", ColumnKind::Percent),
+            Column::new("above_ma200", "站上MA200", ColumnKind::Percent),
+            Column::new("new_highs", "52週新高", ColumnKind::Integer),
+            Column::new("new_lows", "52週新低", ColumnKind::Integer),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.date),
+            Value::from(self.advances),
+            Value::from(self.declines),
+            Value::from(self.unchanged),
+            Value::from(self.ad_line),
+            record::number(self.mcclellan),
+            record::number(self.above_ma[0]),
+            record::number(self.above_ma[1]),
+            record::number(self.above_ma[2]),
+            Value::from(self.new_highs),
+            Value::from(self.new_lows),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct DayCounts {
+    advances: usize,
+    declines: usize,
+    unchanged: usize,
+    above_ma: [usize; 3],
+    with_ma: [usize; 3], // 資料足夠計算均線的家數
+    new_highs: usize,
+    new_lows: usize,
+}
+
+/// 以本地所有股票 (不含 ETF) 計算區間內每個交易日的市場寬度，
+/// 與掃描範圍無關，讓自選股或單一產業的選股也能用全市場的多空環境過濾；
+/// sector::performance 與每日報告的市場概況則只統計掃描範圍內的股票
+pub fn series(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+) -> Vec<BreadthPoint> {
+    let (Some(warmup_from), Some(range_start)) = (
+        common::shift_year_month(year_month_from, -BREADTH_WARMUP_MONTHS),
+        common::to_roc_date(&format!("{year_month_from}01")),
+    ) else {
+        return Vec::new();
+    };
+
+    let all_daily_data = monthly_data::stored_stock_nos()
+        .into_iter()
+        .filter(|stock_no| !company_map.is_etf(stock_no))
+        .map(|stock_no| {
+            monthly_data::load_daily_data_range(&stock_no, &warmup_from, year_month_to)
+        });
+    points(all_daily_data, &range_start)
+}
+
+/// 由每檔股票的日資料 (依日期排序，含暖機期間) 計算 range_start (民國日期) 起每個交易日的市場寬度
+fn points<I>(all_daily_data: I, range_start: &str) -> Vec<BreadthPoint>
+where
+    I: IntoIterator<Item = Vec<DailyData>>,
+{
+    let mut days: BTreeMap<String, DayCounts> = BTreeMap::new();
+    for daily_data in all_daily_data {
+        let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+        let mas: Vec<Vec<Option<f64>>> = MA_PERIODS
+            .iter()
+            .map(|n| indicators::sma(&closes, *n))
+            .collect();
+
+        for (i, daily) in daily_data.iter().enumerate() {
+            let counts = days.entry(daily.date.clone()).or_default();
+            if daily.change > 0.0 {
+                counts.advances += 1;
+            } else if daily.change < 0.0 {
+                counts.declines += 1;
+            } else {
+                counts.unchanged += 1;
+            }
+
+            // 均線與新高/新低只需要區間內的交易日
+            if daily.date.as_str() < range_start {
+                continue;
+            }
+            for (k, ma) in mas.iter().enumerate() {
+                if let Some(ma) = ma[i] {
+                    counts.with_ma[k] += 1;
+                    if daily.close > ma {
+                        counts.above_ma[k] += 1;
+                    }
+                }
+            }
+            let (new_high, new_low) = indicators::new_high_low(&daily_data, i);
+            counts.new_highs += new_high as usize;
+            counts.new_lows += new_low as usize;
+        }
+    }
+
+    let mut fast = Ema::new(MCCLELLAN_FAST).unwrap();
+    let mut slow = Ema::new(MCCLELLAN_SLOW).unwrap();
+    let mut ad_line = 0;
+    let mut ret = Vec::new();
+    for (date, counts) in days {
+        let net = counts.advances as i64 - counts.declines as i64;
+        let mcclellan = fast.next(net as f64) - slow.next(net as f64);
+        if date.as_str() < range_start {
+            continue;
+        }
+        ad_line += net;
+        let ratio = |k: usize| {
+            if counts.with_ma[k] == 0 {
+                0.0
+            } else {
+                counts.above_ma[k] as f64 / counts.with_ma[k] as f64
+            }
+        };
+        ret.push(BreadthPoint {
+            date,
+            advances: counts.advances,
+            declines: counts.declines,
+            unchanged: counts.unchanged,
+            ad_line,
+            mcclellan,
+            above_ma: [ratio(0), ratio(1), ratio(2)],
+            new_highs: counts.new_highs,
+            new_lows: counts.new_lows,
+        });
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 收盤價依序為 closes 的日資料，日期為 115/01/01 起
+    fn stock(closes: &[f64]) -> Vec<DailyData> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| DailyData {
+                date: format!("115/01/{:02}", i + 1),
+                volume: 1_000,
+                amount: 1_000,
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                change: if i == 0 { 0.0 } else { close - closes[i - 1] },
+                transactions: 1,
+                note: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_advances_and_accumulates_ad_line() {
+        let points = points(
+            [stock(&[10.0, 11.0, 12.0]), stock(&[10.0, 9.0, 9.0])],
+            "115/01/01",
+        );
+        let counts: Vec<(usize, usize, usize, i64)> = points
+            .iter()
+            .map(|p| (p.advances, p.declines, p.unchanged, p.ad_line))
+            .collect();
+        assert_eq!(counts, [(0, 0, 2, 0), (1, 1, 0, 0), (1, 0, 1, 1)]);
+    }
+
+    #[test]
+    fn mcclellan_is_fast_minus_slow_ema() {
+        // 淨上漲家數依序為 0, 0, +2
+        let points = points(
+            [stock(&[10.0, 10.0, 11.0]), stock(&[10.0, 10.0, 11.0])],
+            "115/01/01",
+        );
+        assert_eq!(points[0].mcclellan, 0.0);
+        assert_eq!(points[1].mcclellan, 0.0);
+        // EMA19: 2 × 2/20 = 0.2，EMA39: 2 × 2/40 = 0.1
+        assert!((points[2].mcclellan - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn warmup_days_feed_mcclellan_but_not_ad_line() {
+        let points = points(
+            [
+                stock(&[10.0, 10.0, 11.0, 11.0]),
+                stock(&[10.0, 10.0, 11.0, 11.0]),
+            ],
+            "115/01/04",
+        );
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].date, "115/01/04");
+        assert_eq!(points[0].ad_line, 0);
+        // 第 3 天的 +2 仍在 EMA 中：EMA19 0.2 × 18/20 = 0.18，EMA39 0.1 × 38/40 = 0.095
+        assert!((points[0].mcclellan - 0.085).abs() < 1e-9);
+    }
+
+    #[test]
+    fn above_ma_ratio_counts_stocks_with_enough_data() {
+        let rising: Vec<f64> = (1..=21).map(|i| i as f64).collect();
+        let falling: Vec<f64> = (1..=21).rev().map(|i| i as f64).collect();
+        let short = [5.0, 6.0]; // 資料不足 20 日，不計入分母
+        let points = points(
+            [stock(&rising), stock(&falling), stock(&short)],
+            "115/01/01",
+        );
+
+        // 第 19 天還沒有 MA20
+        assert_eq!(points[18].above_ma(20), Some(0.0));
+        assert_eq!(points[19].above_ma(20), Some(0.5));
+        assert_eq!(points[20].above_ma(20), Some(0.5));
+        assert_eq!(points[20].above_ma(60), Some(0.0));
+        assert_eq!(points[20].above_ma(10), None);
+    }
+
+    #[test]
+    fn counts_new_highs_within_range() {
+        let points = points([stock(&[10.0, 12.0, 11.0])], "115/01/02");
+        assert_eq!(points[0].new_highs, 1);
+        assert_eq!(points[1].new_highs, 0);
+    }
+}
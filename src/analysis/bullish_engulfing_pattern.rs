@@ -1,8 +1,14 @@
+use serde::Serialize;
+use serde_json::Value;
+
 use crate::common;
+use crate::config;
 use crate::data;
 use crate::data::monthly_data::DailyData;
+use crate::output::record::{self, Column, ColumnKind, Record};
 use crate::twse::company_map::CompanyMap;
 
+#[derive(Serialize)]
 pub struct BullishEngulfingPattern {
     pub stock_no: String,
     pub date: String,
@@ -10,7 +16,31 @@ pub struct BullishEngulfingPattern {
     pub curr_day: DailyData,
 }
 
-fn is_bullish_engulfing(prev: &DailyData, curr: &DailyData) -> bool {
+impl Record for BullishEngulfingPattern {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("close", "收盤", ColumnKind::Price),
+            Column::new("target", "高點", ColumnKind::Price),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.date),
+            record::text(&self.stock_no),
+            record::number(self.curr_day.close),
+            record::number(self.curr_day.close * config::get().thresholds.engulfing_target_ratio),
+            record::text(&company_map.get_name(&self.stock_no)),
+            record::text(&company_map.get_industry(&self.stock_no)),
+        ]
+    }
+}
+
+pub fn is_bullish_engulfing(prev: &DailyData, curr: &DailyData) -> bool {
     // 前一天是黑K
     let prev_black = prev.close < prev.open;
     // 當天是紅K
@@ -28,18 +58,13 @@ pub async fn anal_range_company(
     year_month_from: &str,
     year_month_to: &str,
 ) -> Vec<BullishEngulfingPattern> {
-    let (year_str, month_str) = year_month_from.split_at(4);
-    let mut year: u32 = year_str.parse().unwrap();
-    let mut month: u32 = month_str.parse().unwrap();
-
     let mut results = Vec::new();
     let mut prev = None;
     #[allow(unused_assignments)]
     let mut curr = None;
-    loop {
-        let year_month = format!("{:04}{:02}", year, month);
+    for year_month in common::year_months(year_month_from, year_month_to) {
         let monthly_data =
-            data::monthly_data::MonthlyData::new(company_map, stock_no, &year_month).await;
+            data::monthly_data::MonthlyData::new_or_empty(company_map, stock_no, &year_month).await;
 
         let mut daily_data = monthly_data.daily_data.clone();
         daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
@@ -62,65 +87,42 @@ pub async fn anal_range_company(
             }
             prev = curr.clone();
         }
-
-        if year_month == year_month_to {
-            break;
-        }
-
-        // 月份 +1
-        month += 1;
-        if month > 12 {
-            month = 1;
-            year += 1;
-        }
     }
     results
 }
 
-fn get_prev_month(year_month: &str) -> String {
-    let (year_str, month_str) = year_month.split_at(4);
-    let mut year: u32 = year_str.parse().unwrap();
-    let mut month: u32 = month_str.parse().unwrap();
-
-    // 月份 -1
-    if month == 1 {
-        month = 12;
-        year -= 1;
-    } else {
-        month -= 1;
-    }
-
-    format!("{:04}{:02}", year, month)
-}
-
 pub async fn anal_date_company(
     company_map: &CompanyMap,
     stock_no: &str,
     date: &str,
 ) -> Vec<BullishEngulfingPattern> {
-    let year_month = &date[0..6]; // 提取年月部分
-    let monthly_data =
-        data::monthly_data::MonthlyData::new(company_map, stock_no, year_month).await;
-
     let mut results = Vec::new();
+    let (Some(year_month), Some(roc_date)) =
+        (common::year_month_of(date), common::to_roc_date(date))
+    else {
+        return results;
+    };
+    let monthly_data =
+        data::monthly_data::MonthlyData::new_or_empty(company_map, stock_no, year_month).await;
 
     let mut daily_data = monthly_data.daily_data.clone();
     daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
-    let daily = daily_data
-        .iter()
-        .find(|d| d.date == common::to_roc_date(date));
-    if daily.is_none() {
-        println!("==========> No data for {} on date {}", stock_no, date);
+    let Some(prev_index) = daily_data.iter().position(|d| d.date == roc_date) else {
+        common::log(&format!(
+            "==========> No data for {} on date {}",
+            stock_no, date
+        ));
         return results;
-    }
+    };
 
-    let curr = daily.unwrap();
-    let prev_index = daily_data.iter().position(|d| d.date == curr.date).unwrap();
+    let curr = &daily_data[prev_index];
     if prev_index == 0 {
         // get prev month's last day
-        let prev_month = get_prev_month(year_month);
+        let Some(prev_month) = common::shift_year_month(year_month, -1) else {
+            return results;
+        };
         let prev_monthly_data =
-            data::monthly_data::MonthlyData::new(company_map, stock_no, &prev_month).await;
+            data::monthly_data::MonthlyData::new_or_empty(company_map, stock_no, &prev_month).await;
         let mut prev_daily_data = prev_monthly_data.daily_data.clone();
         prev_daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
         if let Some(prev) = prev_daily_data.last() {
@@ -133,10 +135,10 @@ pub async fn anal_date_company(
                 });
             }
         } else {
-            panic!(
+            common::log(&format!(
                 "==========> No previous data for {} on date {}",
                 stock_no, date
-            );
+            ));
         }
     } else {
         let prev = &daily_data[prev_index - 1];
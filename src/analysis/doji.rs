@@ -1,16 +1,25 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config;
 use crate::data::monthly_data::DailyData;
 use crate::data::monthly_data::MonthlyData;
+use crate::output::record::{self, Column, Record};
 use crate::twse::company_map::CompanyMap;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct DojiAnalysisResult {
     pub stock_no: String,
     pub daily_data: DailyData,
 }
 
-impl DojiAnalysisResult {
-    pub fn print(&self, company_map: &CompanyMap) {
-        self.daily_data.print(company_map, &self.stock_no);
+impl Record for DojiAnalysisResult {
+    fn columns() -> Vec<Column> {
+        record::daily_columns()
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        record::daily_values(company_map, &self.stock_no, &self.daily_data)
     }
 }
 
@@ -38,31 +47,38 @@ pub fn anal_month_company(company_map: &CompanyMap, monthly_data: &MonthlyData)
 }
  */
 
+pub fn is_doji(daily: &DailyData, tolerance: f64) -> bool {
+    let open = daily.open;
+    let close = daily.close;
+    let high = daily.high;
+    let low = daily.low;
+
+    // A simple Doji condition: Open and Close prices are very close
+    // And, open is not equal to high and low
+    // And, close is not equal to high and low
+    (open - close).abs() < tolerance * ((high - low).max(1.0))
+        && open != high
+        && open != low
+        && close != high
+        && close != low
+}
+
 pub fn anal_date_company(
     _company_map: &CompanyMap,
     monthly_data: &MonthlyData,
     year_month_date: &str,
 ) -> Vec<DojiAnalysisResult> {
     let mut ret_results = Vec::new();
+    let Some(roc_date) = crate::common::to_roc_date(year_month_date) else {
+        return ret_results;
+    };
 
     for daily in &monthly_data.daily_data {
-        if daily.date == crate::common::to_roc_date(year_month_date) {
-            let open = daily.open;
-            let close = daily.close;
-            let high = daily.high;
-            let low = daily.low;
-
-            // A simple Doji condition: Open and Close prices are very close
-            // And, open is not equal to high and low
-            // And, close is not equal to high and low
-            if (open - close).abs() < 0.01 * ((high - low).max(1.0)) &&
-               open != high && open != low &&
-               close != high && close != low {
-                ret_results.push(DojiAnalysisResult {
-                    stock_no: monthly_data.stock_no.clone(),
-                    daily_data: daily.clone(),
-                });
-            }
+        if daily.date == roc_date && is_doji(daily, config::get().thresholds.doji_tolerance) {
+            ret_results.push(DojiAnalysisResult {
+                stock_no: monthly_data.stock_no.clone(),
+                daily_data: daily.clone(),
+            });
         }
     }
 
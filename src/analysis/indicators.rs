@@ -0,0 +1,108 @@
+use chrono::Duration;
+use ta::Next;
+use ta::indicators::ExponentialMovingAverage as Ema;
+
+use crate::common;
+use crate::data::monthly_data::DailyData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossType {
+    GoldenCross,
+    DeathCross,
+}
+
+/// 判斷 a 線與 b 線在前一天到今天之間是否交叉
+pub fn cross(prev_a: f64, prev_b: f64, a: f64, b: f64) -> Option<CrossType> {
+    if prev_a <= prev_b && a > b {
+        Some(CrossType::GoldenCross)
+    } else if prev_a >= prev_b && a < b {
+        Some(CrossType::DeathCross)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MacdValue {
+    pub dif: f64,
+    pub signal: f64,
+}
+
+/// MACD(12, 26, 9)，與 MacdCalculator 相同算法
+pub fn macd(closes: &[f64]) -> Vec<MacdValue> {
+    let mut ema12 = Ema::new(12).unwrap();
+    let mut ema26 = Ema::new(26).unwrap();
+    let mut signal_ema9 = Ema::new(9).unwrap();
+
+    closes
+        .iter()
+        .map(|close| {
+            let dif = ema12.next(*close) - ema26.next(*close);
+            let signal = signal_ema9.next(dif);
+            MacdValue { dif, signal }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KdValue {
+    pub k: f64,
+    pub d: f64,
+}
+
+/// 台股常用 KD(9, 3, 3)：RSV 以 n 日最高/最低計算，K、D 起始值為 50
+pub fn kd(daily_data: &[DailyData], n: usize) -> Vec<KdValue> {
+    let mut k = 50.0;
+    let mut d = 50.0;
+
+    (0..daily_data.len())
+        .map(|i| {
+            let from = (i + 1).saturating_sub(n);
+            let window = &daily_data[from..=i];
+            let highest = window.iter().map(|d| d.high).fold(f64::MIN, f64::max);
+            let lowest = window.iter().map(|d| d.low).fold(f64::MAX, f64::min);
+
+            let rsv = if highest > lowest {
+                (daily_data[i].close - lowest) / (highest - lowest) * 100.0
+            } else {
+                50.0
+            };
+            k = k * 2.0 / 3.0 + rsv / 3.0;
+            d = d * 2.0 / 3.0 + k / 3.0;
+            KdValue { k, d }
+        })
+        .collect()
+}
+
+/// n 日簡單移動平均，資料不足 n 日的位置為 None
+pub fn sma(values: &[f64], n: usize) -> Vec<Option<f64>> {
+    (0..values.len())
+        .map(|i| {
+            (n > 0 && i + 1 >= n).then(|| values[i + 1 - n..=i].iter().sum::<f64>() / n as f64)
+        })
+        .collect()
+}
+
+/// 第 index 日是否創 52 週新高/新低 (與前 52 週的最高/最低價比較，不含當日)，
+/// 沒有前一年資料 (例如新上市) 時都不算
+pub fn new_high_low(daily_data: &[DailyData], index: usize) -> (bool, bool) {
+    let daily = &daily_data[index];
+    let Some(start) = common::roc_date_to_naive(&daily.date)
+        .map(|date| common::naive_to_roc_date(date - Duration::weeks(52)))
+    else {
+        return (false, false);
+    };
+    let previous: Vec<&DailyData> = daily_data[..index]
+        .iter()
+        .rev()
+        .take_while(|d| d.date >= start)
+        .filter(|d| d.high > 0.0)
+        .collect();
+    if previous.is_empty() {
+        return (false, false);
+    }
+    (
+        previous.iter().all(|d| daily.high > d.high),
+        previous.iter().all(|d| daily.low < d.low),
+    )
+}
@@ -1,38 +1,71 @@
+use serde::Serialize;
+use serde_json::Value;
+
 use crate::common;
-use crate::data::monthly_data::MonthlyData;
+use crate::config;
+use crate::data::monthly_data::{DailyData, MonthlyData};
+use crate::output::record::{self, Column, Record};
 use crate::twse::company_map::CompanyMap;
 
-pub async fn anal_date_company(company_map: &CompanyMap, stock_no: &str, year_month_date: &str) {
-    let monthly_data = MonthlyData::new(company_map, stock_no, &year_month_date[..6]).await;
-    let roc_date = common::to_roc_date(year_month_date);
+#[derive(Clone, Serialize)]
+pub struct LongRedCandleResult {
+    pub stock_no: String,
+    pub daily_data: DailyData,
+}
+
+impl Record for LongRedCandleResult {
+    fn columns() -> Vec<Column> {
+        record::daily_columns()
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        record::daily_values(company_map, &self.stock_no, &self.daily_data)
+    }
+}
+
+pub fn is_long_red_candle(daily: &DailyData, ratio: f64) -> bool {
+    // A simple Long Red Candle condition: Close price significantly higher than Open price
+    daily.close > daily.open * ratio
+}
+
+pub async fn anal_date_company(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month_date: &str,
+) -> Vec<LongRedCandleResult> {
+    let (Some(year_month), Some(roc_date)) = (
+        common::year_month_of(year_month_date),
+        common::to_roc_date(year_month_date),
+    ) else {
+        return Vec::new();
+    };
+    let monthly_data = MonthlyData::new_or_empty(company_map, stock_no, year_month).await;
 
     // Filter daily data for the specific date
-    let daily_data_for_date: Vec<_> = monthly_data
+    monthly_data
         .daily_data
         .iter()
-        .filter(|daily| daily.date == roc_date)
-        .cloned()
-        .collect();
-
-    for daily in daily_data_for_date {
-        let open = daily.open;
-        let close = daily.close;
-
-        // A simple Long Red Candle condition: Close price significantly higher than Open price
-        if close > open * 1.05 {
-            daily.print(company_map, &monthly_data.stock_no);
-        }
-    }
+        .filter(|daily| {
+            daily.date == roc_date
+                && is_long_red_candle(daily, config::get().thresholds.long_red_ratio)
+        })
+        .map(|daily| LongRedCandleResult {
+            stock_no: monthly_data.stock_no.clone(),
+            daily_data: daily.clone(),
+        })
+        .collect()
 }
 
-pub async fn anal_date_all_companies(company_map: &CompanyMap, year_month_date: &str) {
-    println!(
-        "{:<8}{:<5}{:>6}{:>5}{:>5}{:>5}{:>5}{:>6} 公司名稱",
-        "日期", "台股", "成交股數", "開盤價", "收盤價", "最高價", "最低價", "漲跌",
-    );
+pub async fn anal_date_all_companies(
+    company_map: &CompanyMap,
+    year_month_date: &str,
+) -> Vec<LongRedCandleResult> {
+    let mut ret_results = Vec::new();
     for company in &company_map.stock_map {
-        anal_date_company(company_map, &company.stock_no, year_month_date).await;
+        ret_results
+            .extend(anal_date_company(company_map, &company.stock_no, year_month_date).await);
     }
+    ret_results
 }
 
 /*
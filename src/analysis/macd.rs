@@ -1,16 +1,30 @@
+use serde::Serialize;
+use serde_json::Value;
 use ta::Next;
 use ta::indicators::ExponentialMovingAverage as Ema;
 
+use crate::common;
 use crate::data;
+use crate::output::record::{self, Column, ColumnKind, Record};
 use crate::twse::company_map::CompanyMap;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MacdCrossType {
     GoldenCross,
     DeathCross,
 }
 
-#[derive(Debug, Clone)]
+impl MacdCrossType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MacdCrossType::GoldenCross => "黃金交叉",
+            MacdCrossType::DeathCross => "死亡交叉",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct MacdCross {
     pub stock_no: String,
     pub date: String,
@@ -19,26 +33,29 @@ pub struct MacdCross {
     pub cross_type: MacdCrossType,
 }
 
-impl MacdCross {
-    pub fn print(&self, company_map: &CompanyMap) {
-        match self.cross_type {
-            MacdCrossType::GoldenCross => {
-                println!(
-                    "🚀 【黃金交叉】{} Date: {} {}",
-                    self.stock_no,
-                    self.date,
-                    company_map.get(&self.stock_no)
-                );
-            }
-            MacdCrossType::DeathCross => {
-                println!(
-                    "💀 【死亡交叉】{} Date: {} {}",
-                    self.stock_no,
-                    self.date,
-                    company_map.get(&self.stock_no)
-                );
-            }
-        }
+impl Record for MacdCross {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("cross_type", "交叉", ColumnKind::Text),
+            Column::new("dif", "DIF", ColumnKind::Price),
+            Column::new("macd_signal", "MACD", ColumnKind::Price),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.date),
+            record::text(&self.stock_no),
+            record::text(self.cross_type.name()),
+            record::number(self.dif),
+            record::number(self.macd_signal),
+            record::text(&company_map.get_name(&self.stock_no)),
+            record::text(&company_map.get_industry(&self.stock_no)),
+        ]
     }
 }
 
@@ -85,18 +102,16 @@ impl MacdCalculator {
     pub async fn calc(&mut self, company_map: &CompanyMap) -> (Vec<MacdResult>, Vec<MacdCross>) {
         self.reset();
 
-        let (year_str, month_str) = self.year_month_from.split_at(4);
-        let mut year: u32 = year_str.parse().unwrap();
-        let mut month: u32 = month_str.parse().unwrap();
-
         let mut results = Vec::new();
         let mut macd_crosses = Vec::new();
 
-        loop {
-            let year_month = format!("{:04}{:02}", year, month);
-            let monthly_data =
-                data::monthly_data::MonthlyData::new(company_map, &self.stock_no, &year_month)
-                    .await;
+        for year_month in common::year_months(&self.year_month_from, &self.year_month_to) {
+            let monthly_data = data::monthly_data::MonthlyData::new_or_empty(
+                company_map,
+                &self.stock_no,
+                &year_month,
+            )
+            .await;
 
             let mut daily_data = monthly_data.daily_data.clone();
             daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
@@ -109,17 +124,6 @@ impl MacdCalculator {
                     macd_crosses.push(c);
                 }
             }
-
-            if year_month == self.year_month_to {
-                break;
-            }
-
-            // 月份 +1
-            month += 1;
-            if month > 12 {
-                month = 1;
-                year += 1;
-            }
         }
 
         (results, macd_crosses)
@@ -1,6 +1,10 @@
+pub mod breadth;
 pub mod bullish_engulfing_pattern;
 pub mod doji;
+pub mod indicators;
 pub mod long_red_candle;
 pub mod macd;
 pub mod range;
+pub mod sector;
+pub mod sweep;
 pub mod volume;
@@ -1,3 +1,4 @@
+use crate::common;
 use crate::data::monthly_data::MonthlyData;
 use crate::twse::company_map::CompanyMap;
 
@@ -16,16 +17,11 @@ pub async fn anal_range_high_low_company(
     year_month_from: &str,
     year_month_to: &str,
 ) -> RangeHighLow {
-    let (year_str, month_str) = year_month_from.split_at(4);
-    let mut year: u32 = year_str.parse().unwrap();
-    let mut month: u32 = month_str.parse().unwrap();
-
     let mut highest_price: f64 = 0.0;
     let mut lowest_price: f64 = f64::MAX;
 
-    loop {
-        let year_month = format!("{:04}{:02}", year, month);
-        let monthly_data = MonthlyData::new(company_map, stock_no, &year_month).await;
+    for year_month in common::year_months(year_month_from, year_month_to) {
+        let monthly_data = MonthlyData::new_or_empty(company_map, stock_no, &year_month).await;
 
         for daily in &monthly_data.daily_data {
             if daily.close > highest_price {
@@ -35,17 +31,6 @@ pub async fn anal_range_high_low_company(
                 lowest_price = daily.close;
             }
         }
-
-        if year_month == year_month_to {
-            break;
-        }
-
-        // 月份 +1
-        month += 1;
-        if month > 12 {
-            month = 1;
-            year += 1;
-        }
     }
 
     RangeHighLow {
@@ -0,0 +1,347 @@
+use serde_json::Value;
+
+use crate::analysis::indicators;
+use crate::common;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::CompanyMap;
+
+/// 計算 52 週新高/新低往前讀的月份數
+const HIGH_LOW_MONTHS: i32 = 12;
+
+/// 單一股票當日的漲跌幅
+#[derive(Debug, Clone)]
+pub struct Mover {
+    pub stock_no: String,
+    pub name: String,
+    pub close: f64,
+    pub change_rate: f64, // 比例
+}
+
+impl Mover {
+    /// 前一日收盤價為 0 (例如新上市首日) 時無法計算
+    pub fn new(company_map: &CompanyMap, stock_no: &str, daily: &DailyData) -> Option<Self> {
+        let prev_close = daily.close - daily.change;
+        (prev_close > 0.0).then(|| Mover {
+            stock_no: stock_no.to_string(),
+            name: company_map.get_name(stock_no),
+            close: daily.close,
+            change_rate: daily.change / prev_close,
+        })
+    }
+}
+
+/// 依產業或類股分組
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Industry,
+    Sector,
+}
+
+/// 單一股票當日資料，以及是否創 52 週新高/新低
+struct StockDay {
+    stock_no: String,
+    daily: DailyData,
+    new_high: bool,
+    new_low: bool,
+}
+
+/// 一個產業或類股當日的表現；最後一列是全市場
+#[derive(Debug, Clone)]
+pub struct GroupPerformance {
+    pub sector: String,
+    pub industry: String, // 依類股分組或全市場時為空白
+    pub total: usize,
+    pub advances: usize,
+    pub declines: usize,
+    pub unchanged: usize,
+    pub avg_change_rate: f64,    // 比例
+    pub median_change_rate: f64, // 比例
+    pub amount: u64,             // 元
+    pub new_highs: usize,        // 創 52 週新高
+    pub new_lows: usize,         // 創 52 週新低
+    pub gainers: Vec<Mover>,
+    pub losers: Vec<Mover>,
+}
+
+impl GroupPerformance {
+    fn new(
+        company_map: &CompanyMap,
+        sector: &str,
+        industry: &str,
+        days: &[&StockDay],
+        top: usize,
+    ) -> Self {
+        let mut movers: Vec<Mover> = days
+            .iter()
+            .filter_map(|day| Mover::new(company_map, &day.stock_no, &day.daily))
+            .collect();
+        movers.sort_by(|a, b| b.change_rate.total_cmp(&a.change_rate));
+
+        let rates: Vec<f64> = movers.iter().map(|m| m.change_rate).collect();
+        let avg_change_rate = if rates.is_empty() {
+            0.0
+        } else {
+            rates.iter().sum::<f64>() / rates.len() as f64
+        };
+        let median_change_rate = match rates.len() {
+            0 => 0.0,
+            n if n % 2 == 1 => rates[n / 2],
+            n => (rates[n / 2 - 1] + rates[n / 2]) / 2.0,
+        };
+
+        GroupPerformance {
+            sector: sector.to_string(),
+            industry: industry.to_string(),
+            total: days.len(),
+            advances: days.iter().filter(|d| d.daily.change > 0.0).count(),
+            declines: days.iter().filter(|d| d.daily.change < 0.0).count(),
+            unchanged: days.iter().filter(|d| d.daily.change == 0.0).count(),
+            avg_change_rate,
+            median_change_rate,
+            amount: days.iter().map(|d| d.daily.amount).sum(),
+            new_highs: days.iter().filter(|d| d.new_high).count(),
+            new_lows: days.iter().filter(|d| d.new_low).count(),
+            gainers: movers
+                .iter()
+                .take_while(|m| m.change_rate > 0.0)
+                .take(top)
+                .cloned()
+                .collect(),
+            losers: movers
+                .iter()
+                .rev()
+                .take_while(|m| m.change_rate < 0.0)
+                .take(top)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn format_movers(movers: &[Mover]) -> String {
+    movers
+        .iter()
+        .map(|m| format!("{} {} {:+.2}%", m.stock_no, m.name, m.change_rate * 100.0))
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+impl Record for GroupPerformance {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("sector", "類股", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+            Column::new("total", "檔數", ColumnKind::Integer),
+            Column::new("advances", "上漲", ColumnKind::Integer),
+            Column::new("declines", "下跌", ColumnKind::Integer),
+            Column::new("unchanged", "平盤", ColumnKind::Integer),
+            Column::new("avg_change_rate", "平均漲跌幅", ColumnKind::Percent).colored(),
+            Column::new("median_change_rate", "漲跌幅中位數", ColumnKind::Percent).colored(),
+            Column::new("amount", "成交金額", ColumnKind::Integer),
+            Column::new("new_highs", "52週新高", ColumnKind::Integer),
+            Column::new("new_lows", "52週新低", ColumnKind::Integer),
+            Column::new("gainers", "漲幅前幾名", ColumnKind::Text),
+            Column::new("losers", "跌幅前幾名", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.sector),
+            record::text(&self.industry),
+            Value::from(self.total),
+            Value::from(self.advances),
+            Value::from(self.declines),
+            Value::from(self.unchanged),
+            record::number(self.avg_change_rate),
+            record::number(self.median_change_rate),
+            Value::from(self.amount),
+            Value::from(self.new_highs),
+            Value::from(self.new_lows),
+            record::text(&format_movers(&self.gainers)),
+            record::text(&format_movers(&self.losers)),
+        ]
+    }
+}
+
+/// 讀取當日資料與前 52 週的最高/最低價，當日沒有資料的股票略過
+fn stock_day(stock_no: &str, date: &str) -> Option<StockDay> {
+    let year_month = common::year_month_of(date)?;
+    let roc_date = common::to_roc_date(date)?;
+    let from = common::shift_year_month(year_month, -HIGH_LOW_MONTHS)?;
+    let daily_data = monthly_data::load_daily_data_range(stock_no, &from, year_month);
+
+    let index = daily_data.iter().position(|d| d.date == roc_date)?;
+    let (new_high, new_low) = indicators::new_high_low(&daily_data, index);
+    Some(StockDay {
+        stock_no: stock_no.to_string(),
+        daily: daily_data[index].clone(),
+        new_high,
+        new_low,
+    })
+}
+
+/// 依產業或類股統計當日表現，依平均漲跌幅由高到低排序，最後加上全市場；
+/// 只統計 company_map 範圍內的股票，與 breadth::series 的本地所有股票不同
+pub fn performance(
+    company_map: &CompanyMap,
+    date: &str,
+    group_by: GroupBy,
+    top: usize,
+) -> Vec<GroupPerformance> {
+    let days: Vec<StockDay> = company_map
+        .stock_map
+        .iter()
+        .filter_map(|company| stock_day(&company.stock_no, date))
+        .collect();
+
+    // (類股, 產業) → 當日資料
+    let mut groups: Vec<((String, String), Vec<&StockDay>)> = Vec::new();
+    for day in &days {
+        let sector = company_map.get_sector(&day.stock_no);
+        let industry = match group_by {
+            GroupBy::Industry => company_map.get_industry(&day.stock_no),
+            GroupBy::Sector => String::new(),
+        };
+        let key = (sector, industry);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(day),
+            None => groups.push((key, vec![day])),
+        }
+    }
+
+    let mut ret: Vec<GroupPerformance> = groups
+        .iter()
+        .map(|((sector, industry), members)| {
+            GroupPerformance::new(company_map, sector, industry, members, top)
+        })
+        .collect();
+    ret.sort_by(|a, b| b.avg_change_rate.total_cmp(&a.avg_change_rate));
+
+    if !days.is_empty() {
+        let all: Vec<&StockDay> = days.iter().collect();
+        ret.push(GroupPerformance::new(company_map, "全市場", "", &all, top));
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twse::company_map::{CompanyInfo, CompanyProfile, Market, SecurityType};
+
+    fn company_map() -> CompanyMap {
+        let company = |stock_no: &str, name: &str| CompanyInfo {
+            stock_no: stock_no.to_string(),
+            name: name.to_string(),
+            industry: "24".to_string(),
+            market: Market::Twse,
+            security_type: SecurityType::Stock,
+            profile: CompanyProfile::default(),
+        };
+        CompanyMap::with_companies(vec![
+            company("2330", "台積電"),
+            company("2303", "聯電"),
+            company("2454", "聯發科"),
+            company("3711", "日月光"),
+            company("6770", "力積電"),
+        ])
+    }
+
+    /// 收盤價 close、漲跌 change 的當日資料
+    fn day(stock_no: &str, close: f64, change: f64) -> StockDay {
+        StockDay {
+            stock_no: stock_no.to_string(),
+            daily: DailyData {
+                date: "115/02/06".to_string(),
+                volume: 1_000,
+                amount: 1_000_000,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                change,
+                transactions: 10,
+                note: String::new(),
+            },
+            new_high: change > 0.0,
+            new_low: false,
+        }
+    }
+
+    fn group(days: &[StockDay], top: usize) -> GroupPerformance {
+        let members: Vec<&StockDay> = days.iter().collect();
+        GroupPerformance::new(&company_map(), "電子", "半導體業", &members, top)
+    }
+
+    #[test]
+    fn odd_count_median_is_middle_rate() {
+        let days = [
+            day("2330", 110.0, 10.0), // +10%
+            day("2303", 95.0, -5.0),  // -5%
+            day("2454", 100.0, 0.0),
+        ];
+        let performance = group(&days, 3);
+        assert!((performance.avg_change_rate - 0.05 / 3.0).abs() < 1e-9);
+        assert_eq!(performance.median_change_rate, 0.0);
+        assert_eq!(
+            (
+                performance.advances,
+                performance.declines,
+                performance.unchanged
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(performance.amount, 3_000_000);
+        assert_eq!(performance.new_highs, 1);
+    }
+
+    #[test]
+    fn even_count_median_averages_middle_rates() {
+        let days = [
+            day("2330", 110.0, 10.0), // +10%
+            day("2303", 95.0, -5.0),  // -5%
+            day("2454", 100.0, 0.0),
+            day("3711", 102.0, 2.0), // +2%
+        ];
+        let performance = group(&days, 3);
+        assert!((performance.avg_change_rate - 0.0175).abs() < 1e-9);
+        assert!((performance.median_change_rate - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skips_rates_without_previous_close() {
+        // 新上市首日：前一日收盤價為 0，不計入漲跌幅但計入檔數
+        let days = [day("2330", 110.0, 10.0), day("6770", 50.0, 50.0)];
+        let performance = group(&days, 3);
+        assert_eq!(performance.total, 2);
+        assert!((performance.avg_change_rate - 0.1).abs() < 1e-9);
+        assert!((performance.median_change_rate - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn movers_are_limited_to_top() {
+        let days = [
+            day("2330", 110.0, 10.0),
+            day("3711", 102.0, 2.0),
+            day("2454", 100.0, 0.0),
+            day("2303", 95.0, -5.0),
+        ];
+        let performance = group(&days, 1);
+        let stock_nos = |movers: &[Mover]| -> Vec<String> {
+            movers.iter().map(|m| m.stock_no.clone()).collect()
+        };
+        assert_eq!(stock_nos(&performance.gainers), ["2330"]);
+        assert_eq!(stock_nos(&performance.losers), ["2303"]);
+        assert_eq!(performance.gainers[0].name, "台積電");
+    }
+
+    #[test]
+    fn empty_group_has_zero_rates() {
+        let performance = group(&[], 3);
+        assert_eq!(performance.total, 0);
+        assert_eq!(performance.avg_change_rate, 0.0);
+        assert_eq!(performance.median_change_rate, 0.0);
+    }
+}
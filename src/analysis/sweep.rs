@@ -0,0 +1,617 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::analysis::bullish_engulfing_pattern;
+use crate::analysis::doji;
+use crate::analysis::long_red_candle;
+use crate::config;
+use crate::data::monthly_data::DailyData;
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::CompanyMap;
+
+/// 十字線波段驗證預設往前看的交易日數。
+/// 選股指令以使用者輸入的月份區間 (可能包含訊號日之後) 計算高低點，
+/// 回測不能看到未來價格，因此改用訊號日往前 swing_lookback_days 個交易日 (含當日) 的收盤價
+pub const SWING_LOOKBACK_DAYS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Doji,
+    LongRedCandle,
+    DojiInSwing,
+    BullishEngulfing,
+}
+
+impl Strategy {
+    pub fn all() -> [Strategy; 4] {
+        [
+            Strategy::Doji,
+            Strategy::LongRedCandle,
+            Strategy::DojiInSwing,
+            Strategy::BullishEngulfing,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Doji => "十字線",
+            Strategy::LongRedCandle => "長紅 K 棒",
+            Strategy::DojiInSwing => "十字線波段驗證",
+            Strategy::BullishEngulfing => "陽吞噬形態",
+        }
+    }
+
+    pub fn param_names(&self) -> &'static [&'static str] {
+        match self {
+            Strategy::Doji => &["doji_tolerance"],
+            Strategy::LongRedCandle => &["long_red_ratio"],
+            Strategy::DojiInSwing => &[
+                "doji_tolerance",
+                "swing_high_ratio",
+                "swing_low_ratio",
+                "swing_lookback_days",
+            ],
+            Strategy::BullishEngulfing => &["engulfing_target"],
+        }
+    }
+
+    pub fn default_params(&self) -> Vec<f64> {
+        let thresholds = &config::get().thresholds;
+        match self {
+            Strategy::Doji => vec![thresholds.doji_tolerance],
+            Strategy::LongRedCandle => vec![thresholds.long_red_ratio],
+            Strategy::DojiInSwing => vec![
+                thresholds.doji_tolerance,
+                thresholds.swing_high_ratio,
+                thresholds.swing_low_ratio,
+                SWING_LOOKBACK_DAYS as f64,
+            ],
+            Strategy::BullishEngulfing => vec![thresholds.engulfing_target_ratio],
+        }
+    }
+
+    fn is_signal(&self, params: &[f64], series: &SweepSeries, index: usize) -> bool {
+        let daily = &series.daily_data[index];
+        match self {
+            Strategy::Doji => doji::is_doji(daily, params[0]),
+            Strategy::LongRedCandle => long_red_candle::is_long_red_candle(daily, params[0]),
+            Strategy::DojiInSwing => {
+                if !doji::is_doji(daily, params[0]) {
+                    return false;
+                }
+                let from = index.saturating_sub(params[3] as usize);
+                let (highest_price, lowest_price) = series.daily_data[from..=index]
+                    .iter()
+                    .fold((0.0_f64, f64::MAX), |(high, low), d| {
+                        (high.max(d.close), low.min(d.close))
+                    });
+                highest_price >= daily.close * params[1] || lowest_price <= daily.close * params[2]
+            }
+            Strategy::BullishEngulfing => {
+                index > 0
+                    && bullish_engulfing_pattern::is_bullish_engulfing(
+                        &series.daily_data[index - 1],
+                        daily,
+                    )
+            }
+        }
+    }
+
+    /// 訊號日收盤進場，持有 horizon 個交易日後的報酬率
+    /// 陽吞噬形態以目標價停利：期間最高價到達目標價即以目標價出場
+    fn outcome(
+        &self,
+        params: &[f64],
+        series: &SweepSeries,
+        index: usize,
+        horizon: usize,
+    ) -> Option<f64> {
+        let entry = series.daily_data[index].close;
+        let exit_index = index + horizon;
+        if exit_index >= series.daily_data.len() || entry == 0.0 {
+            return None;
+        }
+
+        if *self == Strategy::BullishEngulfing {
+            let target = entry * params[0];
+            if series.daily_data[index + 1..=exit_index]
+                .iter()
+                .any(|d| d.high >= target)
+            {
+                return Some(params[0] - 1.0);
+            }
+        }
+
+        Some(series.daily_data[exit_index].close / entry - 1.0)
+    }
+}
+
+/// 參數範圍 (含起訖)
+#[derive(Debug, Clone, Copy)]
+pub struct ParamRange {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+}
+
+impl ParamRange {
+    pub fn single(value: f64) -> Self {
+        ParamRange {
+            start: value,
+            end: value,
+            step: 0.0,
+        }
+    }
+
+    /// 解析 "起始:結束:間隔" 或單一數值
+    pub fn parse(input: &str) -> Option<Self> {
+        let parts: Vec<&str> = input.split(':').map(|s| s.trim()).collect();
+        match parts.as_slice() {
+            [value] => value.parse().ok().map(ParamRange::single),
+            [start, end, step] => {
+                let range = ParamRange {
+                    start: start.parse().ok()?,
+                    end: end.parse().ok()?,
+                    step: step.parse().ok()?,
+                };
+                if range.step <= 0.0 || range.end < range.start {
+                    return None;
+                }
+                Some(range)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        if self.step <= 0.0 {
+            return vec![self.start];
+        }
+        let count = ((self.end - self.start) / self.step + 1e-9).floor() as usize;
+        (0..=count)
+            .map(|i| ((self.start + self.step * i as f64) * 1e6).round() / 1e6)
+            .collect()
+    }
+}
+
+/// 所有參數範圍的笛卡兒積
+pub fn param_grid(ranges: &[ParamRange]) -> Vec<Vec<f64>> {
+    let mut grid = vec![Vec::new()];
+    for range in ranges {
+        let mut next = Vec::new();
+        for params in &grid {
+            for value in range.values() {
+                let mut p = params.clone();
+                p.push(value);
+                next.push(p);
+            }
+        }
+        grid = next;
+    }
+    grid
+}
+
+/// 單一股票的日資料
+pub struct SweepSeries {
+    pub daily_data: Vec<DailyData>,
+}
+
+impl SweepSeries {
+    pub fn new(daily_data: Vec<DailyData>) -> Self {
+        SweepSeries { daily_data }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepResult {
+    pub params: Vec<f64>,
+    pub signals: usize,
+    pub win_rate: f64,
+    pub avg_return: f64,
+    pub median_return: f64,
+}
+
+/// 在 [date_from, date_to] (民國日期，None 表示不限) 內評估一組參數；
+/// 出場日 (訊號後第 horizon 個交易日) 也必須在區間內，避免樣本內用到樣本外的價格
+pub fn evaluate(
+    strategy: Strategy,
+    params: &[f64],
+    all_series: &[SweepSeries],
+    horizon: usize,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+) -> SweepResult {
+    let after_to = |date: &str| date_to.is_some_and(|to| date > to);
+    let mut returns = Vec::new();
+
+    for series in all_series {
+        for (index, daily) in series.daily_data.iter().enumerate() {
+            if date_from.is_some_and(|from| daily.date.as_str() < from) || after_to(&daily.date) {
+                continue;
+            }
+            if series
+                .daily_data
+                .get(index + horizon)
+                .is_none_or(|exit| after_to(&exit.date))
+            {
+                continue;
+            }
+            if !strategy.is_signal(params, series, index) {
+                continue;
+            }
+            if let Some(ret) = strategy.outcome(params, series, index, horizon) {
+                returns.push(ret);
+            }
+        }
+    }
+
+    let signals = returns.len();
+    if signals == 0 {
+        return SweepResult {
+            params: params.to_vec(),
+            signals,
+            win_rate: 0.0,
+            avg_return: 0.0,
+            median_return: 0.0,
+        };
+    }
+
+    returns.sort_by(|a, b| a.total_cmp(b));
+    let wins = returns.iter().filter(|r| **r > 0.0).count();
+
+    SweepResult {
+        params: params.to_vec(),
+        signals,
+        win_rate: wins as f64 / signals as f64,
+        avg_return: returns.iter().sum::<f64>() / signals as f64,
+        median_return: returns[signals / 2],
+    }
+}
+
+/// 依平均報酬排序，訊號數不足 min_signals 的排在最後
+pub fn rank(results: &mut [SweepResult], min_signals: usize) {
+    results.sort_by(|a, b| {
+        (b.signals >= min_signals)
+            .cmp(&(a.signals >= min_signals))
+            .then(b.avg_return.total_cmp(&a.avg_return))
+            .then(b.signals.cmp(&a.signals))
+    });
+}
+
+pub fn sweep(
+    strategy: Strategy,
+    grid: &[Vec<f64>],
+    all_series: &[SweepSeries],
+    horizon: usize,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+    min_signals: usize,
+) -> Vec<SweepResult> {
+    let mut results: Vec<SweepResult> = grid
+        .iter()
+        .map(|params| evaluate(strategy, params, all_series, horizon, date_from, date_to))
+        .collect();
+    rank(&mut results, min_signals);
+    results
+}
+
+#[derive(Serialize)]
+pub struct WalkForwardFold {
+    pub in_sample_from: String,
+    pub in_sample_to: String,
+    pub out_of_sample_from: String,
+    pub out_of_sample_to: String,
+    pub in_sample: SweepResult,
+    pub out_of_sample: SweepResult,
+}
+
+/// 前進式最佳化：將所有交易日切成 folds + 1 段，
+/// 第 i 段做樣本內最佳化，最佳參數在第 i + 1 段做樣本外驗證；
+/// 不分段、交易日不足或沒有參數組合時回傳 None
+pub fn walk_forward(
+    strategy: Strategy,
+    grid: &[Vec<f64>],
+    all_series: &[SweepSeries],
+    horizon: usize,
+    folds: usize,
+    min_signals: usize,
+) -> Option<Vec<WalkForwardFold>> {
+    let mut dates: Vec<&str> = all_series
+        .iter()
+        .flat_map(|s| s.daily_data.iter().map(|d| d.date.as_str()))
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    let segments = folds + 1;
+    if folds == 0 || dates.len() < segments {
+        return None;
+    }
+
+    let segment_len = dates.len() / segments;
+    let segment = |i: usize| {
+        let from = i * segment_len;
+        let to = if i == segments - 1 {
+            dates.len() - 1
+        } else {
+            (i + 1) * segment_len - 1
+        };
+        (dates[from].to_string(), dates[to].to_string())
+    };
+
+    let mut ret = Vec::new();
+    for i in 0..folds {
+        let (is_from, is_to) = segment(i);
+        let (oos_from, oos_to) = segment(i + 1);
+
+        let in_sample = sweep(
+            strategy,
+            grid,
+            all_series,
+            horizon,
+            Some(&is_from),
+            Some(&is_to),
+            min_signals,
+        )
+        .into_iter()
+        .next()?;
+        let out_of_sample = evaluate(
+            strategy,
+            &in_sample.params,
+            all_series,
+            horizon,
+            Some(&oos_from),
+            Some(&oos_to),
+        );
+
+        ret.push(WalkForwardFold {
+            in_sample_from: is_from,
+            in_sample_to: is_to,
+            out_of_sample_from: oos_from,
+            out_of_sample_to: oos_to,
+            in_sample,
+            out_of_sample,
+        });
+    }
+    Some(ret)
+}
+
+/// 輸出用的一列：全期排名或前進式最佳化的一段
+pub struct SweepRow<'a> {
+    pub strategy: Strategy,
+    pub sample: &'static str, // 全期/樣本內/樣本外
+    pub rank: Option<usize>,
+    pub fold: Option<usize>,
+    pub date_from: Option<&'a str>,
+    pub date_to: Option<&'a str>,
+    pub result: &'a SweepResult,
+}
+
+impl<'a> SweepRow<'a> {
+    pub fn ranked(strategy: Strategy, results: &'a [SweepResult]) -> Vec<SweepRow<'a>> {
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| SweepRow {
+                strategy,
+                sample: "全期",
+                rank: Some(i + 1),
+                fold: None,
+                date_from: None,
+                date_to: None,
+                result,
+            })
+            .collect()
+    }
+
+    pub fn folds(strategy: Strategy, folds: &'a [WalkForwardFold]) -> Vec<SweepRow<'a>> {
+        let mut ret = Vec::new();
+        for (i, fold) in folds.iter().enumerate() {
+            ret.push(SweepRow {
+                strategy,
+                sample: "樣本內",
+                rank: None,
+                fold: Some(i + 1),
+                date_from: Some(&fold.in_sample_from),
+                date_to: Some(&fold.in_sample_to),
+                result: &fold.in_sample,
+            });
+            ret.push(SweepRow {
+                strategy,
+                sample: "樣本外",
+                rank: None,
+                fold: Some(i + 1),
+                date_from: Some(&fold.out_of_sample_from),
+                date_to: Some(&fold.out_of_sample_to),
+                result: &fold.out_of_sample,
+            });
+        }
+        ret
+    }
+}
+
+impl Record for SweepRow<'_> {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("sample", "區間", ColumnKind::Text),
+            Column::new("fold", "段", ColumnKind::Integer),
+            Column::new("date_from", "起始日", ColumnKind::Text),
+            Column::new("date_to", "結束日", ColumnKind::Text),
+            Column::new("rank", "排名", ColumnKind::Integer),
+            Column::new("params", "參數", ColumnKind::Text),
+            Column::new("signals", "訊號數", ColumnKind::Integer),
+            Column::new("win_rate", "勝率", ColumnKind::Percent),
+            Column::new("avg_return", "平均報酬", ColumnKind::Percent).colored(),
+            Column::new("median_return", "中位數", ColumnKind::Percent).colored(),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        let params = self
+            .strategy
+            .param_names()
+            .iter()
+            .zip(&self.result.params)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        vec![
+            record::text(self.sample),
+            self.fold.map(Value::from).unwrap_or(Value::Null),
+            self.date_from.map(record::text).unwrap_or(Value::Null),
+            self.date_to.map(record::text).unwrap_or(Value::Null),
+            self.rank.map(Value::from).unwrap_or(Value::Null),
+            record::text(&params),
+            Value::from(self.result.signals),
+            record::number(self.result.win_rate),
+            record::number(self.result.avg_return),
+            record::number(self.result.median_return),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每日開盤價等於收盤價 (十字線)，日期為 115/01/01 起
+    fn series(closes: &[f64]) -> SweepSeries {
+        SweepSeries::new(
+            closes
+                .iter()
+                .enumerate()
+                .map(|(i, close)| DailyData {
+                    date: format!("115/01/{:02}", i + 1),
+                    volume: 1_000,
+                    amount: (close * 1_000.0) as u64,
+                    open: *close,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close: *close,
+                    change: 0.0,
+                    transactions: 10,
+                    note: String::new(),
+                })
+                .collect(),
+        )
+    }
+
+    fn result(signals: usize, avg_return: f64) -> SweepResult {
+        SweepResult {
+            params: vec![avg_return],
+            signals,
+            win_rate: 0.0,
+            avg_return,
+            median_return: 0.0,
+        }
+    }
+
+    #[test]
+    fn params_match_names() {
+        for strategy in Strategy::all() {
+            assert_eq!(
+                strategy.param_names().len(),
+                strategy.default_params().len()
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_summarizes_returns() {
+        let all_series = [series(&[10.0, 11.0, 9.0, 12.0, 12.0])];
+        let result = evaluate(Strategy::Doji, &[0.1], &all_series, 1, None, None);
+
+        // 最後一天沒有出場日
+        assert_eq!(result.signals, 4);
+        assert_eq!(result.win_rate, 0.5);
+        let expected = (0.1 + (9.0 / 11.0 - 1.0) + (12.0 / 9.0 - 1.0) + 0.0) / 4.0;
+        assert!((result.avg_return - expected).abs() < 1e-9);
+        assert!((result.median_return - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn evaluate_requires_exit_inside_range() {
+        let all_series = [series(&[10.0, 11.0, 9.0, 12.0, 12.0])];
+
+        // 01/03 的出場日 01/04 在區間外
+        let result = evaluate(
+            Strategy::Doji,
+            &[0.1],
+            &all_series,
+            1,
+            Some("115/01/01"),
+            Some("115/01/03"),
+        );
+        assert_eq!(result.signals, 2);
+
+        let result = evaluate(
+            Strategy::Doji,
+            &[0.1],
+            &all_series,
+            1,
+            Some("115/01/02"),
+            None,
+        );
+        assert_eq!(result.signals, 3);
+    }
+
+    #[test]
+    fn evaluate_without_signals() {
+        let all_series = [series(&[10.0])];
+        let result = evaluate(Strategy::Doji, &[0.1], &all_series, 1, None, None);
+        assert_eq!(result.signals, 0);
+        assert_eq!(result.avg_return, 0.0);
+    }
+
+    #[test]
+    fn swing_lookback_limits_range() {
+        let series = series(&[20.0, 10.0, 10.0]);
+        let strategy = Strategy::DojiInSwing;
+        assert!(!strategy.is_signal(&[0.1, 1.5, 0.5, 1.0], &series, 2));
+        assert!(strategy.is_signal(&[0.1, 1.5, 0.5, 2.0], &series, 2));
+    }
+
+    #[test]
+    fn rank_puts_enough_signals_first() {
+        let mut results = vec![
+            result(1, 0.5),
+            result(5, 0.1),
+            result(5, 0.2),
+            result(6, 0.2),
+        ];
+        rank(&mut results, 3);
+
+        let order: Vec<(usize, f64)> = results.iter().map(|r| (r.signals, r.avg_return)).collect();
+        assert_eq!(order, [(6, 0.2), (5, 0.2), (5, 0.1), (1, 0.5)]);
+    }
+
+    #[test]
+    fn walk_forward_splits_segments() {
+        let all_series = [series(&[10.0, 11.0, 9.0, 12.0, 12.0, 13.0])];
+        let grid = param_grid(&[ParamRange::parse("0.1:0.2:0.1").unwrap()]);
+
+        let folds = walk_forward(Strategy::Doji, &grid, &all_series, 1, 2, 0).unwrap();
+        let ranges: Vec<_> = folds
+            .iter()
+            .map(|f| {
+                (
+                    f.in_sample_from.as_str(),
+                    f.in_sample_to.as_str(),
+                    f.out_of_sample_from.as_str(),
+                    f.out_of_sample_to.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                ("115/01/01", "115/01/02", "115/01/03", "115/01/04"),
+                ("115/01/03", "115/01/04", "115/01/05", "115/01/06"),
+            ]
+        );
+
+        assert!(walk_forward(Strategy::Doji, &grid, &all_series, 1, 0, 0).is_none());
+        assert!(walk_forward(Strategy::Doji, &grid, &all_series, 1, 6, 0).is_none());
+        assert!(walk_forward(Strategy::Doji, &[], &all_series, 1, 2, 0).is_none());
+    }
+}
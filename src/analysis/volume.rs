@@ -1,28 +1,47 @@
+use serde::Serialize;
+use serde_json::Value;
+
 use crate::common;
 use crate::data::monthly_data::{DailyData, MonthlyData};
+use crate::output::record::{self, Column, Record};
 use crate::twse::company_map::CompanyMap;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct VolumeAnalysisResult {
     pub stock_no: String,
     pub daily_data: DailyData,
 }
 
+impl Record for VolumeAnalysisResult {
+    fn columns() -> Vec<Column> {
+        record::daily_columns()
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        record::daily_values(company_map, &self.stock_no, &self.daily_data)
+    }
+}
+
 pub async fn volume_larger_than_threshold(
     company_map: &CompanyMap,
     threshold: u64,
     year_month_date: &str,
 ) -> Vec<VolumeAnalysisResult> {
-    let year_month = &year_month_date[0..6];
     let mut daily_data_all = Vec::new();
+    let (Some(year_month), Some(roc_date)) = (
+        common::year_month_of(year_month_date),
+        common::to_roc_date(year_month_date),
+    ) else {
+        return daily_data_all;
+    };
 
     for company in &company_map.stock_map {
-        let monthly_data = MonthlyData::new(company_map, &company.stock_no, year_month).await;
+        let monthly_data =
+            MonthlyData::new_or_empty(company_map, &company.stock_no, year_month).await;
 
         for daily in &monthly_data.daily_data {
             // println!("Checking {} on date {} with {}", stock_no, daily.date, common::to_roc_date(year_month_date));
-            if daily.date == common::to_roc_date(year_month_date) && daily.volume > threshold * 1000
-            {
+            if daily.date == roc_date && daily.volume > threshold * 1000 {
                 daily_data_all.push(VolumeAnalysisResult {
                     stock_no: company.stock_no.clone(),
                     daily_data: daily.clone(),
@@ -32,7 +51,7 @@ pub async fn volume_larger_than_threshold(
     }
 
     // sort by volume descending
-    daily_data_all.sort_by(|a, b| b.daily_data.volume.cmp(&a.daily_data.volume));
+    daily_data_all.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
 
     // println!(
     //     "{:<8}{:<5}{:>6}{:>5}{:>5}{:>5}{:>5}{:>6} {}",
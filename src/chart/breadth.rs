@@ -0,0 +1,156 @@
+use crate::analysis::breadth::{BreadthPoint, MA_PERIODS};
+use crate::chart::style::{
+    DOWN_COLOR, FLAT_COLOR, MARGIN_BOTTOM, MARGIN_LEFT, MARGIN_RIGHT, MARGIN_TOP, OVERLAY_COLORS,
+    PANEL_GAP, Scale, TEXT_COLOR, UP_COLOR, draw_grid,
+};
+use crate::chart::svg::Svg;
+
+/// 市場寬度圖：騰落線、McClellan 震盪指標、站上均線比例、52 週新高/新低家數四個面板
+pub fn render_svg(title: &str, points: &[BreadthPoint], width: u32, height: u32) -> String {
+    let width = width as f64;
+    let height = height as f64;
+    let mut svg = Svg::new(width, height);
+
+    svg.text(MARGIN_LEFT, 24.0, title, 16.0, TEXT_COLOR, "start");
+    if points.is_empty() {
+        svg.text(
+            width / 2.0,
+            height / 2.0,
+            "沒有資料",
+            16.0,
+            TEXT_COLOR,
+            "middle",
+        );
+        return svg.finish();
+    }
+
+    let plot_width = width - MARGIN_LEFT - MARGIN_RIGHT;
+    let panel_height = (height - MARGIN_TOP - MARGIN_BOTTOM) / 4.0 - PANEL_GAP;
+    let panel_top = |n: usize| MARGIN_TOP + n as f64 * (panel_height + PANEL_GAP);
+    let slot = plot_width / points.len() as f64;
+    let x = |i: usize| MARGIN_LEFT + i as f64 * slot + slot / 2.0;
+    let bar_width = (slot * 0.7).max(1.0);
+    let label = |svg: &mut Svg, top: f64, text: &str| {
+        svg.text(MARGIN_LEFT, top + 10.0, text, 10.0, TEXT_COLOR, "start");
+    };
+
+    // 騰落線
+    let ad_line: Vec<f64> = points.iter().map(|p| p.ad_line as f64).collect();
+    let scale = Scale::new(
+        panel_top(0),
+        panel_height,
+        ad_line.iter().copied().fold(f64::MAX, f64::min),
+        ad_line.iter().copied().fold(f64::MIN, f64::max),
+    );
+    draw_grid(&mut svg, &scale, plot_width, 2, 0);
+    let line: Vec<_> = ad_line
+        .iter()
+        .enumerate()
+        .map(|(i, v)| Some((x(i), scale.y(*v))))
+        .collect();
+    svg.polyline(&line, OVERLAY_COLORS[1], false);
+    label(&mut svg, panel_top(0), "騰落線 (上漲 - 下跌 累計)");
+
+    // McClellan
+    let bound = points
+        .iter()
+        .fold(0.0_f64, |acc, p| acc.max(p.mcclellan.abs()))
+        .max(1.0);
+    let scale = Scale::new(panel_top(1), panel_height, -bound, bound);
+    draw_grid(&mut svg, &scale, plot_width, 2, 0);
+    let zero = scale.y(0.0);
+    for (i, point) in points.iter().enumerate() {
+        let y = scale.y(point.mcclellan);
+        let color = if point.mcclellan >= 0.0 {
+            UP_COLOR
+        } else {
+            DOWN_COLOR
+        };
+        svg.rect(
+            x(i) - bar_width / 2.0,
+            y.min(zero),
+            bar_width,
+            (zero - y).abs(),
+            color,
+        );
+    }
+    label(&mut svg, panel_top(1), "McClellan 震盪指標");
+
+    // 站上均線比例
+    let scale = Scale::new(panel_top(2), panel_height, 0.0, 100.0);
+    draw_grid(&mut svg, &scale, plot_width, 2, 0);
+    svg.line(
+        (MARGIN_LEFT, scale.y(50.0)),
+        (MARGIN_LEFT + plot_width, scale.y(50.0)),
+        FLAT_COLOR,
+        true,
+    );
+    let mut names = Vec::new();
+    for (k, n) in MA_PERIODS.iter().enumerate() {
+        let line: Vec<_> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| Some((x(i), scale.y(p.above_ma[k] * 100.0))))
+            .collect();
+        svg.polyline(&line, OVERLAY_COLORS[k], false);
+        names.push(format!("MA{n}"));
+    }
+    label(
+        &mut svg,
+        panel_top(2),
+        &format!("站上均線比例 (%) {}", names.join("/")),
+    );
+
+    // 52 週新高 (往上) / 新低 (往下)
+    let bound = points
+        .iter()
+        .fold(0.0_f64, |acc, p| {
+            acc.max(p.new_highs.max(p.new_lows) as f64)
+        })
+        .max(1.0);
+    let scale = Scale::new(panel_top(3), panel_height, -bound, bound);
+    draw_grid(&mut svg, &scale, plot_width, 2, 0);
+    let zero = scale.y(0.0);
+    for (i, point) in points.iter().enumerate() {
+        let high = scale.y(point.new_highs as f64);
+        let low = scale.y(-(point.new_lows as f64));
+        svg.rect(
+            x(i) - bar_width / 2.0,
+            high,
+            bar_width,
+            zero - high,
+            UP_COLOR,
+        );
+        svg.rect(
+            x(i) - bar_width / 2.0,
+            zero,
+            bar_width,
+            low - zero,
+            DOWN_COLOR,
+        );
+    }
+    label(&mut svg, panel_top(3), "52 週新高 / 新低家數");
+
+    // 日期刻度，頭尾對齊邊界避免被裁掉
+    let ticks = 8.min(points.len());
+    for t in 0..ticks {
+        let i = t * (points.len() - 1) / (ticks - 1).max(1);
+        let anchor = if t == 0 {
+            "start"
+        } else if t == ticks - 1 {
+            "end"
+        } else {
+            "middle"
+        };
+        svg.text(
+            x(i),
+            height - MARGIN_BOTTOM + 16.0,
+            &points[i].date,
+            10.0,
+            TEXT_COLOR,
+            anchor,
+        );
+    }
+
+    svg.finish()
+}
@@ -0,0 +1,510 @@
+use crate::analysis::bullish_engulfing_pattern;
+use crate::analysis::doji;
+use crate::analysis::indicators::{self, CrossType};
+use crate::chart::style::{
+    DOWN_COLOR, FLAT_COLOR, MARGIN_BOTTOM, MARGIN_LEFT, MARGIN_RIGHT, MARGIN_TOP, OVERLAY_COLORS,
+    PANEL_GAP, Scale, TEXT_COLOR, UP_COLOR, draw_grid,
+};
+use crate::chart::svg::Svg;
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::{self, DailyData};
+use crate::twse::company_map::CompanyMap;
+
+/// 往前多載入的月份數，讓 MA60 等指標在圖表起點就有值
+const CHART_WARMUP_MONTHS: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overlay {
+    Ma(usize),
+    Bollinger { n: usize, k: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subpanel {
+    Macd,
+    Kd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalKind {
+    Doji,
+    BullishEngulfing,
+    MacdGoldenCross,
+    MacdDeathCross,
+    KdGoldenCross,
+    KdDeathCross,
+}
+
+impl SignalKind {
+    pub fn all() -> [SignalKind; 6] {
+        [
+            SignalKind::Doji,
+            SignalKind::BullishEngulfing,
+            SignalKind::MacdGoldenCross,
+            SignalKind::MacdDeathCross,
+            SignalKind::KdGoldenCross,
+            SignalKind::KdDeathCross,
+        ]
+    }
+
+    /// 圖上標記用的短標籤
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignalKind::Doji => "D",
+            SignalKind::BullishEngulfing => "E",
+            SignalKind::MacdGoldenCross => "M+",
+            SignalKind::MacdDeathCross => "M-",
+            SignalKind::KdGoldenCross => "K+",
+            SignalKind::KdDeathCross => "K-",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignalKind::Doji => "十字線",
+            SignalKind::BullishEngulfing => "陽吞噬",
+            SignalKind::MacdGoldenCross => "MACD 黃金交叉",
+            SignalKind::MacdDeathCross => "MACD 死亡交叉",
+            SignalKind::KdGoldenCross => "KD 黃金交叉",
+            SignalKind::KdDeathCross => "KD 死亡交叉",
+        }
+    }
+
+    /// 偏多的訊號標在 K 棒下方，其餘標在上方
+    fn is_bullish(&self) -> bool {
+        matches!(
+            self,
+            SignalKind::BullishEngulfing | SignalKind::MacdGoldenCross | SignalKind::KdGoldenCross
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Signal {
+    pub index: usize,
+    pub kind: SignalKind,
+}
+
+pub struct ChartOptions {
+    pub overlays: Vec<Overlay>,
+    pub subpanel: Option<Subpanel>,
+    pub signals: bool,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            overlays: vec![Overlay::Ma(5), Overlay::Ma(20), Overlay::Ma(60)],
+            subpanel: Some(Subpanel::Macd),
+            signals: true,
+            width: 1200,
+            height: 800,
+        }
+    }
+}
+
+/// 載入 [year_month_from, year_month_to] 的日資料 (含暖機月份)，回傳資料與圖表起點
+pub async fn load(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month_from: &str,
+    year_month_to: &str,
+) -> (Vec<DailyData>, usize) {
+    let (Some(warmup_from), Some(first_date)) = (
+        common::shift_year_month(year_month_from, -CHART_WARMUP_MONTHS),
+        common::to_roc_date(&format!("{year_month_from}01")),
+    ) else {
+        return (Vec::new(), 0);
+    };
+    let daily_data =
+        monthly_data::fetch_daily_data_range(company_map, stock_no, &warmup_from, year_month_to)
+            .await;
+
+    let start = daily_data
+        .iter()
+        .position(|d| d.date >= first_date)
+        .unwrap_or(daily_data.len());
+    (daily_data, start)
+}
+
+pub fn detect_signals(daily_data: &[DailyData]) -> Vec<Signal> {
+    let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+    let macd = indicators::macd(&closes);
+    let kd = indicators::kd(daily_data, 9);
+
+    let mut signals = Vec::new();
+    for (i, daily) in daily_data.iter().enumerate() {
+        if doji::is_doji(daily, config::get().thresholds.doji_tolerance) {
+            signals.push(Signal {
+                index: i,
+                kind: SignalKind::Doji,
+            });
+        }
+        if i == 0 {
+            continue;
+        }
+        if bullish_engulfing_pattern::is_bullish_engulfing(&daily_data[i - 1], daily) {
+            signals.push(Signal {
+                index: i,
+                kind: SignalKind::BullishEngulfing,
+            });
+        }
+        match indicators::cross(
+            macd[i - 1].dif,
+            macd[i - 1].signal,
+            macd[i].dif,
+            macd[i].signal,
+        ) {
+            Some(CrossType::GoldenCross) => signals.push(Signal {
+                index: i,
+                kind: SignalKind::MacdGoldenCross,
+            }),
+            Some(CrossType::DeathCross) => signals.push(Signal {
+                index: i,
+                kind: SignalKind::MacdDeathCross,
+            }),
+            None => {}
+        }
+        match indicators::cross(kd[i - 1].k, kd[i - 1].d, kd[i].k, kd[i].d) {
+            Some(CrossType::GoldenCross) => signals.push(Signal {
+                index: i,
+                kind: SignalKind::KdGoldenCross,
+            }),
+            Some(CrossType::DeathCross) => signals.push(Signal {
+                index: i,
+                kind: SignalKind::KdDeathCross,
+            }),
+            None => {}
+        }
+    }
+    signals
+}
+
+/// 布林通道 (中線, 上軌, 下軌)，標準差用母體標準差
+fn bollinger(closes: &[f64], n: usize, k: f64) -> Vec<Option<(f64, f64, f64)>> {
+    indicators::sma(closes, n)
+        .into_iter()
+        .enumerate()
+        .map(|(i, mid)| {
+            let mid = mid?;
+            let variance = closes[i + 1 - n..=i]
+                .iter()
+                .map(|c| (c - mid).powi(2))
+                .sum::<f64>()
+                / n as f64;
+            let band = variance.sqrt() * k;
+            Some((mid, mid + band, mid - band))
+        })
+        .collect()
+}
+
+fn candle_color(daily: &DailyData) -> &'static str {
+    if daily.close > daily.open {
+        UP_COLOR
+    } else if daily.close < daily.open {
+        DOWN_COLOR
+    } else {
+        FLAT_COLOR
+    }
+}
+
+/// K 線圖：價格面板 (K 棒、均線/布林通道、訊號標記)、成交量面板與 MACD/KD 面板
+pub fn render_svg(
+    title: &str,
+    daily_data: &[DailyData],
+    start: usize,
+    options: &ChartOptions,
+) -> String {
+    let width = options.width as f64;
+    let height = options.height as f64;
+    let mut svg = Svg::new(width, height);
+
+    let visible = &daily_data[start.min(daily_data.len())..];
+    svg.text(MARGIN_LEFT, 24.0, title, 16.0, TEXT_COLOR, "start");
+    if visible.is_empty() {
+        svg.text(
+            width / 2.0,
+            height / 2.0,
+            "沒有資料",
+            16.0,
+            TEXT_COLOR,
+            "middle",
+        );
+        return svg.finish();
+    }
+
+    let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+    let plot_width = width - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = height - MARGIN_TOP - MARGIN_BOTTOM;
+    let (price_ratio, volume_ratio) = if options.subpanel.is_some() {
+        (0.58, 0.17)
+    } else {
+        (0.75, 0.25)
+    };
+    let price_height = plot_height * price_ratio - PANEL_GAP;
+    let volume_height = plot_height * volume_ratio - PANEL_GAP;
+    let sub_height = plot_height - price_height - volume_height - PANEL_GAP * 2.0;
+
+    let slot = plot_width / visible.len() as f64;
+    let x = |i: usize| MARGIN_LEFT + (i - start) as f64 * slot + slot / 2.0;
+    let body_width = (slot * 0.7).max(1.0);
+
+    // 均線與布林通道
+    let mut overlay_series: Vec<(String, Vec<Option<f64>>, bool)> = Vec::new();
+    for overlay in &options.overlays {
+        match overlay {
+            Overlay::Ma(n) => {
+                overlay_series.push((format!("MA{n}"), indicators::sma(&closes, *n), false))
+            }
+            Overlay::Bollinger { n, k } => {
+                let bands = bollinger(&closes, *n, *k);
+                overlay_series.push((
+                    format!("BB{n}"),
+                    bands.iter().map(|b| b.map(|b| b.0)).collect(),
+                    true,
+                ));
+                overlay_series.push((
+                    format!("BB{n}+{k}σ"),
+                    bands.iter().map(|b| b.map(|b| b.1)).collect(),
+                    false,
+                ));
+                overlay_series.push((
+                    format!("BB{n}-{k}σ"),
+                    bands.iter().map(|b| b.map(|b| b.2)).collect(),
+                    false,
+                ));
+            }
+        }
+    }
+
+    // 價格面板
+    let mut low = visible.iter().map(|d| d.low).fold(f64::MAX, f64::min);
+    let mut high = visible.iter().map(|d| d.high).fold(f64::MIN, f64::max);
+    for (_, series, _) in &overlay_series {
+        for value in series[start..].iter().flatten() {
+            low = low.min(*value);
+            high = high.max(*value);
+        }
+    }
+    let padding = (high - low) * 0.08;
+    let price = Scale::new(MARGIN_TOP, price_height, low - padding, high + padding);
+    draw_grid(&mut svg, &price, plot_width, 5, 2);
+
+    for (i, daily) in visible.iter().enumerate() {
+        let i = i + start;
+        let color = candle_color(daily);
+        svg.line(
+            (x(i), price.y(daily.high)),
+            (x(i), price.y(daily.low)),
+            color,
+            false,
+        );
+        let top = price.y(daily.open.max(daily.close));
+        let bottom = price.y(daily.open.min(daily.close));
+        svg.rect(
+            x(i) - body_width / 2.0,
+            top,
+            body_width,
+            (bottom - top).max(1.0),
+            color,
+        );
+    }
+
+    let mut legend_x = MARGIN_LEFT;
+    let legend_y = MARGIN_TOP - 6.0;
+    for (n, (name, series, dashed)) in overlay_series.iter().enumerate() {
+        let color = OVERLAY_COLORS[n % OVERLAY_COLORS.len()];
+        let points: Vec<Option<(f64, f64)>> = (start..daily_data.len())
+            .map(|i| series[i].map(|value| (x(i), price.y(value))))
+            .collect();
+        svg.polyline(&points, color, *dashed);
+        svg.text(legend_x, legend_y, name, 11.0, color, "start");
+        legend_x += 12.0 + name.len() as f64 * 7.0;
+    }
+
+    // 訊號標記
+    if options.signals {
+        let signals = detect_signals(daily_data);
+        let mut stacked: Vec<(usize, bool, usize)> = Vec::new(); // (index, 下方, 已標數量)
+        for signal in signals.iter().filter(|s| s.index >= start) {
+            let bullish = signal.kind.is_bullish();
+            let level = match stacked
+                .iter_mut()
+                .find(|(index, below, _)| *index == signal.index && *below == bullish)
+            {
+                Some(entry) => {
+                    entry.2 += 1;
+                    entry.2
+                }
+                None => {
+                    stacked.push((signal.index, bullish, 0));
+                    0
+                }
+            };
+
+            let daily = &daily_data[signal.index];
+            let cx = x(signal.index);
+            let offset = 10.0 + level as f64 * 12.0;
+            if bullish {
+                let y = price.y(daily.low) + offset;
+                svg.polygon(&[(cx, y - 6.0), (cx - 4.0, y), (cx + 4.0, y)], UP_COLOR);
+                svg.text(cx, y + 9.0, signal.kind.label(), 9.0, UP_COLOR, "middle");
+            } else {
+                let y = price.y(daily.high) - offset;
+                let color = if signal.kind == SignalKind::Doji {
+                    TEXT_COLOR
+                } else {
+                    DOWN_COLOR
+                };
+                svg.polygon(&[(cx, y + 6.0), (cx - 4.0, y), (cx + 4.0, y)], color);
+                svg.text(cx, y - 3.0, signal.kind.label(), 9.0, color, "middle");
+            }
+        }
+
+        let legend: Vec<String> = SignalKind::all()
+            .iter()
+            .map(|kind| format!("{} {}", kind.label(), kind.name()))
+            .collect();
+        svg.text(
+            width - MARGIN_RIGHT,
+            legend_y,
+            &legend.join("  "),
+            10.0,
+            TEXT_COLOR,
+            "end",
+        );
+    }
+
+    // 成交量面板 (張)
+    let volume_top = price.bottom() + PANEL_GAP;
+    let max_volume = visible
+        .iter()
+        .map(|d| common::divide_by_1000(d.volume))
+        .max()
+        .unwrap_or(0) as f64;
+    let volume = Scale::new(volume_top, volume_height, 0.0, max_volume.max(1.0));
+    draw_grid(&mut svg, &volume, plot_width, 2, 0);
+    for (i, daily) in visible.iter().enumerate() {
+        let i = i + start;
+        let top = volume.y(common::divide_by_1000(daily.volume) as f64);
+        svg.rect(
+            x(i) - body_width / 2.0,
+            top,
+            body_width,
+            volume.bottom() - top,
+            candle_color(daily),
+        );
+    }
+    svg.text(
+        MARGIN_LEFT,
+        volume_top + 10.0,
+        "成交量 (張)",
+        10.0,
+        TEXT_COLOR,
+        "start",
+    );
+
+    // MACD/KD 面板
+    let sub_top = volume.bottom() + PANEL_GAP;
+    match options.subpanel {
+        Some(Subpanel::Macd) => {
+            let macd = indicators::macd(&closes);
+            let values = macd[start..]
+                .iter()
+                .flat_map(|m| [m.dif, m.signal, m.dif - m.signal]);
+            let bound = values.fold(0.0_f64, |acc, v| acc.max(v.abs())).max(0.01);
+            let scale = Scale::new(sub_top, sub_height, -bound, bound);
+            draw_grid(&mut svg, &scale, plot_width, 2, 2);
+
+            for (i, m) in macd.iter().enumerate().skip(start) {
+                let histogram = m.dif - m.signal;
+                let zero = scale.y(0.0);
+                let y = scale.y(histogram);
+                let color = if histogram >= 0.0 {
+                    UP_COLOR
+                } else {
+                    DOWN_COLOR
+                };
+                svg.rect(
+                    x(i) - body_width / 2.0,
+                    y.min(zero),
+                    body_width,
+                    (zero - y).abs(),
+                    color,
+                );
+            }
+            let dif: Vec<_> = (start..macd.len())
+                .map(|i| Some((x(i), scale.y(macd[i].dif))))
+                .collect();
+            let signal: Vec<_> = (start..macd.len())
+                .map(|i| Some((x(i), scale.y(macd[i].signal))))
+                .collect();
+            svg.polyline(&dif, OVERLAY_COLORS[0], false);
+            svg.polyline(&signal, OVERLAY_COLORS[1], false);
+            svg.text(
+                MARGIN_LEFT,
+                sub_top + 10.0,
+                "MACD(12,26,9) DIF/訊號",
+                10.0,
+                TEXT_COLOR,
+                "start",
+            );
+        }
+        Some(Subpanel::Kd) => {
+            let kd = indicators::kd(daily_data, 9);
+            let scale = Scale::new(sub_top, sub_height, 0.0, 100.0);
+            draw_grid(&mut svg, &scale, plot_width, 2, 0);
+            for level in [20.0, 80.0] {
+                svg.line(
+                    (MARGIN_LEFT, scale.y(level)),
+                    (MARGIN_LEFT + plot_width, scale.y(level)),
+                    FLAT_COLOR,
+                    true,
+                );
+            }
+            let k: Vec<_> = (start..kd.len())
+                .map(|i| Some((x(i), scale.y(kd[i].k))))
+                .collect();
+            let d: Vec<_> = (start..kd.len())
+                .map(|i| Some((x(i), scale.y(kd[i].d))))
+                .collect();
+            svg.polyline(&k, OVERLAY_COLORS[0], false);
+            svg.polyline(&d, OVERLAY_COLORS[1], false);
+            svg.text(
+                MARGIN_LEFT,
+                sub_top + 10.0,
+                "KD(9) K/D",
+                10.0,
+                TEXT_COLOR,
+                "start",
+            );
+        }
+        None => {}
+    }
+
+    // 日期刻度，頭尾對齊邊界避免被裁掉
+    let ticks = 8.min(visible.len());
+    for t in 0..ticks {
+        let i = start + t * (visible.len() - 1) / (ticks - 1).max(1);
+        let anchor = if t == 0 {
+            "start"
+        } else if t == ticks - 1 {
+            "end"
+        } else {
+            "middle"
+        };
+        svg.text(
+            x(i),
+            height - MARGIN_BOTTOM + 16.0,
+            &daily_data[i].date,
+            10.0,
+            TEXT_COLOR,
+            anchor,
+        );
+    }
+
+    svg.finish()
+}
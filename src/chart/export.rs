@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use resvg::{tiny_skia, usvg};
+
+use crate::config;
+
+pub const CHART_DIR: &str = "charts";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ChartFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ChartFormat::Svg => "svg",
+            ChartFormat::Png => "png",
+        }
+    }
+}
+
+/// 預設輸出路徑: charts/<股號>_<起始月份>_<結束月份>.<副檔名>
+pub fn default_path(
+    stock_no: &str,
+    year_month_from: &str,
+    year_month_to: &str,
+    format: ChartFormat,
+) -> PathBuf {
+    config::path(CHART_DIR).join(format!(
+        "{stock_no}_{year_month_from}_{year_month_to}.{}",
+        format.extension()
+    ))
+}
+
+/// 寫出 SVG，或用系統字型轉成 PNG
+pub fn write(svg: &str, format: ChartFormat, path: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match format {
+        ChartFormat::Svg => fs::write(path, svg)?,
+        ChartFormat::Png => {
+            let mut options = usvg::Options::default();
+            options.fontdb_mut().load_system_fonts();
+            let tree = usvg::Tree::from_str(svg, &options)?;
+
+            let size = tree.size().to_int_size();
+            let mut pixmap =
+                tiny_skia::Pixmap::new(size.width(), size.height()).ok_or("無法建立圖片")?;
+            resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+            pixmap.save_png(path)?;
+        }
+    }
+    Ok(())
+}
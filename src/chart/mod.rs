@@ -0,0 +1,6 @@
+pub mod breadth;
+pub mod candlestick;
+pub mod export;
+pub mod style;
+pub mod svg;
+pub mod terminal;
@@ -0,0 +1,70 @@
+use crate::chart::svg::Svg;
+
+// K 線圖與市場寬度圖共用的配色與邊界
+pub const UP_COLOR: &str = "#d9342b"; // 紅漲
+pub const DOWN_COLOR: &str = "#2b9e4b"; // 綠跌
+pub const FLAT_COLOR: &str = "#666666";
+pub const GRID_COLOR: &str = "#e6e6e6";
+pub const TEXT_COLOR: &str = "#333333";
+pub const OVERLAY_COLORS: [&str; 5] = ["#f08c00", "#1c7ed6", "#ae3ec9", "#0ca678", "#495057"];
+
+pub const MARGIN_LEFT: f64 = 10.0;
+pub const MARGIN_RIGHT: f64 = 70.0;
+pub const MARGIN_TOP: f64 = 40.0;
+pub const MARGIN_BOTTOM: f64 = 30.0;
+pub const PANEL_GAP: f64 = 12.0;
+
+/// 將數值映射到面板的 y 座標
+#[derive(Clone, Copy)]
+pub struct Scale {
+    top: f64,
+    height: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Scale {
+    pub fn new(top: f64, height: f64, min: f64, max: f64) -> Self {
+        let (min, max) = if max > min {
+            (min, max)
+        } else {
+            (min - 1.0, max + 1.0)
+        };
+        Scale {
+            top,
+            height,
+            min,
+            max,
+        }
+    }
+
+    pub fn y(&self, value: f64) -> f64 {
+        self.top + (self.max - value) / (self.max - self.min) * self.height
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+}
+
+/// 水平格線與右側刻度
+pub fn draw_grid(svg: &mut Svg, scale: &Scale, plot_width: f64, lines: usize, decimals: usize) {
+    for n in 0..=lines {
+        let value = scale.min + (scale.max - scale.min) * n as f64 / lines as f64;
+        let y = scale.y(value);
+        svg.line(
+            (MARGIN_LEFT, y),
+            (MARGIN_LEFT + plot_width, y),
+            GRID_COLOR,
+            false,
+        );
+        svg.text(
+            MARGIN_LEFT + plot_width + 6.0,
+            y + 4.0,
+            &format!("{value:.decimals$}"),
+            10.0,
+            TEXT_COLOR,
+            "start",
+        );
+    }
+}
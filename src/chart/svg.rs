@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+/// 圖表用的簡易 SVG 產生器
+pub struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl Svg {
+    pub fn new(width: f64, height: f64) -> Self {
+        Svg {
+            width,
+            height,
+            body: String::new(),
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{x:.1}" y="{y:.1}" width="{width:.1}" height="{height:.1}" fill="{fill}"/>"#
+        );
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), stroke: &str, dashed: bool) {
+        let dash = if dashed {
+            r#" stroke-dasharray="4 3""#
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{stroke}" stroke-width="1"{dash}/>"#,
+            from.0, from.1, to.0, to.1
+        );
+    }
+
+    /// 連續的折線，None 的位置斷開
+    pub fn polyline(&mut self, points: &[Option<(f64, f64)>], stroke: &str, dashed: bool) {
+        let dash = if dashed {
+            r#" stroke-dasharray="4 3""#
+        } else {
+            ""
+        };
+        for segment in points.split(|p| p.is_none()) {
+            if segment.len() < 2 {
+                continue;
+            }
+            let path: Vec<String> = segment
+                .iter()
+                .flatten()
+                .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                .collect();
+            let _ = writeln!(
+                self.body,
+                r#"<polyline points="{}" fill="none" stroke="{stroke}" stroke-width="1.2"{dash}/>"#,
+                path.join(" ")
+            );
+        }
+    }
+
+    pub fn polygon(&mut self, points: &[(f64, f64)], fill: &str) {
+        let path: Vec<String> = points
+            .iter()
+            .map(|(x, y)| format!("{x:.1},{y:.1}"))
+            .collect();
+        let _ = writeln!(
+            self.body,
+            r#"<polygon points="{}" fill="{fill}"/>"#,
+            path.join(" ")
+        );
+    }
+
+    /// anchor: start/middle/end
+    pub fn text(&mut self, x: f64, y: f64, text: &str, size: f64, fill: &str, anchor: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{x:.1}" y="{y:.1}" font-size="{size}" fill="{fill}" text-anchor="{anchor}">{}</text>"#,
+            escape(text)
+        );
+    }
+
+    pub fn finish(self) -> String {
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" "#,
+                r#"font-family="Noto Sans CJK TC, PingFang TC, Microsoft JhengHei, DejaVu Sans, sans-serif">"#,
+                "\n",
+                r#"<rect width="100%" height="100%" fill="white"/>"#,
+                "\n{body}</svg>\n"
+            ),
+            w = self.width,
+            h = self.height,
+            body = self.body
+        )
+    }
+}
+
+/// XML/HTML 文字跳脫
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use chrono::Datelike;
+
+use crate::common;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::table::{self, Color};
+
+/// 由低到高的 8 階方塊字
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// 價格刻度的寬度
+const LABEL_WIDTH: usize = 9;
+
+/// 迷你走勢圖，每個值一個字元；全部相同時畫在中間
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::MAX, f64::min);
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if max > min {
+                let level = (value - min) / (max - min) * (SPARK_LEVELS.len() - 1) as f64;
+                SPARK_LEVELS[level.round() as usize]
+            } else {
+                SPARK_LEVELS[SPARK_LEVELS.len() / 2]
+            }
+        })
+        .collect()
+}
+
+/// 本地資料中最近 days 個交易日 (依日期排序)
+pub fn recent_daily_data(stock_no: &str, days: usize) -> Vec<DailyData> {
+    let Some(date) = monthly_data::latest_stored_daily_data(stock_no)
+        .and_then(|daily| common::roc_date_to_naive(&daily.date))
+    else {
+        return Vec::new();
+    };
+
+    // 每月約 20 個交易日，多抓一個月避免月初資料不足
+    let year_month_to = format!("{:04}{:02}", date.year(), date.month());
+    let months = (days / 20 + 1) as i32;
+    let Some(year_month_from) = common::shift_year_month(&year_month_to, -months) else {
+        return Vec::new();
+    };
+    let mut daily_data =
+        monthly_data::load_daily_data_range(stock_no, &year_month_from, &year_month_to);
+    daily_data.drain(..daily_data.len().saturating_sub(days));
+    daily_data
+}
+
+fn candle_color(daily: &DailyData) -> Option<Color> {
+    Color::from_sign(daily.close - daily.open)
+}
+
+/// 一格代表的價格區間內，K 棒要畫的字元；每格再分上下兩半提高解析度
+fn candle_char(daily: &DailyData, row_low: f64, row_high: f64) -> char {
+    let row_mid = (row_low + row_high) / 2.0;
+    let body_high = daily.open.max(daily.close);
+    let body_low = daily.open.min(daily.close);
+
+    if body_high >= row_low && body_low <= row_high {
+        let top = body_high >= row_mid;
+        let bottom = body_low <= row_mid;
+        return match (top, bottom) {
+            (true, false) => '▀',
+            (false, true) => '▄',
+            _ => '█',
+        };
+    }
+    if daily.high >= row_low && daily.low <= row_high {
+        let top = daily.high >= row_mid;
+        let bottom = daily.low <= row_mid;
+        return match (top, bottom) {
+            (true, false) => '╵',
+            (false, true) => '╷',
+            _ => '│',
+        };
+    }
+    ' '
+}
+
+/// 終端機 K 線圖：每個交易日一欄，上方為 K 棒、下方為成交量，最底列是起訖日期
+pub fn render_candles(daily_data: &[DailyData], height: usize, colored: bool) -> String {
+    let mut ret = String::new();
+    if daily_data.is_empty() {
+        return ret;
+    }
+    let height = height.max(2);
+
+    let low = daily_data.iter().map(|d| d.low).fold(f64::MAX, f64::min);
+    let high = daily_data.iter().map(|d| d.high).fold(f64::MIN, f64::max);
+    let step = if high > low {
+        (high - low) / height as f64
+    } else {
+        1.0
+    };
+
+    for row in 0..height {
+        let row_high = high - step * row as f64;
+        let row_low = row_high - step;
+        let label = if row == 0 || row == height - 1 || row == height / 2 {
+            format!("{:.2}", (row_high + row_low) / 2.0)
+        } else {
+            String::new()
+        };
+
+        let mut line = String::new();
+        for daily in daily_data {
+            let c = candle_char(daily, row_low, row_high).to_string();
+            line.push_str(&table::paint(&c, candle_color(daily), colored));
+        }
+        let _ = writeln!(
+            ret,
+            "{} {line}",
+            table::pad(&label, LABEL_WIDTH, table::Align::Right)
+        );
+    }
+
+    let volumes: Vec<f64> = daily_data.iter().map(|d| d.volume as f64).collect();
+    let volume_line: String = sparkline(&volumes)
+        .chars()
+        .zip(daily_data)
+        .map(|(c, daily)| table::paint(&c.to_string(), candle_color(daily), colored))
+        .collect();
+    let max_volume = daily_data.iter().map(|d| d.volume).max().unwrap_or(0);
+    let _ = writeln!(
+        ret,
+        "{} {volume_line}",
+        table::pad(
+            &common::format_commas(common::divide_by_1000(max_volume)),
+            LABEL_WIDTH,
+            table::Align::Right
+        )
+    );
+
+    let first = &daily_data[0].date;
+    let last = &daily_data[daily_data.len() - 1].date;
+    let gap = daily_data
+        .len()
+        .saturating_sub(first.len() + last.len())
+        .max(1);
+    let _ = writeln!(
+        ret,
+        "{} {first}{}{last}",
+        " ".repeat(LABEL_WIDTH),
+        " ".repeat(gap)
+    );
+    ret
+}
@@ -0,0 +1,539 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use chrono::NaiveTime;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use stock::analysis::sector::GroupBy;
+use stock::analysis::sweep::{ParamRange, Strategy};
+use stock::chart::candlestick::{ChartOptions, Overlay, Subpanel};
+use stock::chart::export::ChartFormat;
+use stock::common;
+use stock::config;
+use stock::output::render::OutputFormat;
+use stock::server;
+use stock::twse::company_map::{CompanyMap, Market, SecurityType};
+use stock::universe::filter::UniverseFilter;
+use stock::universe::market_cap::MarketCapRange;
+use stock::universe::scope::Scope;
+
+/// 台股資料抓取與選股工具，不帶子命令時進入全螢幕介面
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub scope: ScopeArgs,
+
+    /// 結果輸出格式
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
+    /// 設定檔 (TOML)，不存在時使用預設值
+    #[arg(long, global = true, default_value = config::CONFIG_FILE)]
+    pub config: String,
+
+    /// 覆寫設定，可重複指定，例如 --set thresholds.doji_tolerance=0.02
+    #[arg(long = "set", global = true, value_name = "SECTION.KEY=VALUE")]
+    pub overrides: Vec<String>,
+
+    /// 不連線抓取公司清單，只使用快取
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// 解析命令列，月份區間不合理時與其他參數錯誤一樣顯示用法並結束
+    pub fn parse_checked() -> Self {
+        let cli = Self::parse();
+        if let Some(range) = cli.command.as_ref().and_then(Command::month_range)
+            && let Err(e) = range.check()
+        {
+            Self::command().error(ErrorKind::ValueValidation, e).exit();
+        }
+        cli
+    }
+}
+
+/// 選股範圍 (預設全市場)
+#[derive(Debug, Args)]
+pub struct ScopeArgs {
+    /// 只掃描自選股清單內的股票
+    #[arg(long, global = true, conflicts_with = "industry")]
+    pub watchlist: Option<String>,
+
+    /// 只掃描指定產業 (代號或名稱)
+    #[arg(long, global = true, conflicts_with = "sector")]
+    pub industry: Option<String>,
+
+    /// 只掃描指定類股 (例如 電子、傳產、金融)
+    #[arg(long, global = true, conflicts_with = "watchlist")]
+    pub sector: Option<String>,
+
+    /// 只掃描上市 (twse) 或上櫃 (tpex) 股票
+    #[arg(long, global = true, value_enum, conflicts_with_all = ["watchlist", "industry", "sector"])]
+    pub market: Option<Market>,
+
+    /// 只掃描指定的證券類型，可重複指定
+    #[arg(long = "security-type", global = true, value_enum)]
+    pub security_types: Vec<SecurityType>,
+
+    /// 最小市值 (億元，以本地最新收盤價計算)，覆寫 universe.min_market_cap
+    #[arg(long, global = true)]
+    pub min_market_cap: Option<f64>,
+
+    /// 最大市值 (億元，以本地最新收盤價計算)，覆寫 universe.max_market_cap
+    #[arg(long, global = true)]
+    pub max_market_cap: Option<f64>,
+}
+
+impl ScopeArgs {
+    pub fn scope(&self) -> Scope {
+        match (&self.watchlist, &self.industry, &self.sector, self.market) {
+            (Some(name), _, _, _) => Scope::Watchlist(name.clone()),
+            (None, Some(industry), _, _) => Scope::Industry(industry.clone()),
+            (None, None, Some(sector), _) => Scope::Sector(sector.clone()),
+            (None, None, None, Some(market)) => Scope::Market(market),
+            (None, None, None, None) => Scope::All,
+        }
+    }
+
+    pub fn market_cap(&self) -> MarketCapRange {
+        MarketCapRange {
+            min: self.min_market_cap,
+            max: self.max_market_cap,
+        }
+    }
+
+    /// [universe] 設定加上命令列的市值範圍
+    pub fn universe_filter(&self) -> UniverseFilter {
+        UniverseFilter::from_config().with_market_cap(self.market_cap())
+    }
+
+    /// 只套用掃描範圍與證券類型，不讀取本地資料
+    pub fn select(&self, company_map: &CompanyMap) -> Result<CompanyMap, Box<dyn Error>> {
+        let scoped_map = self.scope().apply(company_map)?;
+        if self.security_types.is_empty() {
+            return Ok(scoped_map);
+        }
+        Ok(scoped_map.filter(|company| self.security_types.contains(&company.security_type)))
+    }
+
+    /// 套用掃描範圍與證券類型，再套用需要本地資料的 [universe] 條件 (市值、日均量)
+    pub fn apply(&self, company_map: &CompanyMap) -> Result<CompanyMap, Box<dyn Error>> {
+        Ok(self.universe_filter().apply(&self.select(company_map)?))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct MonthRangeArgs {
+    /// 起始月份 (YYYYMM)
+    #[arg(long, value_parser = parse_year_month)]
+    pub from: String,
+
+    /// 結束月份 (YYYYMM)
+    #[arg(long, value_parser = parse_year_month)]
+    pub to: String,
+}
+
+impl MonthRangeArgs {
+    fn check(&self) -> Result<(), String> {
+        if self.from > self.to {
+            return Err(format!(
+                "起始月份 {} 不能晚於結束月份 {}",
+                self.from, self.to
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 數字選單 (舊版互動介面)
+    Menu,
+    /// 抓取 TWSE 資料
+    Fetch {
+        /// 月份 (YYYYMM)
+        #[arg(value_parser = parse_year_month)]
+        year_month: String,
+    },
+    /// 整理資料 (移除價格為 0 的日資料)
+    Clean,
+    /// 單日長紅 K 棒
+    LongRed {
+        /// 日期 (YYYYMMDD)
+        #[arg(value_parser = parse_date)]
+        date: String,
+    },
+    /// 單日 MACD 黃金交叉
+    Macd {
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+    },
+    /// 單日大成交量
+    Volume {
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+
+        /// 成交量閾值 (張)
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// 單日 MACD 黃金交叉且大成交量
+    MacdVolume {
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+
+        /// 成交量閾值 (張)
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// 單日十字線
+    Doji {
+        /// 日期 (YYYYMMDD)
+        #[arg(value_parser = parse_date)]
+        date: String,
+    },
+    /// 單日十字線波段驗證
+    DojiSwing {
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+    },
+    /// 多日陽吞噬形態
+    EngulfingRange {
+        #[command(flatten)]
+        range: MonthRangeArgs,
+    },
+    /// 單日陽吞噬形態
+    EngulfingDate {
+        /// 日期 (YYYYMMDD)
+        #[arg(value_parser = parse_date)]
+        date: String,
+    },
+    /// 參數掃描與前進式最佳化
+    Sweep {
+        #[arg(long, value_enum)]
+        strategy: SweepStrategy,
+
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        /// 持有交易日數
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        horizon: u64,
+
+        /// 參數範圍，例如 doji_tolerance=0.01:0.05:0.01，未指定的參數使用預設值
+        #[arg(long = "param", value_parser = parse_param)]
+        params: Vec<(String, ParamRange)>,
+
+        /// 最少訊號數
+        #[arg(long, default_value_t = 0)]
+        min_signals: usize,
+
+        /// 前進式分段數 (0 表示不做)
+        #[arg(long, default_value_t = 0)]
+        folds: usize,
+    },
+    /// 投資組合損益
+    Portfolio,
+    /// 自選股管理
+    Watchlist {
+        #[command(subcommand)]
+        command: WatchlistCommand,
+    },
+    /// 檢查警示
+    Alerts {
+        /// 月份 (YYYYMM)
+        #[arg(value_parser = parse_year_month)]
+        year_month: String,
+    },
+    /// 自訂選股條件
+    Screen {
+        /// 條件式或已儲存的名稱
+        expression: String,
+
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+
+        /// 以此名稱儲存條件
+        #[arg(long)]
+        save: Option<String>,
+    },
+    /// 繪製 K 線圖 (SVG/PNG)
+    Chart {
+        stock_no: String,
+
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        #[command(flatten)]
+        chart: ChartArgs,
+
+        /// 輸出格式
+        #[arg(long, value_enum, default_value_t = ChartFormat::Svg)]
+        format: ChartFormat,
+
+        /// 輸出路徑，預設為 charts/<股號>_<起始月份>_<結束月份>.<副檔名>
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// 產生每日報告 (Markdown/HTML)
+    ///
+    /// 市場概況與 sectors 相同，只統計掃描範圍內的股票；
+    /// 全市場的多空環境請用 breadth
+    Report {
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+    },
+    /// 啟動本地 HTTP API (JSON)
+    Serve {
+        /// 監聽位址
+        #[arg(long, default_value = server::app::DEFAULT_ADDR)]
+        addr: String,
+    },
+    /// 排程模式：台北時間每個交易日收盤後抓取資料、檢查警示並產生報告
+    Daemon {
+        /// 每日執行時間 (台北時間 HH:MM)
+        #[arg(long, default_value = "15:00", value_parser = parse_time)]
+        at: NaiveTime,
+
+        /// TWSE 尚未公布時的重試間隔 (分鐘)
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        retry_minutes: u64,
+
+        /// 每個交易日最多重試次數
+        #[arg(long, default_value_t = 6)]
+        max_retries: usize,
+
+        /// 啟動時往回補跑幾天內未完成的交易日
+        #[arg(long, default_value_t = 7)]
+        catch_up_days: u32,
+
+        /// 補跑完未完成的交易日後就結束
+        #[arg(long)]
+        once: bool,
+    },
+    /// 在終端機顯示 K 線圖 (本地資料)
+    Candles {
+        stock_no: String,
+
+        /// 交易日數
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        days: u64,
+
+        /// K 棒區的行數
+        #[arg(long, default_value_t = 15, value_parser = clap::value_parser!(u64).range(2..))]
+        height: u64,
+    },
+    /// 重新抓取公司清單並列出與上次執行此命令相比新上市、下市的公司
+    RefreshCompanies,
+    /// 列出公司，可用 --industry/--sector/--market/--security-type 篩選
+    Companies {
+        /// 股號或名稱 (部分比對)
+        keyword: Option<String>,
+    },
+    /// 列出依 [universe] 設定與忽略清單排除的公司及原因
+    Universe,
+    /// 各類股/產業當日漲跌、成交金額與 52 週新高/新低
+    ///
+    /// 只統計掃描範圍內的股票 ([universe] 設定、忽略清單與 --watchlist/--industry/--sector)
+    Sectors {
+        /// 日期 (YYYYMMDD)
+        #[arg(long, value_parser = parse_date)]
+        date: String,
+
+        /// 分組方式
+        #[arg(long, value_enum, default_value_t = SectorGroup::Sector)]
+        by: SectorGroup,
+
+        /// 每組列出的漲幅/跌幅前幾名
+        #[arg(long, default_value_t = 3)]
+        top: usize,
+    },
+    /// 列出產業表 (代號、名稱、類股、公司數)
+    Industries {
+        /// 匯出自訂的產業項目 (與內建不同的部分) 到 industries.toml，編輯後會覆蓋內建的同代號項目
+        #[arg(long)]
+        export: bool,
+    },
+    /// 市場寬度：騰落線、McClellan、站上均線比例與 52 週新高/新低家數 (本地資料)
+    ///
+    /// 統計本地有資料的所有股票 (不含 ETF)，不受掃描範圍與 [universe] 排除條件影響，
+    /// 與選股條件中的 breadth.* 相同；sectors 與 report 的市場概況只統計掃描範圍內的股票
+    Breadth {
+        #[command(flatten)]
+        range: MonthRangeArgs,
+
+        /// 同時輸出圖表
+        #[arg(long, value_enum)]
+        chart: Option<ChartFormat>,
+
+        /// 圖表路徑，預設為 charts/breadth_<起始月份>_<結束月份>.<副檔名>
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    fn month_range(&self) -> Option<&MonthRangeArgs> {
+        match self {
+            Command::Macd { range, .. }
+            | Command::MacdVolume { range, .. }
+            | Command::DojiSwing { range, .. }
+            | Command::EngulfingRange { range }
+            | Command::Sweep { range, .. }
+            | Command::Chart { range, .. }
+            | Command::Breadth { range, .. } => Some(range),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ChartArgs {
+    /// 均線天數，可重複指定
+    #[arg(long = "ma", default_values_t = [5, 20, 60])]
+    pub ma: Vec<usize>,
+
+    /// 疊加布林通道 (20 日, 2 倍標準差)
+    #[arg(long)]
+    pub bollinger: bool,
+
+    /// 下方指標面板
+    #[arg(long, value_enum, default_value_t = ChartSubpanel::Macd)]
+    pub subpanel: ChartSubpanel,
+
+    /// 不標示十字線、陽吞噬與交叉訊號
+    #[arg(long)]
+    pub no_signals: bool,
+
+    /// 圖片寬度 (像素)
+    #[arg(long, default_value_t = 1200)]
+    pub width: u32,
+
+    /// 圖片高度 (像素)
+    #[arg(long, default_value_t = 800)]
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ChartSubpanel {
+    Macd,
+    Kd,
+    None,
+}
+
+impl ChartArgs {
+    pub fn options(&self) -> ChartOptions {
+        let mut overlays: Vec<Overlay> = self.ma.iter().map(|n| Overlay::Ma(*n)).collect();
+        if self.bollinger {
+            overlays.push(Overlay::Bollinger { n: 20, k: 2.0 });
+        }
+        ChartOptions {
+            overlays,
+            subpanel: match self.subpanel {
+                ChartSubpanel::Macd => Some(Subpanel::Macd),
+                ChartSubpanel::Kd => Some(Subpanel::Kd),
+                ChartSubpanel::None => None,
+            },
+            signals: !self.no_signals,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WatchlistCommand {
+    /// 列出清單
+    List,
+    /// 顯示清單內容
+    Show { name: String },
+    /// 加入股號
+    Add { name: String, stock_no: String },
+    /// 移除股號
+    Remove { name: String, stock_no: String },
+    /// 刪除清單
+    Delete { name: String },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SectorGroup {
+    Sector,
+    Industry,
+}
+
+impl From<SectorGroup> for GroupBy {
+    fn from(group: SectorGroup) -> Self {
+        match group {
+            SectorGroup::Sector => GroupBy::Sector,
+            SectorGroup::Industry => GroupBy::Industry,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SweepStrategy {
+    Doji,
+    LongRed,
+    DojiSwing,
+    Engulfing,
+}
+
+impl From<SweepStrategy> for Strategy {
+    fn from(strategy: SweepStrategy) -> Self {
+        match strategy {
+            SweepStrategy::Doji => Strategy::Doji,
+            SweepStrategy::LongRed => Strategy::LongRedCandle,
+            SweepStrategy::DojiSwing => Strategy::DojiInSwing,
+            SweepStrategy::Engulfing => Strategy::BullishEngulfing,
+        }
+    }
+}
+
+fn parse_year_month(input: &str) -> Result<String, String> {
+    if common::is_valid_year_month(input) {
+        Ok(input.to_string())
+    } else {
+        Err("月份格式應為 YYYYMM".to_string())
+    }
+}
+
+fn parse_date(input: &str) -> Result<String, String> {
+    if common::is_valid_date(input) {
+        Ok(input.to_string())
+    } else {
+        Err("日期格式應為 YYYYMMDD".to_string())
+    }
+}
+
+fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(input, "%H:%M").map_err(|_| "時間格式應為 HH:MM".to_string())
+}
+
+fn parse_param(input: &str) -> Result<(String, ParamRange), String> {
+    let (name, range) = input
+        .split_once('=')
+        .ok_or("格式應為 名稱=起始:結束:間隔")?;
+    let range = ParamRange::parse(range).ok_or(format!("無效的範圍: {range}"))?;
+    Ok((name.trim().to_string(), range))
+}
@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::time::Duration;
+
+use stock::analysis::sweep::{ParamRange, Strategy};
+use stock::daemon::scheduler::{self, DaemonOptions};
+use stock::output::render::OutputFormat;
+use stock::screener;
+use stock::server;
+use stock::twse::company_map::CompanyMap;
+
+use crate::cli::args::{Command, ScopeArgs, WatchlistCommand};
+use crate::menu::{actions, main_menu};
+
+/// 執行子命令；只有掃描股票的命令 (選股、參數掃描、報告、類股表現、選單/服務/排程) 依 scope 與
+/// [universe] 條件篩選，其餘使用全市場，結果依 format 輸出
+pub async fn run(
+    command: Command,
+    company_map: &CompanyMap,
+    scope: &ScopeArgs,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    // 資料相關的 [universe] 條件要讀取每檔的本地資料，只在需要時篩選
+    let scoped_map = || scope.apply(company_map);
+
+    match command {
+        Command::Menu => main_menu::main_menu(&scoped_map()?).await,
+        Command::Fetch { year_month } => actions::fetch_data(company_map, &year_month).await?,
+        Command::Clean => actions::refactor_data()?,
+        Command::LongRed { date } => actions::long_red_candle(&scoped_map()?, &date, format).await,
+        Command::Macd { range, date } => {
+            actions::macd_golden_cross(&scoped_map()?, &range.from, &range.to, &date, format).await
+        }
+        Command::Volume { date, threshold } => {
+            actions::volume_larger(&scoped_map()?, &date, threshold, format).await
+        }
+        Command::MacdVolume {
+            range,
+            date,
+            threshold,
+        } => {
+            actions::macd_golden_cross_volume_larger(
+                &scoped_map()?,
+                &range.from,
+                &range.to,
+                &date,
+                threshold,
+                format,
+            )
+            .await
+        }
+        Command::Doji { date } => actions::doji(&scoped_map()?, &date, format).await,
+        Command::DojiSwing { range, date } => {
+            actions::doji_in_swing(&scoped_map()?, &range.from, &range.to, &date, format).await
+        }
+        Command::EngulfingRange { range } => {
+            actions::bullish_engulfing_range(&scoped_map()?, &range.from, &range.to, format).await
+        }
+        Command::EngulfingDate { date } => {
+            actions::bullish_engulfing_date(&scoped_map()?, &date, format).await
+        }
+        Command::Sweep {
+            strategy,
+            range,
+            horizon,
+            params,
+            min_signals,
+            folds,
+        } => {
+            let strategy: Strategy = strategy.into();
+            let ranges = sweep_ranges(strategy, &params)?;
+            actions::parameter_sweep(
+                &scoped_map()?,
+                &actions::SweepOptions {
+                    strategy,
+                    year_month_from: range.from,
+                    year_month_to: range.to,
+                    horizon: horizon as usize,
+                    ranges,
+                    min_signals,
+                    folds,
+                },
+                format,
+            )?;
+        }
+        Command::Portfolio => actions::portfolio(company_map, format)?,
+        Command::Watchlist { command } => match command {
+            WatchlistCommand::List => actions::watchlist_list(),
+            WatchlistCommand::Show { name } => actions::watchlist_show(&name)?,
+            WatchlistCommand::Add { name, stock_no } => actions::watchlist_add(&name, &stock_no)?,
+            WatchlistCommand::Remove { name, stock_no } => {
+                actions::watchlist_remove(&name, &stock_no)?
+            }
+            WatchlistCommand::Delete { name } => actions::watchlist_delete(&name)?,
+        },
+        Command::Alerts { year_month } => actions::check_alerts(company_map, &year_month).await?,
+        Command::Screen {
+            expression,
+            date,
+            save,
+        } => {
+            let (_, expression) = screener::saved::resolve(&expression);
+            actions::screen(&scoped_map()?, &expression, &date, format).await?;
+            if let Some(name) = save {
+                actions::save_screen(&name, &expression)?;
+            }
+        }
+        Command::Chart {
+            stock_no,
+            range,
+            chart,
+            format,
+            out,
+        } => {
+            actions::chart(
+                company_map,
+                &stock_no,
+                &range.from,
+                &range.to,
+                &chart.options(),
+                format,
+                out,
+            )
+            .await?
+        }
+        Command::Report { date } => actions::daily_report(&scoped_map()?, &date).await?,
+        Command::Serve { addr } => server::app::serve(scoped_map()?, &addr).await?,
+        Command::Daemon {
+            at,
+            retry_minutes,
+            max_retries,
+            catch_up_days,
+            once,
+        } => {
+            let options = DaemonOptions {
+                run_at: at,
+                retry_interval: Duration::from_secs(retry_minutes * 60),
+                max_retries,
+                catch_up_days,
+                once,
+            };
+            scheduler::run(scoped_map()?, &options).await?
+        }
+        Command::Candles {
+            stock_no,
+            days,
+            height,
+        } => actions::terminal_chart(company_map, &stock_no, days as usize, height as usize),
+        Command::RefreshCompanies => actions::refresh_companies().await?,
+        Command::Companies { keyword } => actions::companies(
+            &scope.select(company_map)?,
+            keyword.as_deref().unwrap_or_default(),
+            format,
+        ),
+        Command::Sectors { date, by, top } => {
+            actions::sector_performance(&scoped_map()?, &date, by.into(), top, format)
+        }
+        Command::Industries { export } => actions::industries(company_map, export, format)?,
+        Command::Breadth { range, chart, out } => {
+            actions::breadth(company_map, &range.from, &range.to, chart, out, format)?
+        }
+        Command::Universe => {
+            actions::universe_exclusions(company_map, &scope.universe_filter(), format)
+        }
+    }
+    Ok(())
+}
+
+/// 依策略的參數順序組出範圍，未指定的使用預設值
+fn sweep_ranges(
+    strategy: Strategy,
+    params: &[(String, ParamRange)],
+) -> Result<Vec<ParamRange>, Box<dyn Error>> {
+    for (name, _) in params {
+        if !strategy.param_names().contains(&name.as_str()) {
+            return Err(format!(
+                "{} 沒有參數 {name}，可用參數: {}",
+                strategy.name(),
+                strategy.param_names().join(", ")
+            )
+            .into());
+        }
+    }
+
+    Ok(strategy
+        .param_names()
+        .iter()
+        .zip(strategy.default_params())
+        .map(|(name, default)| {
+            params
+                .iter()
+                .find(|(param, _)| param == name)
+                .map(|(_, range)| *range)
+                .unwrap_or(ParamRange::single(default))
+        })
+        .collect())
+}
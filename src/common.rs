@@ -1,15 +1,57 @@
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use chrono::{Datelike, NaiveDate};
 
-pub fn to_roc_date(date_str: &str) -> String {
-    // 解析字串成 NaiveDate
-    let date = NaiveDate::parse_from_str(date_str, "%Y%m%d").ok().unwrap();
+pub fn read_lines_to_vec<P>(filename: P) -> io::Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(filename)?;
+    let reader = io::BufReader::new(file);
+
+    let mut result = Vec::new();
+
+    // for line in reader.lines() {
+    //     if let Ok(l) = line {
+    //         // 如果有註解，用 "//" 切開，只取前半段
+    //         let clean = l.split("//").next().unwrap().trim();
+    //         if !clean.is_empty() {
+    //             result.push(clean.to_string());
+    //         }
+    //     }
+    // }
+    for l in reader.lines().map_while(Result::ok) {
+        // 如果有註解，用 "//" 切開，只取前半段
+        let clean = l.split("//").next().unwrap().trim();
+        if !clean.is_empty() {
+            result.push(clean.to_string());
+        }
+    }
+    Ok(result)
+}
+
+/// 檢查是否為 YYYYMM 格式的月份
+pub fn is_valid_year_month(year_month: &str) -> bool {
+    year_month.len() == 6 && NaiveDate::parse_from_str(&format!("{year_month}01"), "%Y%m%d").is_ok()
+}
+
+/// 檢查是否為 YYYYMMDD 格式的日期
+pub fn is_valid_date(date: &str) -> bool {
+    date.len() == 8 && NaiveDate::parse_from_str(date, "%Y%m%d").is_ok()
+}
 
-    // 民國年 = 西元年 - 1911
-    let roc_year = date.year() - 1911;
-    let month = date.month();
-    let day = date.day();
+/// YYYYMMDD 轉成民國日期 (例如 115/01/02)，格式不符時回傳 None
+pub fn to_roc_date(date_str: &str) -> Option<String> {
+    let date = NaiveDate::parse_from_str(date_str, "%Y%m%d").ok()?;
+    Some(naive_to_roc_date(date))
+}
 
-    format!("{}/{:02}/{:02}", roc_year, month, day)
+/// YYYYMMDD 的年月部分 (YYYYMM)，格式不符時回傳 None
+pub fn year_month_of(date: &str) -> Option<&str> {
+    is_valid_date(date).then(|| &date[0..6])
 }
 
 pub fn naive_to_roc_date(date: NaiveDate) -> String {
@@ -17,6 +59,15 @@ pub fn naive_to_roc_date(date: NaiveDate) -> String {
     format!("{}/{:02}/{:02}", roc_year, date.month(), date.day())
 }
 
+/// 民國日期 (例如 115/01/02) 轉成 NaiveDate
+pub fn roc_date_to_naive(roc_date: &str) -> Option<NaiveDate> {
+    let mut parts = roc_date.trim().split('/');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    NaiveDate::from_ymd_opt(year + 1911, month, day)
+}
+
 pub fn format_commas(value: u64) -> String {
     let s = value.to_string();
     let bytes = s.as_bytes();
@@ -34,3 +85,50 @@ pub fn format_commas(value: u64) -> String {
 pub fn divide_by_1000(value: u64) -> u64 {
     (value as f64 / 1000.0) as u64
 }
+
+/// YYYYMM 轉成從西元 0 年 1 月起算的月份數
+fn month_index(year_month: &str) -> Option<i32> {
+    if !is_valid_year_month(year_month) {
+        return None;
+    }
+    let (year_str, month_str) = year_month.split_at(4);
+    let year: i32 = year_str.parse().ok()?;
+    let month: i32 = month_str.parse().ok()?;
+    Some(year * 12 + (month - 1))
+}
+
+fn from_month_index(index: i32) -> String {
+    format!("{:04}{:02}", index.div_euclid(12), index.rem_euclid(12) + 1)
+}
+
+/// 回傳 year_month_from 到 year_month_to (含) 之間的所有月份，格式 YYYYMM；
+/// 任一端格式不符時回傳空的 Vec
+pub fn year_months(year_month_from: &str, year_month_to: &str) -> Vec<String> {
+    match (month_index(year_month_from), month_index(year_month_to)) {
+        (Some(from), Some(to)) => (from..=to).map(from_month_index).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 將 YYYYMM 往後 (正數) 或往前 (負數) 移動 months 個月，格式不符時回傳 None
+pub fn shift_year_month(year_month: &str, months: i32) -> Option<String> {
+    Some(from_month_index(month_index(year_month)? + months))
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// 關閉進度訊息 (全螢幕介面使用時避免干擾畫面)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// 進度訊息輸出到 stderr，不影響 stdout 的結果輸出
+pub fn log(message: &str) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("{message}");
+    }
+}
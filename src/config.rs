@@ -0,0 +1,280 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use crate::twse::company_map::{Market, SecurityType};
+
+/// 設定檔，範例:
+/// [data]
+/// dir = "/mnt/stock/data"
+/// state_dir = "/mnt/stock"
+///
+/// [thresholds]
+/// doji_tolerance = 0.02
+///
+/// [twse]
+/// timeout_secs = 20
+///
+/// [universe]
+/// markets = ["twse"]
+/// exclude_industries = ["23"]
+/// security_types = ["stock", "etf", "leveraged_etf", "inverse_etf"]
+/// min_avg_volume = 500
+///
+/// 沒有寫到的項目使用預設值，沒有設定檔時全部使用預設值
+pub const CONFIG_FILE: &str = "stock.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub data: DataConfig,
+    pub thresholds: Thresholds,
+    pub twse: TwseConfig,
+    pub tpex: TpexConfig,
+    pub universe: UniverseConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataConfig {
+    pub dir: String,       // 日資料存放目錄
+    pub state_dir: String, // 自選股、交易紀錄、警示、快取與報告等檔案的目錄，空白表示目前目錄
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        DataConfig {
+            dir: "data".to_string(),
+            state_dir: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    pub doji_tolerance: f64,   // 十字線：開收盤差距小於 (最高 - 最低) 的比例
+    pub long_red_ratio: f64,   // 長紅K：收盤價高於開盤價的倍數
+    pub swing_high_ratio: f64, // 十字線波段驗證：區間最高價需達現價的倍數
+    pub swing_low_ratio: f64,  // 十字線波段驗證：區間最低價需低於現價的倍數
+    pub engulfing_target_ratio: f64, // 陽吞噬形態出現後的目標價倍數
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            doji_tolerance: 0.01,
+            long_red_ratio: 1.05,
+            swing_high_ratio: 1.3,
+            swing_low_ratio: 0.7,
+            engulfing_target_ratio: 1.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TwseConfig {
+    pub stock_day_url: String, // 個股日成交資訊
+    pub company_url: String,   // 上市公司基本資料
+    pub etf_url: String,       // 上市證券每日收盤行情，從中取出 ETF
+    pub user_agent: String,
+    pub timeout_secs: u64,
+}
+
+impl Default for TwseConfig {
+    fn default() -> Self {
+        TwseConfig {
+            stock_day_url: "https://www.twse.com.tw/exchangeReport/STOCK_DAY".to_string(),
+            company_url: "https://openapi.twse.com.tw/v1/opendata/t187ap03_L".to_string(),
+            etf_url: "https://openapi.twse.com.tw/v1/exchangeReport/STOCK_DAY_ALL".to_string(),
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/144.0.0.0 Safari/537.36".to_string(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// User-Agent 與逾時沿用 [twse]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TpexConfig {
+    pub stock_day_url: String, // 上櫃個股日成交資訊
+    pub company_url: String,   // 上櫃公司基本資料
+    pub etf_url: String,       // 上櫃證券每日收盤行情，從中取出 ETF
+}
+
+impl Default for TpexConfig {
+    fn default() -> Self {
+        TpexConfig {
+            stock_day_url: "https://www.tpex.org.tw/www/zh-tw/afterTrading/tradingStock"
+                .to_string(),
+            company_url: "https://www.tpex.org.tw/openapi/v1/mopsfin_t187ap03_O".to_string(),
+            etf_url: "https://www.tpex.org.tw/openapi/v1/tpex_mainboard_daily_close_quotes"
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UniverseConfig {
+    pub markets: Vec<Market>,              // 載入哪些市場的公司清單
+    pub include_industries: Vec<String>,   // 只納入這些產業代號，空白表示全部
+    pub exclude_industries: Vec<String>,   // 不納入公司清單的產業代號
+    pub security_types: Vec<SecurityType>, // 納入的證券類型
+    pub min_listing_days: u32,             // 上市/上櫃未滿天數的不納入，0 表示不限
+    pub min_market_cap: Option<f64>,       // 掃描前排除市值 (億元) 低於此值的股票
+    pub max_market_cap: Option<f64>,       // 掃描前排除市值 (億元) 高於此值的股票
+    pub min_avg_volume: Option<u64>, // 掃描前排除近 avg_volume_days 日均量 (張) 低於此值的股票
+    pub avg_volume_days: usize,
+}
+
+impl Default for UniverseConfig {
+    fn default() -> Self {
+        UniverseConfig {
+            markets: vec![Market::Twse, Market::Tpex],
+            include_industries: Vec::new(),
+            exclude_industries: vec!["17".to_string()], // 金融保險業
+            security_types: vec![SecurityType::Stock, SecurityType::Etf], // 不含槓桿、反向型 ETF
+            min_listing_days: 0,
+            min_market_cap: None,
+            max_market_cap: None,
+            min_avg_volume: None,
+            avg_volume_days: 20,
+        }
+    }
+}
+
+impl Config {
+    /// 讀取設定檔 (不存在時使用預設值)，再套用 "section.key=value" 形式的覆寫
+    pub fn load(path: &str, overrides: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut table = if Path::new(path).exists() {
+            fs::read_to_string(path)?
+                .parse::<Table>()
+                .map_err(|e| format!("{path} 格式錯誤: {e}"))?
+        } else {
+            Table::new()
+        };
+
+        for item in overrides {
+            apply_override(&mut table, item)?;
+        }
+
+        Ok(Value::Table(table)
+            .try_into()
+            .map_err(|e| format!("設定錯誤: {e}"))?)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// "thresholds.doji_tolerance=0.02"；值依 TOML 語法解析，無法解析時當作字串
+fn apply_override(table: &mut Table, item: &str) -> Result<(), Box<dyn Error>> {
+    let (key, value) = item
+        .split_once('=')
+        .ok_or_else(|| format!("覆寫格式應為 section.key=value: {item}"))?;
+    let (section, key) = key
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| format!("覆寫格式應為 section.key=value: {item}"))?;
+
+    let value = value.trim();
+    let value = format!("v = {value}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut parsed| parsed.remove("v"))
+        .unwrap_or_else(|| Value::String(value.to_string()));
+
+    let section = table
+        .entry(section.to_string())
+        .or_insert_with(|| Value::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| format!("{section} 不是設定區段"))?;
+    section.insert(key.to_string(), value);
+    Ok(())
+}
+
+/// 程式啟動時設定一次；之後再呼叫不會改變已生效的設定
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// 目前的設定，未呼叫 init 時為預設值
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// 狀態檔或目錄在 [data] state_dir 下的路徑，例如 path("holdings.txt")
+pub fn path(name: &str) -> PathBuf {
+    Path::new(&get().data.state_dir).join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(overrides: &[&str]) -> Result<Config, Box<dyn Error>> {
+        let overrides: Vec<String> = overrides.iter().map(|s| s.to_string()).collect();
+        Config::load("no_such_stock.toml", &overrides)
+    }
+
+    #[test]
+    fn defaults_without_file() {
+        let config = load(&[]).unwrap();
+        assert_eq!(config.data.dir, "data");
+        assert_eq!(config.data.state_dir, "");
+        assert_eq!(config.thresholds.doji_tolerance, 0.01);
+    }
+
+    #[test]
+    fn overrides_parse_toml_values() {
+        let config = load(&[
+            "thresholds.doji_tolerance=0.02",
+            "twse.timeout_secs = 30",
+            "universe.markets=[\"tpex\"]",
+            "universe.min_avg_volume=500",
+        ])
+        .unwrap();
+        assert_eq!(config.thresholds.doji_tolerance, 0.02);
+        assert_eq!(config.twse.timeout_secs, 30);
+        assert_eq!(config.universe.markets, [Market::Tpex]);
+        assert_eq!(config.universe.min_avg_volume, Some(500));
+        // 沒有覆寫的項目維持預設值
+        assert_eq!(config.thresholds.long_red_ratio, 1.05);
+    }
+
+    #[test]
+    fn unquoted_override_is_string() {
+        let config = load(&["data.dir=/mnt/stock/data", "data.state_dir=/mnt/stock"]).unwrap();
+        assert_eq!(config.data.dir, "/mnt/stock/data");
+        assert_eq!(config.data.state_dir, "/mnt/stock");
+    }
+
+    #[test]
+    fn rejects_malformed_overrides() {
+        assert!(load(&["thresholds.doji_tolerance"]).is_err());
+        assert!(load(&["doji_tolerance=0.02"]).is_err());
+        assert!(load(&["thresholds.no_such_key=1"]).is_err());
+        assert!(load(&["thresholds.doji_tolerance=abc"]).is_err());
+    }
+
+    #[test]
+    fn rejects_override_of_non_section() {
+        let mut table = Table::new();
+        table.insert("data".to_string(), Value::Integer(1));
+        assert!(apply_override(&mut table, "data.dir=x").is_err());
+    }
+
+    #[test]
+    fn path_defaults_to_current_dir() {
+        assert_eq!(path("holdings.txt"), PathBuf::from("holdings.txt"));
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Asia::Taipei;
+use chrono_tz::Tz;
+
+use crate::common;
+use crate::config;
+
+/// 休市日，一行一個 YYYYMMDD (國定假日、颱風假…)，週末不用列
+pub const HOLIDAYS_FILE: &str = "holidays.txt";
+
+pub fn now_taipei() -> DateTime<Tz> {
+    Utc::now().with_timezone(&Taipei)
+}
+
+pub fn to_date_str(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// 交易日曆：週一到週五且不在休市日清單
+pub struct Calendar {
+    holidays: Vec<NaiveDate>,
+}
+
+impl Calendar {
+    pub fn new(holidays: Vec<NaiveDate>) -> Self {
+        Calendar { holidays }
+    }
+
+    pub fn load() -> Self {
+        Calendar::new(
+            common::read_lines_to_vec(config::path(HOLIDAYS_FILE))
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|line| NaiveDate::parse_from_str(line, "%Y%m%d").ok())
+                .collect(),
+        )
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// 最近 days 天內 (含 until) 的交易日，由舊到新
+    pub fn trading_days_until(&self, until: NaiveDate, days: u32) -> Vec<NaiveDate> {
+        (0..days as i64)
+            .rev()
+            .map(|offset| until - Duration::days(offset))
+            .filter(|date| self.is_trading_day(*date))
+            .collect()
+    }
+
+    /// now 之後第一個交易日的 run_at 時刻 (台北時間)
+    pub fn next_run(&self, now: DateTime<Tz>, run_at: NaiveTime) -> DateTime<Tz> {
+        let mut date = now.date_naive();
+        loop {
+            if self.is_trading_day(date)
+                && let Some(run) = date.and_time(run_at).and_local_timezone(Taipei).single()
+                && run > now
+            {
+                return run;
+            }
+            date += Duration::days(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    // 2026/02/16 (一) 為休市日
+    fn calendar() -> Calendar {
+        Calendar::new(vec![date(2026, 2, 16)])
+    }
+
+    #[test]
+    fn weekends_and_holidays_are_not_trading_days() {
+        let calendar = calendar();
+        assert!(calendar.is_trading_day(date(2026, 2, 13)));
+        assert!(!calendar.is_trading_day(date(2026, 2, 14)));
+        assert!(!calendar.is_trading_day(date(2026, 2, 15)));
+        assert!(!calendar.is_trading_day(date(2026, 2, 16)));
+        assert!(calendar.is_trading_day(date(2026, 2, 17)));
+    }
+
+    #[test]
+    fn trading_days_until_includes_until_and_is_sorted() {
+        assert_eq!(
+            calendar().trading_days_until(date(2026, 2, 17), 5),
+            vec![date(2026, 2, 13), date(2026, 2, 17)]
+        );
+        assert!(
+            calendar()
+                .trading_days_until(date(2026, 2, 17), 0)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn next_run_is_today_before_run_at() {
+        let run_at = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let now = Taipei.with_ymd_and_hms(2026, 2, 13, 9, 0, 0).unwrap();
+        assert_eq!(
+            calendar().next_run(now, run_at),
+            Taipei.with_ymd_and_hms(2026, 2, 13, 18, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_run_skips_weekends_and_holidays() {
+        let run_at = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let now = Taipei.with_ymd_and_hms(2026, 2, 13, 19, 0, 0).unwrap();
+        assert_eq!(
+            calendar().next_run(now, run_at),
+            Taipei.with_ymd_and_hms(2026, 2, 17, 18, 0, 0).unwrap()
+        );
+    }
+}
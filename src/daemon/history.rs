@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+/// 排程工作的執行紀錄，用來補跑錯過的交易日
+pub const HISTORY_FILE: &str = "daemon_history.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Success,
+    Failed,
+    Skipped, // 休市日 (不在休市日清單，但已有更晚的交易日資料)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub date: String, // YYYYMMDD
+    pub status: JobStatus,
+    pub attempts: usize,
+    pub started_at: String,
+    pub finished_at: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JobHistory {
+    pub records: Vec<JobRecord>,
+}
+
+impl JobHistory {
+    pub fn load() -> Self {
+        File::open(config::path(HISTORY_FILE))
+            .ok()
+            .and_then(|file| serde_json::from_reader(std::io::BufReader::new(file)).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(config::path(HISTORY_FILE))?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// 已成功或確認為休市日，不需要補跑
+    pub fn is_done(&self, date: &str) -> bool {
+        self.records.iter().any(|record| {
+            record.date == date && matches!(record.status, JobStatus::Success | JobStatus::Skipped)
+        })
+    }
+
+    /// 同一天只保留最新一筆，依日期排序
+    pub fn record(&mut self, record: JobRecord) {
+        self.records.retain(|r| r.date != record.date);
+        self.records.push(record);
+        self.records.sort_by(|a, b| a.date.cmp(&b.date));
+    }
+}
@@ -0,0 +1,263 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveTime};
+use chrono_tz::Tz;
+use tokio::time::{self, Duration};
+
+use crate::alerts;
+use crate::common;
+use crate::config;
+use crate::daemon::calendar::{self, Calendar};
+use crate::daemon::history::{JobHistory, JobRecord, JobStatus};
+use crate::report;
+use crate::scripts;
+use crate::twse::company_map::CompanyMap;
+
+/// 單次執行的上限，避免 TWSE 一直回錯誤訊息時卡住
+const JOB_TIMEOUT: Duration = Duration::from_secs(3 * 60 * 60);
+
+/// 抓取失敗的檔數超過此比例時視為失敗並重試 (例如 TWSE 中途限流)；
+/// 重試時已有當日資料的股票直接讀本地，只會重抓失敗的
+const MAX_FAILED_RATIO: f64 = 0.01;
+
+pub struct DaemonOptions {
+    pub run_at: NaiveTime, // 台北時間
+    pub retry_interval: Duration,
+    pub max_retries: usize,
+    pub catch_up_days: u32, // 往回檢查幾天內未完成的交易日
+    pub once: bool,         // 補跑完就結束，不進入排程
+}
+
+fn log(message: &str) {
+    common::log(&format!(
+        "[{}] {message}",
+        calendar::now_taipei().format("%Y-%m-%d %H:%M:%S")
+    ));
+}
+
+/// 每日工作：抓取當日資料 → 整理資料 → 警示 → 每日報告；
+/// 確認為休市日時回傳 Skipped，不再重試
+async fn run_job(
+    company_map: Arc<CompanyMap>,
+    date: String,
+) -> Result<(JobStatus, String), String> {
+    let summary = scripts::data::fetch_data_date_all_companies(&company_map, &date).await;
+    if summary.closed {
+        return Ok((
+            JobStatus::Skipped,
+            format!("{date} 沒有資料但已有更晚的交易日，視為休市"),
+        ));
+    }
+    if summary.with_date == 0 {
+        return Err(format!("TWSE 尚未公布 {date} 的資料"));
+    }
+    if summary.failed as f64 > summary.total as f64 * MAX_FAILED_RATIO {
+        return Err(format!("{}/{} 檔抓取失敗", summary.failed, summary.total));
+    }
+
+    let removed = scripts::data::clean_stored_data().map_err(|e| format!("整理資料失敗: {e}"))?;
+
+    let alert_run = alerts::evaluator::run_date(&company_map, &date)
+        .await
+        .map_err(|e| format!("警示評估失敗: {e}"))?;
+    for alert in &alert_run.stdout {
+        log(&format!("🔔 {alert}"));
+    }
+    if let Some(e) = alert_run.delivery_error() {
+        return Err(e);
+    }
+
+    let report_path = config::path(report::config::REPORT_FILE);
+    let config = report::config::ReportConfig::load(&report_path)
+        .map_err(|e| format!("讀取 {} 失敗: {e}", report_path.display()))?;
+    let report_message = if config.resolved_screens().is_empty() {
+        "沒有選股條件，略過報告".to_string()
+    } else {
+        let daily_report = report::daily::build(&company_map, &config, &date)
+            .await
+            .map_err(|e| format!("產生報告失敗: {e}"))?;
+        let paths =
+            report::daily::write(&daily_report).map_err(|e| format!("寫出報告失敗: {e}"))?;
+        format!("報告 {}", paths[0].display())
+    };
+
+    Ok((
+        JobStatus::Success,
+        format!(
+            "{} 檔有資料，移除 {} 筆無效資料，新警示 {} 筆，{report_message}",
+            summary.with_date,
+            removed.len(),
+            alert_run.new_alerts.len()
+        ),
+    ))
+}
+
+/// 在獨立的 task 執行，panic 或逾時都當作一次失敗
+async fn attempt(company_map: &Arc<CompanyMap>, date: &str) -> Result<(JobStatus, String), String> {
+    let mut handle = tokio::spawn(run_job(company_map.clone(), date.to_string()));
+    match time::timeout(JOB_TIMEOUT, &mut handle).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(format!("工作異常結束: {e}")),
+        Err(_) => {
+            handle.abort();
+            Err("工作逾時".to_string())
+        }
+    }
+}
+
+async fn run_with_retries(
+    company_map: &Arc<CompanyMap>,
+    date: &str,
+    options: &DaemonOptions,
+) -> JobRecord {
+    let started_at = calendar::now_taipei().to_rfc3339();
+    let mut attempts = 0;
+    let (status, message) = loop {
+        attempts += 1;
+        log(&format!("{date} 第 {attempts} 次執行"));
+        match attempt(company_map, date).await {
+            Ok(result) => break result,
+            Err(e) if attempts > options.max_retries => break (JobStatus::Failed, e),
+            Err(e) => {
+                log(&format!(
+                    "{date} 失敗: {e}，{} 分鐘後重試",
+                    options.retry_interval.as_secs() / 60
+                ));
+                time::sleep(options.retry_interval).await;
+            }
+        }
+    };
+    log(&format!("{date} {status:?}: {message}"));
+
+    JobRecord {
+        date: date.to_string(),
+        status,
+        attempts,
+        started_at,
+        finished_at: calendar::now_taipei().to_rfc3339(),
+        message,
+    }
+}
+
+/// 最近 catch_up_days 天內尚未完成的交易日 (YYYYMMDD，由舊到新)；今天要過了 run_at 才算
+fn pending_dates(
+    calendar: &Calendar,
+    history: &JobHistory,
+    now: DateTime<Tz>,
+    options: &DaemonOptions,
+) -> Vec<String> {
+    let today = now.date_naive();
+    calendar
+        .trading_days_until(today, options.catch_up_days)
+        .into_iter()
+        .filter(|date| *date < today || now.time() >= options.run_at)
+        .map(calendar::to_date_str)
+        .filter(|date| !history.is_done(date))
+        .collect()
+}
+
+/// 台北時間每個交易日 run_at 之後執行每日工作；啟動時先補跑最近 catch_up_days 天內未成功的交易日
+pub async fn run(company_map: CompanyMap, options: &DaemonOptions) -> Result<(), Box<dyn Error>> {
+    let company_map = Arc::new(company_map);
+
+    loop {
+        let calendar = Calendar::load();
+        let mut history = JobHistory::load();
+
+        for date in pending_dates(&calendar, &history, calendar::now_taipei(), options) {
+            let record = run_with_retries(&company_map, &date, options).await;
+            history.record(record);
+            history.save()?;
+        }
+
+        if options.once {
+            return Ok(());
+        }
+
+        let next = calendar.next_run(calendar::now_taipei(), options.run_at);
+        log(&format!("下次執行: {}", next.format("%Y-%m-%d %H:%M")));
+        let wait = (next - calendar::now_taipei()).to_std().unwrap_or_default();
+        time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+    use chrono_tz::Asia::Taipei;
+
+    use super::*;
+
+    fn options(catch_up_days: u32) -> DaemonOptions {
+        DaemonOptions {
+            run_at: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            retry_interval: Duration::from_secs(60),
+            max_retries: 0,
+            catch_up_days,
+            once: true,
+        }
+    }
+
+    fn record(date: &str, status: JobStatus) -> JobRecord {
+        JobRecord {
+            date: date.to_string(),
+            status,
+            attempts: 1,
+            started_at: String::new(),
+            finished_at: String::new(),
+            message: String::new(),
+        }
+    }
+
+    fn calendar() -> Calendar {
+        Calendar::new(vec![NaiveDate::from_ymd_opt(2026, 2, 16).unwrap()])
+    }
+
+    #[test]
+    fn today_is_pending_only_after_run_at() {
+        let history = JobHistory::default();
+        let before = Taipei.with_ymd_and_hms(2026, 2, 18, 9, 0, 0).unwrap();
+        let after = Taipei.with_ymd_and_hms(2026, 2, 18, 18, 30, 0).unwrap();
+        assert_eq!(
+            pending_dates(&calendar(), &history, before, &options(7)),
+            vec!["20260212", "20260213", "20260217"]
+        );
+        assert_eq!(
+            pending_dates(&calendar(), &history, after, &options(7)),
+            vec!["20260212", "20260213", "20260217", "20260218"]
+        );
+    }
+
+    #[test]
+    fn successful_and_skipped_days_are_not_retried() {
+        let mut history = JobHistory::default();
+        history.record(record("20260212", JobStatus::Success));
+        history.record(record("20260213", JobStatus::Skipped));
+        history.record(record("20260217", JobStatus::Failed));
+        let now = Taipei.with_ymd_and_hms(2026, 2, 18, 9, 0, 0).unwrap();
+        assert_eq!(
+            pending_dates(&calendar(), &history, now, &options(7)),
+            vec!["20260217"]
+        );
+    }
+
+    #[test]
+    fn catch_up_window_limits_pending_days() {
+        let history = JobHistory::default();
+        let now = Taipei.with_ymd_and_hms(2026, 2, 18, 18, 30, 0).unwrap();
+        assert_eq!(
+            pending_dates(&calendar(), &history, now, &options(2)),
+            vec!["20260217", "20260218"]
+        );
+    }
+
+    #[test]
+    fn newer_record_replaces_older_for_the_same_day() {
+        let mut history = JobHistory::default();
+        history.record(record("20260217", JobStatus::Failed));
+        history.record(record("20260217", JobStatus::Skipped));
+        assert_eq!(history.records.len(), 1);
+        assert!(history.is_done("20260217"));
+    }
+}
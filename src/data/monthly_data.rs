@@ -1,3 +1,4 @@
+use std::error::Error;
 use std::fmt;
 use std::fs;
 use std::fs::File;
@@ -7,10 +8,19 @@ use chrono::{Datelike, Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 
 use crate::common;
+use crate::config;
+use crate::tpex;
 use crate::twse;
-use crate::twse::company_map::CompanyMap;
+use crate::twse::company_map::{CompanyMap, Market};
 
-const DATA_DIR: &str = "data";
+/// 日資料存放目錄，見設定檔 [data] dir
+pub fn data_dir() -> &'static str {
+    &config::get().data.dir
+}
+
+fn storage_path(stock_no: &str, year_month: &str) -> String {
+    format!("{}/{stock_no}/{year_month}.json", data_dir())
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyData {
@@ -26,23 +36,6 @@ pub struct DailyData {
     pub note: String,      //備註
 }
 
-impl DailyData {
-    pub fn print(&self, company_map: &CompanyMap, stock_no: &str) {
-        println!(
-            "{:<10}{:<6}{:>10}{:>8.2}{:>8.2}{:>8.2}{:>8.2}{:>8.2}  {:<20}",
-            self.date,
-            stock_no,
-            common::format_commas(common::divide_by_1000(self.volume)),
-            self.open,
-            self.close,
-            self.high,
-            self.low,
-            self.change,
-            company_map.get(stock_no),
-        );
-    }
-}
-
 impl fmt::Display for DailyData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -67,11 +60,16 @@ pub struct MonthlyData {
 }
 
 impl MonthlyData {
-    pub async fn new(company_map: &CompanyMap, stock_no: &str, year_month: &str) -> Self {
+    /// 優先讀取本地檔案，沒有、無法解析或當月資料過時才向 TWSE 抓取
+    pub async fn new(
+        company_map: &CompanyMap,
+        stock_no: &str,
+        year_month: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let mut fetch_again = false;
 
         // if file is not exists or cannot be parsed, fetch again
-        let path = format!("{DATA_DIR}/{stock_no}/{year_month}.json");
+        let path = storage_path(stock_no, year_month);
         if let Ok(file) = File::open(&path) {
             let reader = std::io::BufReader::new(file);
             if let Ok(monthly_data) = serde_json::from_reader::<_, MonthlyData>(reader) {
@@ -85,85 +83,97 @@ impl MonthlyData {
                         .iter()
                         .any(|d| d.date == current_roc_date)
                     {
-                        println!(
+                        common::log(&format!(
                             "Data {} for {stock_no}/{year_month} is outdated, fetching fresh data...",
                             current_roc_date
-                        );
+                        ));
                         fetch_again = true;
                     }
                 }
             } else {
-                println!(
+                common::log(&format!(
                     "Failed to parse data from storage for {stock_no}/{year_month}, fetching fresh data..."
-                );
+                ));
                 fetch_again = true;
             }
         } else {
-            println!("No stored data for {stock_no}/{year_month}, fetching fresh data...");
+            common::log(&format!(
+                "No stored data for {stock_no}/{year_month}, fetching fresh data..."
+            ));
             fetch_again = true;
         }
 
         if fetch_again {
-            let twse_response =
-                twse::stock_data::TwseResponse::new(company_map, stock_no, year_month).await;
-
-            let mut daily_data = Vec::new();
-            for entry in twse_response.data.unwrap_or_default() {
-                // 113/02/01", "46,924,943", "29,237,425,981", "625.00", "628.00", "619.00", "628.00", " 0.00", "51,671", ""
-                // println!("Raw entry: {:?}", entry);
-                let daily_data_item = DailyData {
-                    date: entry[0].clone(),
-                    volume: entry[1].replace(",", "").parse().unwrap_or(0),
-                    amount: entry[2].replace(",", "").parse().unwrap_or(0),
-                    open: entry[3].replace(",", "").parse().unwrap_or(0.0),
-                    high: entry[4].replace(",", "").parse().unwrap_or(0.0),
-                    close: entry[6].replace(",", "").parse().unwrap_or(0.0),
-                    low: entry[5].replace(",", "").parse().unwrap_or(0.0),
-                    change: entry[7].replace(",", "").parse().unwrap_or(0.0),
-                    transactions: entry[8].replace(",", "").parse().unwrap_or(0),
-                    note: entry[9].clone(),
-                };
-
-                daily_data.push(daily_data_item);
-            }
-
-            let monthly_data = MonthlyData {
-                stock_no: stock_no.to_string(),
-                year_month: year_month.to_string(),
-                daily_data,
-            };
-
-            monthly_data.write_to_storage();
-            monthly_data
+            Self::fetch(company_map, stock_no, year_month).await
         } else {
             // read from storage
-            let path = format!("{DATA_DIR}/{stock_no}/{year_month}.json");
-            let file = File::open(&path).unwrap();
+            let path = storage_path(stock_no, year_month);
+            let file = File::open(&path)?;
             let reader = std::io::BufReader::new(file);
-            serde_json::from_reader::<_, MonthlyData>(reader).unwrap()
+            Ok(serde_json::from_reader::<_, MonthlyData>(reader)?)
         }
     }
 
-    pub fn write_to_storage(&self) {
-        // if DATA_DIR does not exist, create it
-        if fs::metadata(DATA_DIR).is_err() {
-            fs::create_dir_all(DATA_DIR).unwrap();
+    /// 掃描多檔時使用：讀取失敗只記錄錯誤，當作該月沒有資料
+    pub async fn new_or_empty(company_map: &CompanyMap, stock_no: &str, year_month: &str) -> Self {
+        match Self::new(company_map, stock_no, year_month).await {
+            Ok(monthly_data) => monthly_data,
+            Err(e) => {
+                common::log(&format!(
+                    "Failed to load data for {stock_no}/{year_month}: {e}"
+                ));
+                MonthlyData {
+                    stock_no: stock_no.to_string(),
+                    year_month: year_month.to_string(),
+                    daily_data: Vec::new(),
+                }
+            }
         }
+    }
 
-        // create stock_no folder if not exists
-        let stock_folder = format!("{}/{}", DATA_DIR, self.stock_no);
-        if fs::metadata(&stock_folder).is_err() {
-            fs::create_dir_all(&stock_folder).unwrap();
-        }
+    /// 不看本地檔案，直接向 TWSE (上櫃股票向 TPEx) 抓取並寫入本地
+    pub async fn fetch(
+        company_map: &CompanyMap,
+        stock_no: &str,
+        year_month: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let daily_data = match company_map.market(stock_no) {
+            Market::Twse => fetch_twse_daily_data(company_map, stock_no, year_month).await?,
+            Market::Tpex => tpex::stock_data::fetch_daily_data(stock_no, year_month).await?,
+        };
+
+        let monthly_data = MonthlyData {
+            stock_no: stock_no.to_string(),
+            year_month: year_month.to_string(),
+            daily_data,
+        };
+
+        monthly_data.write_to_storage()?;
+        Ok(monthly_data)
+    }
+
+    /// 只從本地檔案讀取，不會向 TWSE 抓取
+    pub fn from_storage(stock_no: &str, year_month: &str) -> Option<Self> {
+        let path = storage_path(stock_no, year_month);
+        let file = File::open(&path).ok()?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader::<_, MonthlyData>(reader).ok()
+    }
+
+    pub fn write_to_storage(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // create data_dir/stock_no folder if not exists
+        let stock_folder = format!("{}/{}", data_dir(), self.stock_no);
+        fs::create_dir_all(&stock_folder)?;
 
-        let path = format!("{}/{}/{}.json", DATA_DIR, self.stock_no, self.year_month);
-        println!("Writing data to {}", path);
+        let path = storage_path(&self.stock_no, &self.year_month);
+        common::log(&format!("Writing data to {}", path));
 
         // Implementation for writing data to storage
-        let file = File::create(path).unwrap();
+        let file = File::create(path)?;
         let writer = BufWriter::new(file);
 
-        serde_json::to_writer_pretty(writer, self).unwrap();
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
     }
 
     /*
@@ -185,6 +195,123 @@ impl MonthlyData {
      */
 }
 
+/// TWSE 個股日成交資訊，欄位已是股與元
+async fn fetch_twse_daily_data(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month: &str,
+) -> Result<Vec<DailyData>, Box<dyn Error + Send + Sync>> {
+    let twse_response =
+        twse::stock_data::TwseResponse::new(company_map, stock_no, year_month).await?;
+
+    let mut daily_data = Vec::new();
+    for entry in twse_response.data.unwrap_or_default() {
+        // 113/02/01", "46,924,943", "29,237,425,981", "625.00", "628.00", "619.00", "628.00", " 0.00", "51,671", ""
+        // println!("Raw entry: {:?}", entry);
+        if entry.len() < 10 {
+            continue;
+        }
+        let daily_data_item = DailyData {
+            date: entry[0].clone(),
+            volume: entry[1].replace(",", "").parse().unwrap_or(0),
+            amount: entry[2].replace(",", "").parse().unwrap_or(0),
+            open: entry[3].replace(",", "").parse().unwrap_or(0.0),
+            high: entry[4].replace(",", "").parse().unwrap_or(0.0),
+            close: entry[6].replace(",", "").parse().unwrap_or(0.0),
+            low: entry[5].replace(",", "").parse().unwrap_or(0.0),
+            change: entry[7].replace(",", "").parse().unwrap_or(0.0),
+            transactions: entry[8].replace(",", "").parse().unwrap_or(0),
+            note: entry[9].clone(),
+        };
+
+        daily_data.push(daily_data_item);
+    }
+
+    Ok(daily_data)
+}
+
+/// 從本地檔案讀取一段月份的日資料 (依日期排序)，缺少的月份直接略過
+pub fn load_daily_data_range(
+    stock_no: &str,
+    year_month_from: &str,
+    year_month_to: &str,
+) -> Vec<DailyData> {
+    let mut daily_data = Vec::new();
+    for year_month in common::year_months(year_month_from, year_month_to) {
+        if let Some(monthly_data) = MonthlyData::from_storage(stock_no, &year_month) {
+            daily_data.extend(monthly_data.daily_data);
+        }
+    }
+    daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
+    daily_data
+}
+
+/// 讀取一段月份的日資料 (依日期排序)，本地沒有的月份會向 TWSE 抓取，抓取失敗的月份略過
+pub async fn fetch_daily_data_range(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month_from: &str,
+    year_month_to: &str,
+) -> Vec<DailyData> {
+    let mut daily_data = Vec::new();
+    for year_month in common::year_months(year_month_from, year_month_to) {
+        let monthly_data = MonthlyData::new_or_empty(company_map, stock_no, &year_month).await;
+        daily_data.extend(monthly_data.daily_data);
+    }
+    daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
+    daily_data
+}
+
+/// 本地有資料的所有股號 (依股號排序)
+pub fn stored_stock_nos() -> Vec<String> {
+    let mut stock_nos: Vec<String> = fs::read_dir(data_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    stock_nos.sort();
+    stock_nos
+}
+
+/// 本地資料中最新的一筆日資料
+pub fn latest_stored_daily_data(stock_no: &str) -> Option<DailyData> {
+    recent_stored_daily_data(stock_no, 1).pop()
+}
+
+/// 本地資料中最近 days 筆日資料 (依日期排序)，資料不足時回傳全部
+pub fn recent_stored_daily_data(stock_no: &str, days: usize) -> Vec<DailyData> {
+    let Ok(entries) = fs::read_dir(format!("{}/{stock_no}", data_dir())) else {
+        return Vec::new();
+    };
+    let mut year_months: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .path()
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .collect();
+    year_months.sort();
+
+    let mut daily_data = Vec::new();
+    for year_month in year_months.iter().rev() {
+        if daily_data.len() >= days {
+            break;
+        }
+        if let Some(monthly_data) = MonthlyData::from_storage(stock_no, year_month) {
+            daily_data.extend(monthly_data.daily_data);
+        }
+    }
+    daily_data.sort_by(|a, b| a.date.cmp(&b.date)); // 按日期排序
+    let skip = daily_data.len().saturating_sub(days);
+    daily_data.split_off(skip)
+}
+
 fn is_in_year_month(year_month: &str) -> bool {
     // 取得今天日期
     let today = Local::now().date_naive();
@@ -0,0 +1,47 @@
+//! 台股資料抓取與分析函式庫
+//!
+//! - [`twse::company_map::CompanyMap`]：上市公司清單與產業對照
+//! - [`data::monthly_data`]：日資料的讀取、向 TWSE 抓取與本地儲存
+//! - [`analysis::indicators`]：MA / MACD / KD 等指標
+//! - [`screener`]：選股條件的解析、求值與儲存
+//! - [`scripts`]：對整個公司清單執行的掃描
+//!
+//! 函式庫不會 panic (編譯時內建的產業表除外，由單元測試檢查) 也不會輸出到 stdout；
+//! 網路與檔案錯誤以 `Result` 回傳，
+//! 掃描多檔時單檔的錯誤只透過 [`common::log`] 記錄並略過。
+//! 日期參數 (YYYYMM / YYYYMMDD) 格式不符時，回傳 `Option` / `Result` 的函式回傳
+//! `None` / `Err`，掃描函式回傳空結果；可先以 [`common::is_valid_year_month`]、
+//! [`common::is_valid_date`] 檢查。
+//!
+//! ```no_run
+//! use stock::scripts;
+//! use stock::screener;
+//! use stock::twse::company_map::CompanyMap;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let company_map = CompanyMap::new().await?;
+//! let expr = screener::parser::parse("close > ma(20)")?;
+//! let results =
+//!     scripts::screener::screen_date_all_companies(&company_map, &expr, "20260205").await;
+//! println!("{} 檔符合", results.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod alerts;
+pub mod analysis;
+pub mod chart;
+pub mod common;
+pub mod config;
+pub mod consts;
+pub mod daemon;
+pub mod data;
+pub mod output;
+pub mod portfolio;
+pub mod report;
+pub mod screener;
+pub mod scripts;
+pub mod server;
+pub mod tpex;
+pub mod twse;
+pub mod universe;
@@ -1,14 +1,67 @@
-mod analysis;
-mod common;
-mod consts;
-mod data;
+mod cli;
 mod menu;
-mod scripts;
-mod twse;
+mod tui;
+
+use stock::config::{self, Config};
+use stock::twse::company_map::CompanyMap;
+
+use crate::cli::args::Command;
 
 #[tokio::main]
 async fn main() {
-    let company_map = twse::company_map::CompanyMap::new().await;
+    let cli = cli::args::Cli::parse_checked();
+
+    match Config::load(&cli.config, &cli.overrides) {
+        Ok(config) => config::init(config),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+
+    // refresh-companies 自己抓取並與上次回報的清單比對，不需要先建立 CompanyMap
+    if let Some(Command::RefreshCompanies) = cli.command {
+        if let Err(e) = menu::actions::refresh_companies().await {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let company_map = if cli.offline {
+        CompanyMap::from_cache()
+    } else {
+        CompanyMap::new().await
+    };
+    let company_map = match company_map {
+        Ok(map) => map,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
 
-    menu::main_menu::main_menu(&company_map).await;
+    match cli.command {
+        Some(command) => {
+            if let Err(e) = cli::commands::run(command, &company_map, &cli.scope, cli.output).await
+            {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        None => {
+            // 全螢幕介面只顯示 scope 內的股票
+            let scoped_map = match cli.scope.apply(&company_map) {
+                Ok(map) => map,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+            if let Err(e) = tui::app::run(scoped_map) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -0,0 +1,592 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use stock::alerts;
+use stock::alerts::evaluator::AlertRun;
+use stock::analysis;
+use stock::analysis::sector::GroupBy;
+use stock::analysis::sweep::SweepRow;
+use stock::chart;
+use stock::chart::candlestick::ChartOptions;
+use stock::chart::export::ChartFormat;
+use stock::common;
+use stock::config;
+use stock::output::record::Record;
+use stock::output::render::{self, OutputFormat};
+use stock::output::table::{self, Align, Cell, Color, Table};
+use stock::portfolio;
+use stock::report;
+use stock::screener;
+use stock::scripts;
+use stock::twse::company_map::{CompanyInfo, CompanyMap};
+use stock::twse::industry;
+use stock::universe::filter::UniverseFilter;
+use stock::universe::watchlist;
+
+pub fn print_line() {
+    println!("--------------------------------------------------------------------------------");
+}
+
+pub async fn fetch_data(company_map: &CompanyMap, year_month: &str) -> Result<(), Box<dyn Error>> {
+    let alert_run =
+        scripts::data::fetch_data_monthly_and_alerts(company_map, year_month, |_, _| {})
+            .await
+            .map_err(|e| format!("警示評估失敗: {e}"))?;
+    print_alert_run(&alert_run)
+}
+
+pub async fn check_alerts(
+    company_map: &CompanyMap,
+    year_month: &str,
+) -> Result<(), Box<dyn Error>> {
+    let alert_run = alerts::evaluator::run(company_map, year_month)
+        .await
+        .map_err(|e| format!("警示評估失敗: {e}"))?;
+    print_alert_run(&alert_run)
+}
+
+/// 印出送到 Stdout 目標的警示與新警示數，有目標送出失敗時回傳錯誤
+fn print_alert_run(alert_run: &AlertRun) -> Result<(), Box<dyn Error>> {
+    for alert in &alert_run.stdout {
+        println!("🔔 {alert}");
+    }
+    println!("新警示 {} 筆", alert_run.new_alerts.len());
+    match alert_run.delivery_error() {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+pub fn refactor_data() -> Result<(), Box<dyn Error>> {
+    let removed = scripts::data::clean_stored_data().map_err(|e| format!("整理資料失敗: {e}"))?;
+    for (stock_no, daily) in &removed {
+        println!(
+            "已移除: {stock_no} date={} open={} high={} low={} close={}",
+            daily.date, daily.open, daily.high, daily.low, daily.close
+        );
+    }
+    println!("共移除 {} 筆", removed.len());
+    Ok(())
+}
+
+/// 表格模式加上分隔線，其他格式只輸出資料
+fn print_results<R: Record>(company_map: &CompanyMap, records: &[R], format: OutputFormat) {
+    if format.is_table() {
+        print_line();
+    }
+    print_records(records, company_map, format);
+    if format.is_table() {
+        print_line();
+    }
+}
+
+/// 依格式輸出一組結果到 stdout
+fn print_records<R: Record>(records: &[R], company_map: &CompanyMap, format: OutputFormat) {
+    print!(
+        "{}",
+        render::render(records, company_map, format, table::color_enabled())
+    );
+}
+
+pub async fn long_red_candle(company_map: &CompanyMap, date: &str, format: OutputFormat) {
+    let results = analysis::long_red_candle::anal_date_all_companies(company_map, date).await;
+
+    print_results(company_map, &results, format);
+}
+
+pub async fn macd_golden_cross(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+    date: &str,
+    format: OutputFormat,
+) {
+    let crosses =
+        scripts::macd::anal_date_all_companies(company_map, year_month_from, year_month_to, date)
+            .await;
+    let golden_crosses: Vec<_> = crosses
+        .into_iter()
+        .filter(|cross| cross.cross_type == analysis::macd::MacdCrossType::GoldenCross)
+        .collect();
+
+    print_results(company_map, &golden_crosses, format);
+}
+
+pub async fn volume_larger(
+    company_map: &CompanyMap,
+    date: &str,
+    volume_threshold: u64,
+    format: OutputFormat,
+) {
+    let volume_results =
+        scripts::volume::volume_larger_than_threshold(company_map, volume_threshold, date).await;
+
+    print_results(company_map, &volume_results, format);
+}
+
+pub async fn macd_golden_cross_volume_larger(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+    date: &str,
+    volume_threshold: u64,
+    format: OutputFormat,
+) {
+    let results = scripts::complex::anal_macd_golden_volume_larger_date(
+        company_map,
+        year_month_from,
+        year_month_to,
+        date,
+        volume_threshold,
+    )
+    .await;
+
+    if format.is_table() {
+        println!(
+            "{} MACD 黃金交叉且成交量大:",
+            common::to_roc_date(date).unwrap_or_default()
+        );
+    }
+    print_results(company_map, &results, format);
+}
+
+pub async fn doji(company_map: &CompanyMap, date: &str, format: OutputFormat) {
+    let results = scripts::doji::anal_date_all_companies(company_map, date).await;
+
+    print_results(company_map, &results, format);
+}
+
+pub async fn doji_in_swing(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+    date: &str,
+    format: OutputFormat,
+) {
+    let results = scripts::complex::anal_doji_in_swing_all_companies(
+        company_map,
+        year_month_from,
+        year_month_to,
+        date,
+    )
+    .await;
+
+    print_results(company_map, &results, format);
+}
+
+pub async fn bullish_engulfing_range(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+    format: OutputFormat,
+) {
+    let results = scripts::bullish_engulfing_pattern::anal_range_all_companies(
+        company_map,
+        year_month_from,
+        year_month_to,
+    )
+    .await;
+
+    print_results(company_map, &results, format);
+}
+
+pub async fn bullish_engulfing_date(company_map: &CompanyMap, date: &str, format: OutputFormat) {
+    let results =
+        scripts::bullish_engulfing_pattern::anal_date_all_companies(company_map, date).await;
+
+    print_results(company_map, &results, format);
+}
+
+pub struct SweepOptions {
+    pub strategy: analysis::sweep::Strategy,
+    pub year_month_from: String,
+    pub year_month_to: String,
+    pub horizon: usize,
+    pub ranges: Vec<analysis::sweep::ParamRange>,
+    pub min_signals: usize,
+    pub folds: usize,
+}
+
+/// 表格只列前 20 名，其他格式輸出全部組合；前進式最佳化的各段接在排名之後
+pub fn parameter_sweep(
+    company_map: &CompanyMap,
+    options: &SweepOptions,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let strategy = options.strategy;
+    let grid = analysis::sweep::param_grid(&options.ranges);
+    let all_series = scripts::sweep::load_all_companies(
+        company_map,
+        &options.year_month_from,
+        &options.year_month_to,
+    );
+    if all_series.is_empty() {
+        return Err(format!(
+            "{}~{} 本地沒有資料",
+            options.year_month_from, options.year_month_to
+        )
+        .into());
+    }
+    if format.is_table() {
+        println!(
+            "{}: {} 組參數, {} 檔股票",
+            strategy.name(),
+            grid.len(),
+            all_series.len()
+        );
+    }
+
+    let results = analysis::sweep::sweep(
+        strategy,
+        &grid,
+        &all_series,
+        options.horizon,
+        None,
+        None,
+        options.min_signals,
+    );
+    let walk_forward = analysis::sweep::walk_forward(
+        strategy,
+        &grid,
+        &all_series,
+        options.horizon,
+        options.folds,
+        options.min_signals,
+    );
+    if walk_forward.is_none() && options.folds > 0 && format.is_table() {
+        println!("交易日不足 {} 段，略過前進式最佳化", options.folds + 1);
+    }
+    let walk_forward = walk_forward.unwrap_or_default();
+
+    let mut rows = SweepRow::ranked(strategy, &results);
+    let fold_rows = SweepRow::folds(strategy, &walk_forward);
+    if !format.is_table() {
+        rows.extend(fold_rows);
+        print_records(&rows, company_map, format);
+        return Ok(());
+    }
+
+    rows.truncate(20);
+    print_results(company_map, &rows, format);
+    if !fold_rows.is_empty() {
+        println!("前進式最佳化 (樣本內最佳參數 → 樣本外驗證):");
+        print_records(&fold_rows, company_map, format);
+        print_line();
+    }
+    Ok(())
+}
+
+pub fn portfolio(company_map: &CompanyMap, format: OutputFormat) -> Result<(), Box<dyn Error>> {
+    let path = config::path(portfolio::holdings::HOLDINGS_FILE);
+    let holdings = portfolio::holdings::load_holdings(&path)
+        .map_err(|e| format!("讀取 {} 失敗: {e}", path.display()))?;
+
+    let mut portfolio = portfolio::positions::Portfolio::from_holdings(&holdings, company_map)
+        .map_err(|e| format!("交易紀錄有誤: {e}"))?;
+    portfolio.mark_to_market();
+
+    let positions: Vec<_> = portfolio.open_positions().cloned().collect();
+    print_results(company_map, &positions, format);
+    if !format.is_table() {
+        return Ok(());
+    }
+
+    println!(
+        "總成本: {}  總市值: {}",
+        common::format_commas(portfolio.total_cost() as u64),
+        common::format_commas(portfolio.total_market_value() as u64)
+    );
+    println!(
+        "未實現損益: {}  已實現損益: {}",
+        format_signed(portfolio.total_unrealized_pnl()),
+        format_signed(portfolio.total_realized_pnl())
+    );
+
+    print_line();
+    let mut table = Table::new(&[("產業", Align::Left), ("權重", Align::Right)]);
+    for (industry, weight) in portfolio.industry_weights(company_map) {
+        table.push(vec![
+            Cell::new(industry),
+            Cell::new(format!("{:.2}%", weight * 100.0)),
+        ]);
+    }
+    print!("{}", table.render(table::color_enabled()));
+    print_line();
+    let mut table = Table::new(&[("類股", Align::Left), ("權重", Align::Right)]);
+    for (sector, weight) in portfolio.sector_weights(company_map) {
+        table.push(vec![
+            Cell::new(sector),
+            Cell::new(format!("{:.2}%", weight * 100.0)),
+        ]);
+    }
+    print!("{}", table.render(table::color_enabled()));
+    print_line();
+    Ok(())
+}
+
+/// 帶正負號與千分位，依正負上色
+fn format_signed(value: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let text = format!(
+        "{sign}{}",
+        common::format_commas(value.abs().round() as u64)
+    );
+    table::paint(&text, Color::from_sign(value), table::color_enabled())
+}
+
+pub fn watchlist_list() {
+    print_line();
+    for name in watchlist::list() {
+        println!("{name}");
+    }
+    print_line();
+}
+
+pub fn watchlist_show(name: &str) -> Result<(), Box<dyn Error>> {
+    let stock_nos = watchlist::load(name)?;
+    print_line();
+    for stock_no in stock_nos {
+        println!("{stock_no}");
+    }
+    print_line();
+    Ok(())
+}
+
+pub fn watchlist_add(name: &str, stock_no: &str) -> Result<(), Box<dyn Error>> {
+    if watchlist::add(name, stock_no)? {
+        println!("已加入 {stock_no} 到 {name}");
+    } else {
+        println!("{stock_no} 已在 {name} 中");
+    }
+    Ok(())
+}
+
+pub fn watchlist_remove(name: &str, stock_no: &str) -> Result<(), Box<dyn Error>> {
+    if watchlist::remove(name, stock_no)? {
+        println!("已從 {name} 移除 {stock_no}");
+    } else {
+        println!("{stock_no} 不在 {name} 中");
+    }
+    Ok(())
+}
+
+pub fn watchlist_delete(name: &str) -> Result<(), Box<dyn Error>> {
+    watchlist::delete(name)?;
+    println!("已刪除清單 {name}");
+    Ok(())
+}
+
+pub async fn screen(
+    company_map: &CompanyMap,
+    expression: &str,
+    date: &str,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let expr = screener::parser::parse(expression).map_err(|e| format!("條件錯誤: {e}"))?;
+
+    let results = scripts::screener::screen_date_all_companies(company_map, &expr, date).await;
+
+    if format.is_table() {
+        println!("{expression}");
+    }
+    print_results(company_map, &results, format);
+    Ok(())
+}
+
+pub fn save_screen(name: &str, expression: &str) -> Result<(), Box<dyn Error>> {
+    screener::saved::save(name, expression).map_err(|e| format!("儲存失敗: {e}"))?;
+    println!("已儲存 {name}");
+    Ok(())
+}
+
+/// 繪製 K 線圖並寫出檔案，未指定路徑時寫到 charts/
+pub async fn chart(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month_from: &str,
+    year_month_to: &str,
+    options: &ChartOptions,
+    format: ChartFormat,
+    path: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let (daily_data, start) =
+        chart::candlestick::load(company_map, stock_no, year_month_from, year_month_to).await;
+    if start >= daily_data.len() {
+        return Err(format!("{stock_no} 在 {year_month_from}~{year_month_to} 沒有資料").into());
+    }
+
+    let title = format!(
+        "{stock_no} {} {year_month_from}~{year_month_to}",
+        company_map.get_name(stock_no)
+    );
+    let svg = chart::candlestick::render_svg(&title, &daily_data, start, options);
+    let path = path.unwrap_or_else(|| {
+        chart::export::default_path(stock_no, year_month_from, year_month_to, format)
+    });
+    chart::export::write(&svg, format, &path)?;
+    println!("已輸出 {}", path.display());
+    Ok(())
+}
+
+/// 終端機 K 線圖，只讀本地資料
+pub fn terminal_chart(company_map: &CompanyMap, stock_no: &str, days: usize, height: usize) {
+    let daily_data = chart::terminal::recent_daily_data(stock_no, days);
+    let Some(last) = daily_data.last() else {
+        println!("{stock_no} 本地沒有資料");
+        return;
+    };
+
+    let colored = table::color_enabled();
+    print_line();
+    println!(
+        "{stock_no} {} 最近 {} 個交易日  收盤 {:.2}  漲跌 {}",
+        company_map.get_name(stock_no),
+        daily_data.len(),
+        last.close,
+        table::paint(
+            &format!("{:.2}", last.change),
+            Color::from_sign(last.change),
+            colored
+        )
+    );
+    print!(
+        "{}",
+        chart::terminal::render_candles(&daily_data, height, colored)
+    );
+    print_line();
+}
+
+/// 依相符程度列出公司
+pub fn companies(company_map: &CompanyMap, keyword: &str, format: OutputFormat) {
+    let companies: Vec<CompanyInfo> = company_map.search(keyword).into_iter().cloned().collect();
+    print_results(company_map, &companies, format);
+}
+
+/// 列出建立公司清單時排除的公司，以及目前因市值、日均量條件排除的公司
+pub fn universe_exclusions(
+    company_map: &CompanyMap,
+    filter: &UniverseFilter,
+    format: OutputFormat,
+) {
+    let mut excluded = company_map.excluded().to_vec();
+    excluded.extend(filter.data_exclusions(company_map));
+    excluded.sort_by(|a, b| a.company.stock_no.cmp(&b.company.stock_no));
+    print_results(company_map, &excluded, format);
+}
+
+fn print_companies(title: &str, companies: &[CompanyInfo]) {
+    println!("{title} {} 檔", companies.len());
+    for company in companies {
+        println!(
+            "  {:<6} {} ({})",
+            company.stock_no, company.name, company.market
+        );
+    }
+}
+
+/// 重新抓取公司清單並更新快取
+pub async fn refresh_companies() -> Result<(), Box<dyn Error>> {
+    let changes = CompanyMap::refresh().await?;
+    match &changes.previous_fetched_at {
+        Some(fetched_at) => println!("公司清單已更新，與 {fetched_at} 的清單相比:"),
+        None => {
+            println!("公司清單已更新 (之前沒有快取)");
+            return Ok(());
+        }
+    }
+    print_companies("新上市/上櫃", &changes.listed);
+    print_companies("下市/下櫃", &changes.delisted);
+    println!("產業別異動 {} 檔", changes.reassigned.len());
+    for change in &changes.reassigned {
+        println!("  {:<6} {} → {}", change.stock_no, change.from, change.to);
+    }
+
+    let unknown = CompanyMap::from_cache()?.unknown_industries();
+    if !unknown.is_empty() {
+        println!("產業表沒有的產業代號 (請更新 {}):", industry::INDUSTRY_FILE);
+        for (code, count) in unknown {
+            println!("  {code} ({count} 檔)");
+        }
+    }
+    Ok(())
+}
+
+/// 各類股/產業當日表現
+pub fn sector_performance(
+    company_map: &CompanyMap,
+    date: &str,
+    group_by: GroupBy,
+    top: usize,
+    format: OutputFormat,
+) {
+    let rows = analysis::sector::performance(company_map, date, group_by, top);
+    print_results(company_map, &rows, format);
+}
+
+/// 市場寬度序列，指定 chart 時另外輸出圖表
+pub fn breadth(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+    chart_format: Option<ChartFormat>,
+    path: Option<PathBuf>,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let series = analysis::breadth::series(company_map, year_month_from, year_month_to);
+    if series.is_empty() {
+        return Err(format!("{year_month_from}~{year_month_to} 本地沒有資料").into());
+    }
+    print_results(company_map, &series, format);
+
+    if let Some(chart_format) = chart_format {
+        let title = format!("市場寬度 {year_month_from}~{year_month_to}");
+        let svg = chart::breadth::render_svg(&title, &series, 1200, 800);
+        let path = path.unwrap_or_else(|| {
+            chart::export::default_path("breadth", year_month_from, year_month_to, chart_format)
+        });
+        chart::export::write(&svg, chart_format, &path)?;
+        println!("已輸出 {}", path.display());
+    }
+    Ok(())
+}
+
+/// 列出產業表與各產業的公司數，export 時把自訂項目匯出到 industries.toml
+pub fn industries(
+    company_map: &CompanyMap,
+    export: bool,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    if export {
+        let path = config::path(industry::INDUSTRY_FILE);
+        let overrides = company_map.taxonomy().overrides();
+        overrides.save(&path)?;
+        println!(
+            "已匯出 {} 個自訂產業到 {}",
+            overrides.industries.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    print_results(company_map, &company_map.industry_summary(), format);
+    Ok(())
+}
+
+/// 執行每日選股條件並寫出 Markdown/HTML 報告
+pub async fn daily_report(company_map: &CompanyMap, date: &str) -> Result<(), Box<dyn Error>> {
+    let path = config::path(report::config::REPORT_FILE);
+    let config = report::config::ReportConfig::load(&path)?;
+    if config.resolved_screens().is_empty() {
+        return Err(format!(
+            "沒有可執行的選股條件，請在 {} 設定或先儲存條件",
+            path.display()
+        )
+        .into());
+    }
+
+    let daily_report = report::daily::build(company_map, &config, date).await?;
+    for path in report::daily::write(&daily_report)? {
+        println!("已輸出 {}", path.display());
+    }
+    Ok(())
+}
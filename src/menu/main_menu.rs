@@ -1,21 +1,23 @@
-use std::fs;
-use std::fs::File;
 use std::io;
-use std::path::Path;
 
-use crate::analysis;
-use crate::common;
-use crate::data::monthly_data::MonthlyData;
-use crate::scripts;
-use crate::twse::company_map::CompanyMap;
+use stock::analysis;
+use stock::chart::candlestick::ChartOptions;
+use stock::chart::export::ChartFormat;
+use stock::common;
+use stock::output::render::OutputFormat;
+use stock::screener;
+use stock::twse::company_map::{CompanyMap, Market};
+use stock::universe::scope::Scope;
+use stock::universe::watchlist;
 
-pub fn print_line() {
-    println!("--------------------------------------------------------------------------------");
-}
+use crate::menu::actions;
 
 pub async fn main_menu(company_map: &CompanyMap) {
+    let mut scope = Scope::All;
+    let mut scoped_map = company_map.filter(|_| true);
+
     loop {
-        println!("Main Menu");
+        println!("Main Menu (掃描範圍: {scope})");
         println!("每日工作: 1/2/6/8/10");
         println!("1. 抓取 TWSE 資料");
         println!("2. 整理資料");
@@ -27,28 +29,59 @@ pub async fn main_menu(company_map: &CompanyMap) {
         println!("8. 單日十字線波段驗證");
         println!("9. 多日陽吞噬形態");
         println!("10. 單日陽吞噬形態");
+        println!("11. 參數掃描與前進式最佳化");
+        println!("12. 投資組合損益");
+        println!("13. 自選股管理");
+        println!("14. 設定掃描範圍 (全市場/自選股/產業)");
+        println!("15. 檢查警示");
+        println!("16. 自訂選股條件");
+        println!("17. 繪製 K 線圖");
+        println!("18. 終端機 K 線圖");
+        println!("19. 產生每日報告");
 
         println!("q/e. 退出 (Quit/Exit)");
-        println!("請輸入選項：");
-
-        let mut input = String::new();
 
-        io::stdin().read_line(&mut input).expect("讀取失敗");
+        let input = read_input("請輸入選項：");
 
-        // 去掉換行符號
-        let input = input.trim();
-
-        match input {
+        match input.as_str() {
             "1" => menu_fetch_data(company_map).await,
-            "2" => menu_refactor_data(company_map).await,
-            "3" => menu_long_red_candle_analysis(company_map).await,
-            "4" => menu_macd_golden_cross_analysis(company_map).await,
-            "5" => menu_volume_larger_analysis(company_map).await,
-            "6" => menu_macd_golden_cross_volume_larger_analysis(company_map).await,
-            "7" => menu_doji_analysis(company_map).await,
-            "8" => menu_doji_in_swing_analysis(company_map).await,
-            "9" => menu_bullish_engulfing_analysis(company_map).await,
-            "10" => menu_bullish_engulfing_analysis_date(company_map).await,
+            "2" => {
+                if let Err(e) = actions::refactor_data() {
+                    println!("{e}");
+                }
+            }
+            "3" => menu_long_red_candle_analysis(&scoped_map).await,
+            "4" => menu_macd_golden_cross_analysis(&scoped_map).await,
+            "5" => menu_volume_larger_analysis(&scoped_map).await,
+            "6" => menu_macd_golden_cross_volume_larger_analysis(&scoped_map).await,
+            "7" => menu_doji_analysis(&scoped_map).await,
+            "8" => menu_doji_in_swing_analysis(&scoped_map).await,
+            "9" => menu_bullish_engulfing_analysis(&scoped_map).await,
+            "10" => menu_bullish_engulfing_analysis_date(&scoped_map).await,
+            "11" => menu_parameter_sweep(&scoped_map),
+            "12" => {
+                if let Err(e) = actions::portfolio(company_map, OutputFormat::Table) {
+                    println!("{e}");
+                }
+            }
+            "13" => menu_watchlist(),
+            "14" => {
+                if let Some(new_scope) = menu_scope() {
+                    match new_scope.apply(company_map) {
+                        Ok(map) => {
+                            println!("掃描範圍: {new_scope}，共 {} 檔", map.stock_map.len());
+                            scope = new_scope;
+                            scoped_map = map;
+                        }
+                        Err(e) => println!("{e}"),
+                    }
+                }
+            }
+            "15" => menu_alerts(company_map).await,
+            "16" => menu_screener(&scoped_map).await,
+            "17" => menu_chart(company_map).await,
+            "18" => menu_terminal_chart(company_map),
+            "19" => menu_daily_report(&scoped_map).await,
             "q" | "e" => {
                 println!("退出程式");
                 break;
@@ -60,354 +93,392 @@ pub async fn main_menu(company_map: &CompanyMap) {
     }
 }
 
-async fn menu_fetch_data(company_map: &CompanyMap) {
-    println!("請輸入月份 (YYYYMM): ");
+fn read_input(prompt: &str) -> String {
+    println!("{prompt}");
     let mut input = String::new();
 
     io::stdin().read_line(&mut input).expect("讀取失敗");
 
     // 去掉換行符號
-    let input = input.trim();
-
-    scripts::data::fetch_data_monthly_all_companies(company_map, input).await;
+    input.trim().to_string()
 }
 
-async fn menu_refactor_data(_company_map: &CompanyMap) {
-    let root = Path::new("data");
-
-    // 遞迴走訪
-    fn visit_dir(path: &Path) {
-        if let Ok(entries) = fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let p = entry.path();
-                if p.is_dir() {
-                    // 如果是子目錄，印出目錄名稱
-                    // println!("目錄: {}", p.display());
-                    // 再遞迴進去
-                    visit_dir(&p);
-                } else {
-                    // 如果是檔案，印出檔案名稱
-                    let file = File::open(&p).unwrap();
-                    let reader = std::io::BufReader::new(file);
-                    let mut monthly_data =
-                        serde_json::from_reader::<_, MonthlyData>(reader).unwrap();
-                    let mut refactor = false;
-                    for daily in &monthly_data.daily_data {
-                        if daily.open == 0.0
-                            || daily.high == 0.0
-                            || daily.low == 0.0
-                            || daily.close == 0.0
-                        {
-                            println!(
-                                "將移除: date={} open={} high={} low={} close={}",
-                                daily.date, daily.open, daily.high, daily.low, daily.close
-                            );
-                            refactor = true;
-                        }
-                    }
-                    if refactor {
-                        monthly_data.daily_data.retain(|d| {
-                            d.open != 0.0 && d.high != 0.0 && d.low != 0.0 && d.close != 0.0
-                        });
-                        monthly_data.write_to_storage();
-                    }
-                }
-            }
-        }
+fn read_year_month(prompt: &str) -> Option<String> {
+    let input = read_input(prompt);
+    if common::is_valid_year_month(&input) {
+        Some(input)
+    } else {
+        println!("無效的月份，請重新輸入。");
+        None
     }
-
-    visit_dir(root);
 }
 
-async fn menu_long_red_candle_analysis(company_map: &CompanyMap) {
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input = String::new();
-
-    io::stdin().read_line(&mut input).expect("讀取失敗");
-
-    // 去掉換行符號
-    let input = input.trim();
-
-    print_line();
-    analysis::long_red_candle::anal_date_all_companies(company_map, input).await;
-    print_line();
+fn read_date() -> Option<String> {
+    let input = read_input("請輸入日期 (YYYYMMDD): ");
+    if common::is_valid_date(&input) {
+        Some(input)
+    } else {
+        println!("無效的日期，請重新輸入。");
+        None
+    }
 }
 
-async fn menu_macd_golden_cross_analysis(company_map: &CompanyMap) {
-    println!("請輸入起始月份 (YYYYMM): ");
-    let mut input_from = String::new();
-
-    io::stdin().read_line(&mut input_from).expect("讀取失敗");
-
-    // 去掉換行符號
-    let input_from = input_from.trim();
-
-    println!("請輸入結束月份 (YYYYMM): ");
-    let mut input_to = String::new();
-
-    io::stdin().read_line(&mut input_to).expect("讀取失敗");
-
-    // 去掉換行符號
-    let input_to = input_to.trim();
-
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
-
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
-
-    // 去掉換行符號
-    let input_date = input_date.trim();
-
-    print_line();
-
-    let crosses =
-        scripts::macd::anal_date_all_companies(company_map, input_from, input_to, input_date).await;
+fn read_month_range_and_date() -> Option<(String, String, String)> {
+    let input_from = read_year_month("請輸入起始月份 (YYYYMM): ")?;
+    let input_to = read_year_month("請輸入結束月份 (YYYYMM): ")?;
+    let input_date = read_date()?;
+    Some((input_from, input_to, input_date))
+}
 
-    for cross in &crosses {
-        if cross.cross_type == analysis::macd::MacdCrossType::GoldenCross {
-            cross.print(company_map);
+fn read_volume_threshold() -> Option<u64> {
+    match read_input("請輸入成交量閾值 (數字): ").parse() {
+        Ok(num) => Some(num),
+        Err(_) => {
+            println!("無效的數字，請重新輸入。");
+            None
         }
     }
-    print_line();
 }
 
-async fn menu_volume_larger_analysis(company_map: &CompanyMap) {
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
-
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
-
-    // 去掉換行符號
-    let input_date = input_date.trim();
+async fn menu_fetch_data(company_map: &CompanyMap) {
+    let Some(input) = read_year_month("請輸入月份 (YYYYMM): ") else {
+        return;
+    };
 
-    println!("請輸入成交量閾值 (數字): ");
-    let mut input_threshold = String::new();
+    if let Err(e) = actions::fetch_data(company_map, &input).await {
+        println!("{e}");
+    }
+}
 
-    io::stdin()
-        .read_line(&mut input_threshold)
-        .expect("讀取失敗");
+async fn menu_long_red_candle_analysis(company_map: &CompanyMap) {
+    let Some(input_date) = read_date() else {
+        return;
+    };
 
-    // 去掉換行符號
-    let input_threshold = input_threshold.trim();
+    actions::long_red_candle(company_map, &input_date, OutputFormat::Table).await;
+}
 
-    let volume_threshold: u64 = match input_threshold.parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("無效的數字，請重新輸入。");
-            return;
-        }
+async fn menu_macd_golden_cross_analysis(company_map: &CompanyMap) {
+    let Some((input_from, input_to, input_date)) = read_month_range_and_date() else {
+        return;
     };
 
-    print_line();
+    actions::macd_golden_cross(
+        company_map,
+        &input_from,
+        &input_to,
+        &input_date,
+        OutputFormat::Table,
+    )
+    .await;
+}
 
-    let volume_results =
-        scripts::volume::volume_larger_than_threshold(company_map, volume_threshold, input_date)
-            .await;
-    println!(
-        "{:<8}{:<6}{:>4}{:>5}{:>5}{:>5}{:>5}{:>6} 公司名稱",
-        "日期", "股號", "成交股數", "開盤價", "收盤價", "最高價", "最低價", "漲跌",
-    );
-    for volume_result in &volume_results {
-        volume_result
-            .daily_data
-            .print(company_map, &volume_result.stock_no);
-    }
+async fn menu_volume_larger_analysis(company_map: &CompanyMap) {
+    let Some(input_date) = read_date() else {
+        return;
+    };
+    let Some(volume_threshold) = read_volume_threshold() else {
+        return;
+    };
 
-    print_line();
+    actions::volume_larger(
+        company_map,
+        &input_date,
+        volume_threshold,
+        OutputFormat::Table,
+    )
+    .await;
 }
 
 async fn menu_macd_golden_cross_volume_larger_analysis(company_map: &CompanyMap) {
-    println!("請輸入起始月份 (YYYYMM): ");
-    let mut input_from = String::new();
+    let Some((input_from, input_to, input_date)) = read_month_range_and_date() else {
+        return;
+    };
+    let Some(volume_threshold) = read_volume_threshold() else {
+        return;
+    };
 
-    io::stdin().read_line(&mut input_from).expect("讀取失敗");
+    actions::macd_golden_cross_volume_larger(
+        company_map,
+        &input_from,
+        &input_to,
+        &input_date,
+        volume_threshold,
+        OutputFormat::Table,
+    )
+    .await;
+}
 
-    // 去掉換行符號
-    let input_from = input_from.trim();
+async fn menu_doji_analysis(company_map: &CompanyMap) {
+    let Some(input_date) = read_date() else {
+        return;
+    };
 
-    println!("請輸入結束月份 (YYYYMM): ");
-    let mut input_to = String::new();
+    actions::doji(company_map, &input_date, OutputFormat::Table).await;
+}
 
-    io::stdin().read_line(&mut input_to).expect("讀取失敗");
+async fn menu_doji_in_swing_analysis(company_map: &CompanyMap) {
+    let Some((input_from, input_to, input_date)) = read_month_range_and_date() else {
+        return;
+    };
 
-    // 去掉換行符號
-    let input_to = input_to.trim();
+    actions::doji_in_swing(
+        company_map,
+        &input_from,
+        &input_to,
+        &input_date,
+        OutputFormat::Table,
+    )
+    .await;
+}
 
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
+async fn menu_bullish_engulfing_analysis(company_map: &CompanyMap) {
+    let Some(input_from) = read_year_month("請輸入起始月份 (YYYYMM): ") else {
+        return;
+    };
+    let Some(input_to) = read_year_month("請輸入結束月份 (YYYYMM): ") else {
+        return;
+    };
 
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
+    actions::bullish_engulfing_range(company_map, &input_from, &input_to, OutputFormat::Table)
+        .await;
+}
 
-    // 去掉換行符號
-    let input_date = input_date.trim();
+async fn menu_bullish_engulfing_analysis_date(company_map: &CompanyMap) {
+    let Some(input_date) = read_date() else {
+        return;
+    };
 
-    println!("請輸入成交量閾值 (數字): ");
-    let mut input_threshold = String::new();
+    actions::bullish_engulfing_date(company_map, &input_date, OutputFormat::Table).await;
+}
 
-    io::stdin()
-        .read_line(&mut input_threshold)
-        .expect("讀取失敗");
+fn menu_parameter_sweep(company_map: &CompanyMap) {
+    let strategies = analysis::sweep::Strategy::all();
+    for (i, strategy) in strategies.iter().enumerate() {
+        println!("{}. {}", i + 1, strategy.name());
+    }
+    let strategy = match read_input("請選擇策略: ").parse::<usize>() {
+        Ok(n) if (1..=strategies.len()).contains(&n) => strategies[n - 1],
+        _ => {
+            println!("無效的選項，請重新輸入。");
+            return;
+        }
+    };
 
-    // 去掉換行符號
-    let input_threshold = input_threshold.trim();
+    let Some(input_from) = read_year_month("請輸入起始月份 (YYYYMM): ") else {
+        return;
+    };
+    let Some(input_to) = read_year_month("請輸入結束月份 (YYYYMM): ") else {
+        return;
+    };
 
-    let volume_threshold: u64 = match input_threshold.parse() {
-        Ok(num) => num,
-        Err(_) => {
+    let horizon: usize = match read_input("請輸入持有交易日數 (數字): ").parse() {
+        Ok(num) if num > 0 => num,
+        _ => {
             println!("無效的數字，請重新輸入。");
             return;
         }
     };
 
-    print_line();
+    let mut ranges = Vec::new();
+    for (name, default) in strategy.param_names().iter().zip(strategy.default_params()) {
+        let input_range = read_input(&format!(
+            "請輸入 {name} 範圍 (起始:結束:間隔，直接 Enter 使用預設 {default}): "
+        ));
+        if input_range.is_empty() {
+            ranges.push(analysis::sweep::ParamRange::single(default));
+            continue;
+        }
+        match analysis::sweep::ParamRange::parse(&input_range) {
+            Some(range) => ranges.push(range),
+            None => {
+                println!("無效的範圍，請重新輸入。");
+                return;
+            }
+        }
+    }
 
-    println!(
-        "{} MACD 黃金交叉且成交量大:",
-        common::to_roc_date(input_date)
-    );
+    let min_signals: usize = read_input("請輸入最少訊號數 (數字): ").parse().unwrap_or(0);
+    let folds: usize = read_input("請輸入前進式分段數 (0 表示不做): ")
+        .parse()
+        .unwrap_or(0);
 
-    let results = scripts::complex::anal_macd_golden_volume_larger_date(
+    if let Err(e) = actions::parameter_sweep(
         company_map,
-        input_from,
-        input_to,
-        input_date,
-        volume_threshold,
-    )
-    .await;
+        &actions::SweepOptions {
+            strategy,
+            year_month_from: input_from,
+            year_month_to: input_to,
+            horizon,
+            ranges,
+            min_signals,
+            folds,
+        },
+        OutputFormat::Table,
+    ) {
+        println!("{e}");
+    }
+}
 
-    println!("{:<8} {:<10} {:<4} 公司名稱", "股號", "日期", "成交張數",);
-    for result in &results {
-        result.print(company_map);
+fn menu_watchlist() {
+    println!("1. 列出清單");
+    println!("2. 顯示清單內容");
+    println!("3. 加入股號");
+    println!("4. 移除股號");
+    println!("5. 刪除清單");
+    let input = read_input("請輸入選項：");
+
+    if input == "1" {
+        actions::watchlist_list();
+        return;
     }
 
-    print_line();
-}
+    let input_name = read_input("請輸入清單名稱: ");
 
-async fn menu_doji_analysis(company_map: &CompanyMap) {
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
+    let result = match input.as_str() {
+        "2" => actions::watchlist_show(&input_name),
+        "3" => actions::watchlist_add(&input_name, &read_input("請輸入股號: ")),
+        "4" => actions::watchlist_remove(&input_name, &read_input("請輸入股號: ")),
+        "5" => actions::watchlist_delete(&input_name),
+        _ => {
+            println!("無效的選項，請重新輸入。");
+            Ok(())
+        }
+    };
 
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
+    if let Err(e) = result {
+        println!("{e}");
+    }
+}
 
-    // 去掉換行符號
-    let input_date = input_date.trim();
+fn menu_scope() -> Option<Scope> {
+    println!("1. 全市場");
+    println!("2. 自選股清單");
+    println!("3. 產業 (代號或名稱)");
+    println!("4. 上市");
+    println!("5. 上櫃");
+    println!("6. 類股 (例如 電子、傳產、金融)");
+
+    match read_input("請輸入選項：").as_str() {
+        "1" => Some(Scope::All),
+        "2" => {
+            println!("現有清單: {}", watchlist::list().join(", "));
+            Some(Scope::Watchlist(read_input("請輸入清單名稱: ")))
+        }
+        "3" => Some(Scope::Industry(read_input("請輸入產業代號或名稱: "))),
+        "4" => Some(Scope::Market(Market::Twse)),
+        "5" => Some(Scope::Market(Market::Tpex)),
+        "6" => Some(Scope::Sector(read_input("請輸入類股: "))),
+        _ => {
+            println!("無效的選項，請重新輸入。");
+            None
+        }
+    }
+}
 
-    print_line();
+async fn menu_alerts(company_map: &CompanyMap) {
+    let Some(input) = read_year_month("請輸入月份 (YYYYMM): ") else {
+        return;
+    };
 
-    let results = scripts::doji::anal_date_all_companies(company_map, input_date).await;
+    actions::print_line();
+    if let Err(e) = actions::check_alerts(company_map, &input).await {
+        println!("{e}");
+    }
+    actions::print_line();
+}
 
-    println!(
-        "{:<8}{:<6}{:>4}{:>5}{:>5}{:>5}{:>5}{:>6} 公司名稱",
-        "日期", "股號", "成交股數", "開盤價", "收盤價", "最高價", "最低價", "漲跌",
-    );
-    for result in &results {
-        result.print(company_map);
+async fn menu_screener(company_map: &CompanyMap) {
+    let saved_screens = screener::saved::load();
+    if !saved_screens.is_empty() {
+        println!("已儲存的條件:");
+        for screen in &saved_screens {
+            println!("  {} = {}", screen.name, screen.expression);
+        }
     }
+    let input_expr = read_input(
+        "請輸入條件或已儲存的名稱 (例如 close > ma(60) and volume > 2 * avg_volume(20)): ",
+    );
+    let (saved, expression) = screener::saved::resolve(&input_expr);
 
-    print_line();
-}
+    if let Err(e) = screener::parser::parse(&expression) {
+        println!("條件錯誤: {e}");
+        return;
+    }
 
-async fn menu_doji_in_swing_analysis(company_map: &CompanyMap) {
-    println!("請輸入起始月份 (YYYYMM): ");
-    let mut input_from = String::new();
-    io::stdin().read_line(&mut input_from).expect("讀取失敗");
-    let input_from = input_from.trim();
-
-    println!("請輸入結束月份 (YYYYMM): ");
-    let mut input_to = String::new();
-    io::stdin().read_line(&mut input_to).expect("讀取失敗");
-    let input_to = input_to.trim();
-
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
-    let input_date = input_date.trim();
-
-    let results = scripts::complex::anal_doji_in_swing_all_companies(
-        company_map,
-        input_from,
-        input_to,
-        input_date,
-    )
-    .await;
+    let Some(input_date) = read_date() else {
+        return;
+    };
 
-    print_line();
-    println!(
-        "{:<8}{:<6}{:>6}{:>6}{:>6}{:>5}{:>5}{:>6}{:>6} 公司名稱",
-        "日期", "股號", "現價", "最高價", "最低價", "高", "低", "最高價", "最低價",
-    );
+    if let Err(e) =
+        actions::screen(company_map, &expression, &input_date, OutputFormat::Table).await
+    {
+        println!("{e}");
+        return;
+    }
 
-    for result in &results {
-        println!(
-            "{:<8} {:<6} {:>8} {:>8} {:>8} {:>5} {:>5} {:>8.2} {:>8.2} {:<6}",
-            result.daily_data.date,
-            result.stock_no,
-            result.daily_data.close,
-            result.highest_price,
-            result.lowest_price,
-            if result.meet_high { "是" } else { "否" },
-            if result.meet_low { "是" } else { "否" },
-            result.daily_data.close * 1.3,
-            result.daily_data.close * 0.7,
-            company_map.get(&result.stock_no)
-        );
+    if !saved {
+        let input_name = read_input("輸入名稱以儲存此條件 (直接 Enter 略過): ");
+        if !input_name.is_empty()
+            && let Err(e) = actions::save_screen(&input_name, &expression)
+        {
+            println!("{e}");
+        }
     }
-    print_line();
 }
 
-async fn menu_bullish_engulfing_analysis(company_map: &CompanyMap) {
-    println!("請輸入起始月份 (YYYYMM): ");
-    let mut input_from = String::new();
-    io::stdin().read_line(&mut input_from).expect("讀取失敗");
-    let input_from = input_from.trim();
-
-    println!("請輸入結束月份 (YYYYMM): ");
-    let mut input_to = String::new();
-    io::stdin().read_line(&mut input_to).expect("讀取失敗");
-    let input_to = input_to.trim();
+async fn menu_chart(company_map: &CompanyMap) {
+    let input_stock_no = read_input("請輸入股號: ");
+    let Some(input_from) = read_year_month("請輸入起始月份 (YYYYMM): ") else {
+        return;
+    };
+    let Some(input_to) = read_year_month("請輸入結束月份 (YYYYMM): ") else {
+        return;
+    };
+    let input_format = read_input("輸出格式 1. SVG 2. PNG (直接 Enter 使用 SVG): ");
+    let format = match input_format.as_str() {
+        "2" => ChartFormat::Png,
+        _ => ChartFormat::Svg,
+    };
 
-    let results = scripts::bullish_engulfing_pattern::anal_range_all_companies(
+    if let Err(e) = actions::chart(
         company_map,
-        input_from,
-        input_to,
+        &input_stock_no,
+        &input_from,
+        &input_to,
+        &ChartOptions::default(),
+        format,
+        None,
     )
-    .await;
+    .await
+    {
+        println!("{e}");
+    }
+}
 
-    print_line();
-    println!("{:<8}{:<6} 公司名稱", "日期", "股號",);
+fn menu_terminal_chart(company_map: &CompanyMap) {
+    let input_stock_no = read_input("請輸入股號: ");
+    let input_days = read_input("請輸入交易日數 (直接 Enter 使用 60): ");
+    let days: usize = match input_days.as_str() {
+        "" => 60,
+        input => match input.parse() {
+            Ok(num) if num > 0 => num,
+            _ => {
+                println!("無效的數字，請重新輸入。");
+                return;
+            }
+        },
+    };
 
-    for result in &results {
-        println!(
-            "{:<8} {:<6} {:<6}",
-            result.date,
-            result.stock_no,
-            company_map.get(&result.stock_no)
-        );
-    }
-    print_line();
+    actions::terminal_chart(company_map, &input_stock_no, days, 15);
 }
 
-async fn menu_bullish_engulfing_analysis_date(company_map: &CompanyMap) {
-    println!("請輸入日期 (YYYYMMDD): ");
-    let mut input_date = String::new();
-    io::stdin().read_line(&mut input_date).expect("讀取失敗");
-    let input_date = input_date.trim();
-
-    let results =
-        scripts::bullish_engulfing_pattern::anal_date_all_companies(company_map, input_date).await;
-
-    print_line();
-    println!("{:<8}{:<4} {:<4} {:<4} 公司名稱", "日期", "股號", "收盤", "高點");
-
-    for result in &results {
-        println!(
-            "{:<8} {:<6} {:<6} {:<5.2} {:<5.2}",
-            result.date,
-            result.stock_no,
-            result.curr_day.close,
-            result.curr_day.close * 1.3,
-            company_map.get(&result.stock_no)
-        );
+async fn menu_daily_report(company_map: &CompanyMap) {
+    let Some(input_date) = read_date() else {
+        return;
+    };
+
+    if let Err(e) = actions::daily_report(company_map, &input_date).await {
+        println!("{e}");
     }
-    print_line();
 }
@@ -0,0 +1,97 @@
+use serde_json::Value;
+
+use crate::common;
+use crate::data::monthly_data::DailyData;
+use crate::output::table::Align;
+use crate::twse::company_map::CompanyMap;
+
+/// 欄位的顯示方式；JSON/CSV 一律輸出原始數值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Integer,   // 千分位
+    Price,     // 小數兩位
+    Percent,   // 數值為比例，顯示時乘以 100
+    Bool,      // 顯示為 是/否
+    Sparkline, // 數值陣列，表格中顯示為迷你走勢圖
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub key: &'static str,   // JSON/CSV 的欄位名稱
+    pub title: &'static str, // 表格標題
+    pub kind: ColumnKind,
+    pub align: Align,
+    pub colored: bool, // 表格中依正負號上色 (紅漲綠跌)
+}
+
+impl Column {
+    /// 文字靠左、數字靠右
+    pub const fn new(key: &'static str, title: &'static str, kind: ColumnKind) -> Self {
+        let align = match kind {
+            ColumnKind::Text | ColumnKind::Bool | ColumnKind::Sparkline => Align::Left,
+            _ => Align::Right,
+        };
+        Column {
+            key,
+            title,
+            kind,
+            align,
+            colored: false,
+        }
+    }
+
+    pub const fn colored(mut self) -> Self {
+        self.colored = true;
+        self
+    }
+}
+
+/// 可輸出成表格、JSON、JSON Lines 或 CSV 的結果
+pub trait Record {
+    fn columns() -> Vec<Column>;
+
+    /// 與 columns() 一一對應的欄位值
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value>;
+}
+
+pub fn number(value: f64) -> Value {
+    serde_json::Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+pub fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+/// 日資料類結果 (十字線、大成交量、自訂選股…) 共用的欄位
+pub fn daily_columns() -> Vec<Column> {
+    vec![
+        Column::new("date", "日期", ColumnKind::Text),
+        Column::new("stock_no", "股號", ColumnKind::Text),
+        Column::new("volume", "成交張數", ColumnKind::Integer),
+        Column::new("open", "開盤價", ColumnKind::Price),
+        Column::new("close", "收盤價", ColumnKind::Price),
+        Column::new("high", "最高價", ColumnKind::Price),
+        Column::new("low", "最低價", ColumnKind::Price),
+        Column::new("change", "漲跌", ColumnKind::Price).colored(),
+        Column::new("name", "公司名稱", ColumnKind::Text),
+        Column::new("industry", "產業", ColumnKind::Text),
+    ]
+}
+
+pub fn daily_values(company_map: &CompanyMap, stock_no: &str, daily: &DailyData) -> Vec<Value> {
+    vec![
+        text(&daily.date),
+        text(stock_no),
+        Value::from(common::divide_by_1000(daily.volume)),
+        number(daily.open),
+        number(daily.close),
+        number(daily.high),
+        number(daily.low),
+        number(daily.change),
+        text(&company_map.get_name(stock_no)),
+        text(&company_map.get_industry(stock_no)),
+    ]
+}
@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+use crate::chart::terminal;
+use crate::common;
+use crate::output::record::{Column, ColumnKind, Record};
+use crate::output::table::{Align, Cell, Color, Table};
+use crate::twse::company_map::CompanyMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 對齊的表格 (預設)
+    #[default]
+    Table,
+    /// 單一 JSON 陣列
+    Json,
+    /// 每行一筆 JSON
+    Jsonl,
+    /// CSV，第一行為欄位名稱
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn is_table(self) -> bool {
+        self == OutputFormat::Table
+    }
+}
+
+/// colored 只影響表格格式
+pub fn render<R: Record>(
+    records: &[R],
+    company_map: &CompanyMap,
+    format: OutputFormat,
+    colored: bool,
+) -> String {
+    let columns = R::columns();
+    let rows: Vec<Vec<Value>> = records.iter().map(|r| r.values(company_map)).collect();
+
+    match format {
+        OutputFormat::Table => render_table(&columns, &rows, colored),
+        OutputFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(&to_objects(records, company_map)).unwrap_or_default()
+        ),
+        OutputFormat::Jsonl => rows
+            .into_iter()
+            .map(|row| format!("{}\n", to_object(&columns, row)))
+            .collect(),
+        OutputFormat::Csv => render_csv(&columns, &rows),
+    }
+}
+
+/// 每筆結果轉成以欄位名稱為 key 的 JSON 物件
+pub fn to_objects<R: Record>(records: &[R], company_map: &CompanyMap) -> Vec<Value> {
+    let columns = R::columns();
+    records
+        .iter()
+        .map(|r| to_object(&columns, r.values(company_map)))
+        .collect()
+}
+
+fn to_object(columns: &[Column], row: Vec<Value>) -> Value {
+    let mut object = Map::new();
+    for (column, value) in columns.iter().zip(row) {
+        object.insert(column.key.to_string(), value);
+    }
+    Value::Object(object)
+}
+
+/// 表格中顯示的文字
+pub fn format_cell(kind: ColumnKind, value: &Value) -> String {
+    match (kind, value) {
+        (_, Value::Null) => "-".to_string(),
+        (ColumnKind::Integer, Value::Number(n)) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => common::format_commas(n),
+            (None, Some(n)) => format!("-{}", common::format_commas(n.unsigned_abs())),
+            _ => n.to_string(),
+        },
+        (ColumnKind::Price, Value::Number(n)) => format!("{:.2}", n.as_f64().unwrap_or(0.0)),
+        (ColumnKind::Percent, Value::Number(n)) => {
+            format!("{:.2}%", n.as_f64().unwrap_or(0.0) * 100.0)
+        }
+        (ColumnKind::Bool, Value::Bool(b)) => if *b { "是" } else { "否" }.to_string(),
+        (ColumnKind::Sparkline, Value::Array(values)) => {
+            let values: Vec<f64> = values.iter().filter_map(Value::as_f64).collect();
+            terminal::sparkline(&values)
+        }
+        (_, Value::String(s)) => s.clone(),
+        (_, value) => value.to_string(),
+    }
+}
+
+fn render_table(columns: &[Column], rows: &[Vec<Value>], colored: bool) -> String {
+    let headers: Vec<(&str, Align)> = columns.iter().map(|c| (c.title, c.align)).collect();
+    let mut table = Table::new(&headers);
+    for row in rows {
+        table.push(
+            columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| {
+                    let color = match value {
+                        Value::Number(n) if column.colored => n.as_f64().and_then(Color::from_sign),
+                        _ => None,
+                    };
+                    Cell::colored(format_cell(column.kind, value), color)
+                })
+                .collect(),
+        );
+    }
+    table.render(colored)
+}
+
+fn render_csv(columns: &[Column], rows: &[Vec<Value>]) -> String {
+    let mut ret = String::new();
+    let header: Vec<String> = columns.iter().map(|c| csv_field(c.key)).collect();
+    let _ = writeln!(ret, "{}", header.join(","));
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                Value::String(s) => csv_field(s),
+                value => csv_field(&value.to_string()),
+            })
+            .collect();
+        let _ = writeln!(ret, "{}", fields.join(","));
+    }
+    ret
+}
+
+/// 含逗號、引號或換行的欄位加上引號
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,220 @@
+use std::env;
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+
+use unicode_width::UnicodeWidthStr;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// 台股慣例：紅漲綠跌
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Up,
+    Down,
+}
+
+impl Color {
+    /// 正數為漲、負數為跌，0 不上色
+    pub fn from_sign(value: f64) -> Option<Color> {
+        if value > 0.0 {
+            Some(Color::Up)
+        } else if value < 0.0 {
+            Some(Color::Down)
+        } else {
+            None
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Color::Up => RED,
+            Color::Down => GREEN,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub text: String,
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Cell {
+            text: text.into(),
+            color: None,
+        }
+    }
+
+    pub fn colored(text: impl Into<String>, color: Option<Color>) -> Self {
+        Cell {
+            text: text.into(),
+            color,
+        }
+    }
+}
+
+/// 終端機顯示寬度，中文等全形字算 2 格
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// 依顯示寬度補空白
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(text)));
+    match align {
+        Align::Left => format!("{text}{fill}"),
+        Align::Right => format!("{fill}{text}"),
+    }
+}
+
+/// 加上 ANSI 顏色；colored 為 false 或無顏色時原樣回傳
+pub fn paint(text: &str, color: Option<Color>, colored: bool) -> String {
+    match color {
+        Some(color) if colored => format!("{}{text}{RESET}", color.code()),
+        _ => text.to_string(),
+    }
+}
+
+/// stdout 是終端機且未設定 NO_COLOR 時才上色
+pub fn color_enabled() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(headers: &[(&str, Align)]) -> Self {
+        Table {
+            headers: headers.iter().map(|(title, _)| title.to_string()).collect(),
+            aligns: headers.iter().map(|(_, align)| *align).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    pub fn render(&self, colored: bool) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| display_width(&cell.text))
+                    .chain([display_width(&self.headers[i])])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut ret = String::new();
+        let header: Vec<String> = self
+            .headers
+            .iter()
+            .zip(&self.aligns)
+            .zip(&widths)
+            .map(|((title, align), width)| pad(title, *width, *align))
+            .collect();
+        let _ = writeln!(ret, "{}", header.join("  ").trim_end());
+
+        for row in &self.rows {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&self.aligns)
+                .zip(&widths)
+                .map(|((cell, align), width)| {
+                    paint(&pad(&cell.text, *width, *align), cell.color, colored)
+                })
+                .collect();
+            let _ = writeln!(ret, "{}", line.join("  ").trim_end());
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cjk_characters_are_double_width() {
+        assert_eq!(display_width("2330"), 4);
+        assert_eq!(display_width("台積電"), 6);
+        assert_eq!(display_width("元大台灣50"), 10);
+        assert_eq!(display_width("ＫＹ"), 4); // 全形英文
+    }
+
+    #[test]
+    fn pad_uses_display_width() {
+        assert_eq!(pad("台積電", 8, Align::Left), "台積電  ");
+        assert_eq!(pad("台積電", 8, Align::Right), "  台積電");
+        assert_eq!(pad("12.5", 6, Align::Right), "  12.5");
+        // 比欄寬長時不截斷
+        assert_eq!(pad("台積電", 4, Align::Left), "台積電");
+    }
+
+    #[test]
+    fn render_aligns_mixed_width_columns() {
+        let mut table = Table::new(&[
+            ("股號", Align::Left),
+            ("名稱", Align::Left),
+            ("收盤", Align::Right),
+        ]);
+        table.push(vec![
+            Cell::new("2330"),
+            Cell::new("台積電"),
+            Cell::new("1780.00"),
+        ]);
+        table.push(vec![
+            Cell::new("00632R"),
+            Cell::new("元大台灣50反1"),
+            Cell::new("15.2"),
+        ]);
+
+        let rendered = table.render(false);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "股號    名稱              收盤",
+                "2330    台積電         1780.00",
+                "00632R  元大台灣50反1     15.2",
+            ]
+        );
+        // 每一列的收盤欄都對齊在同一個顯示寬度
+        assert!(
+            lines
+                .iter()
+                .all(|line| display_width(line) == display_width(lines[0]))
+        );
+    }
+
+    #[test]
+    fn colors_only_when_enabled() {
+        let mut table = Table::new(&[("漲跌", Align::Right)]);
+        table.push(vec![Cell::colored("+1.5", Color::from_sign(1.5))]);
+        table.push(vec![Cell::colored("-0.5", Color::from_sign(-0.5))]);
+        table.push(vec![Cell::colored("0", Color::from_sign(0.0))]);
+
+        assert_eq!(table.render(false), "漲跌\n+1.5\n-0.5\n   0\n");
+        assert_eq!(
+            table.render(true),
+            format!("漲跌\n{RED}+1.5{RESET}\n{GREEN}-0.5{RESET}\n   0\n")
+        );
+    }
+}
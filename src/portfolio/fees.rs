@@ -0,0 +1,47 @@
+use crate::twse::company_map::SecurityType;
+
+/// 券商手續費率 0.1425%
+pub const COMMISSION_RATE: f64 = 0.001425;
+/// 手續費最低收取金額
+pub const MIN_COMMISSION: f64 = 20.0;
+/// 股票證券交易稅 0.3% (賣出時收取)
+pub const STOCK_TAX_RATE: f64 = 0.003;
+/// ETF 證券交易稅 0.1% (賣出時收取)
+pub const ETF_TAX_RATE: f64 = 0.001;
+
+/// 手續費 (無條件捨去，最低 20 元)
+pub fn commission(amount: f64) -> f64 {
+    (amount * COMMISSION_RATE).floor().max(MIN_COMMISSION)
+}
+
+/// 證券交易稅 (無條件捨去)，ETF (含槓桿、反向型) 稅率較低
+pub fn transaction_tax(amount: f64, security_type: SecurityType) -> f64 {
+    let rate = match security_type {
+        SecurityType::Stock => STOCK_TAX_RATE,
+        SecurityType::Etf | SecurityType::LeveragedEtf | SecurityType::InverseEtf => ETF_TAX_RATE,
+    };
+    (amount * rate).floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commission_has_a_minimum() {
+        assert_eq!(commission(1_000.0), MIN_COMMISSION);
+        assert_eq!(commission(14_035.0), MIN_COMMISSION);
+        assert_eq!(commission(100_000.0), 142.0);
+    }
+
+    #[test]
+    fn etf_tax_is_lower_than_stock_tax() {
+        assert_eq!(transaction_tax(100_000.0, SecurityType::Stock), 300.0);
+        assert_eq!(transaction_tax(100_000.0, SecurityType::Etf), 100.0);
+        assert_eq!(
+            transaction_tax(100_000.0, SecurityType::LeveragedEtf),
+            100.0
+        );
+        assert_eq!(transaction_tax(999.0, SecurityType::Stock), 2.0);
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::common;
+use crate::portfolio::fees;
+
+pub const HOLDINGS_FILE: &str = "holdings.txt";
+
+/// 一筆交易紀錄，格式: 股號,股數,成交價,日期(YYYYMMDD)[,手續費]
+/// 股數為負數表示賣出；手續費省略時依券商費率計算
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub stock_no: String,
+    pub shares: i64,
+    pub cost: f64,
+    pub date: String,
+    pub fees: f64,
+}
+
+impl Holding {
+    pub fn parse(line: &str) -> Result<Self, Box<dyn Error>> {
+        let fields: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+        if fields.len() != 4 && fields.len() != 5 {
+            return Err(format!("欄位數錯誤: {line}").into());
+        }
+
+        let shares: i64 = fields[1].parse()?;
+        if shares == 0 {
+            return Err(format!("股數不可為 0: {line}").into());
+        }
+        let cost: f64 = fields[2].parse()?;
+        NaiveDate::parse_from_str(fields[3], "%Y%m%d")?;
+
+        let fees = match fields.get(4) {
+            Some(fee) => fee.parse()?,
+            None => fees::commission(shares.unsigned_abs() as f64 * cost),
+        };
+
+        Ok(Holding {
+            stock_no: fields[0].to_string(),
+            shares,
+            cost,
+            date: fields[3].to_string(),
+            fees,
+        })
+    }
+
+    pub fn is_sell(&self) -> bool {
+        self.shares < 0
+    }
+}
+
+/// 讀取交易紀錄檔，依日期排序
+pub fn load_holdings<P: AsRef<Path>>(path: P) -> Result<Vec<Holding>, Box<dyn Error>> {
+    let mut holdings = Vec::new();
+    for line in common::read_lines_to_vec(path)? {
+        holdings.push(Holding::parse(&line)?);
+    }
+    holdings.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(holdings)
+}
+
+/// 目前仍持有 (股數合計大於 0) 的股號，依股號排序
+pub fn held_stock_nos(holdings: &[Holding]) -> Vec<String> {
+    let mut shares: BTreeMap<&str, i64> = BTreeMap::new();
+    for holding in holdings {
+        *shares.entry(&holding.stock_no).or_default() += holding.shares;
+    }
+    shares
+        .into_iter()
+        .filter(|(_, shares)| *shares > 0)
+        .map(|(stock_no, _)| stock_no.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_stock_nos_skips_closed_positions() {
+        let holdings: Vec<Holding> = [
+            "2330,1000,600,20240102",
+            "2317,2000,100,20240103",
+            "2330,-1000,650,20240201",
+            "0050,500,150,20240301",
+            "0050,-200,160,20240302",
+        ]
+        .iter()
+        .map(|line| Holding::parse(line).unwrap())
+        .collect();
+
+        assert_eq!(held_stock_nos(&holdings), ["0050", "2317"]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod fees;
+pub mod holdings;
+pub mod positions;
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::data::monthly_data;
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::portfolio::fees;
+use crate::portfolio::holdings::Holding;
+use crate::twse::company_map::{CompanyMap, SecurityType};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub stock_no: String,
+    pub security_type: SecurityType, // 決定賣出的交易稅率
+    pub shares: u64,
+    pub total_cost: f64, // 含買進手續費的持有成本 (平均成本法)
+    pub realized_pnl: f64,
+    pub last_date: Option<String>,
+    pub last_close: Option<f64>,
+    pub weight: f64, // 占總市值的比例
+}
+
+impl Position {
+    fn new(stock_no: &str, security_type: SecurityType) -> Self {
+        Position {
+            stock_no: stock_no.to_string(),
+            security_type,
+            shares: 0,
+            total_cost: 0.0,
+            realized_pnl: 0.0,
+            last_date: None,
+            last_close: None,
+            weight: 0.0,
+        }
+    }
+
+    fn apply(&mut self, holding: &Holding) -> Result<(), Box<dyn Error>> {
+        let shares = holding.shares.unsigned_abs();
+        let amount = shares as f64 * holding.cost;
+
+        if holding.is_sell() {
+            if shares > self.shares {
+                return Err(format!(
+                    "{} 於 {} 賣出 {} 股，但只持有 {} 股",
+                    holding.stock_no, holding.date, shares, self.shares
+                )
+                .into());
+            }
+            let cost_removed = self.avg_cost() * shares as f64;
+            let proceeds =
+                amount - holding.fees - fees::transaction_tax(amount, self.security_type);
+            self.realized_pnl += proceeds - cost_removed;
+            self.total_cost -= cost_removed;
+            self.shares -= shares;
+        } else {
+            self.total_cost += amount + holding.fees;
+            self.shares += shares;
+        }
+        Ok(())
+    }
+
+    pub fn avg_cost(&self) -> f64 {
+        if self.shares == 0 {
+            0.0
+        } else {
+            self.total_cost / self.shares as f64
+        }
+    }
+
+    pub fn market_value(&self) -> f64 {
+        self.last_close.unwrap_or(0.0) * self.shares as f64
+    }
+
+    /// 未實現損益，已扣除以現價賣出時的手續費與交易稅
+    pub fn unrealized_pnl(&self) -> f64 {
+        if self.shares == 0 || self.last_close.is_none() {
+            return 0.0;
+        }
+        let market_value = self.market_value();
+        market_value
+            - fees::commission(market_value)
+            - fees::transaction_tax(market_value, self.security_type)
+            - self.total_cost
+    }
+
+    pub fn return_rate(&self) -> f64 {
+        if self.total_cost == 0.0 {
+            0.0
+        } else {
+            self.unrealized_pnl() / self.total_cost
+        }
+    }
+}
+
+impl Record for Position {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("shares", "股數", ColumnKind::Integer),
+            Column::new("avg_cost", "平均成本", ColumnKind::Price),
+            Column::new("last_close", "現價", ColumnKind::Price),
+            Column::new("market_value", "市值", ColumnKind::Integer),
+            Column::new("unrealized_pnl", "未實現損益", ColumnKind::Integer).colored(),
+            Column::new("return_rate", "報酬率", ColumnKind::Percent).colored(),
+            Column::new("weight", "權重", ColumnKind::Percent),
+            Column::new("last_date", "資料日期", ColumnKind::Text),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.stock_no),
+            Value::from(self.shares),
+            record::number(self.avg_cost()),
+            self.last_close.map(record::number).unwrap_or(Value::Null),
+            Value::from(self.market_value().round() as u64),
+            Value::from(self.unrealized_pnl().round() as i64),
+            record::number(self.return_rate()),
+            record::number(self.weight),
+            self.last_date
+                .as_deref()
+                .map(record::text)
+                .unwrap_or(Value::Null),
+            record::text(&company_map.get_name(&self.stock_no)),
+            record::text(&company_map.get_industry(&self.stock_no)),
+        ]
+    }
+}
+
+pub struct Portfolio {
+    pub positions: Vec<Position>,
+}
+
+impl Portfolio {
+    pub fn from_holdings(
+        holdings: &[Holding],
+        company_map: &CompanyMap,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut positions: Vec<Position> = Vec::new();
+        for holding in holdings {
+            let index = match positions
+                .iter()
+                .position(|p| p.stock_no == holding.stock_no)
+            {
+                Some(index) => index,
+                None => {
+                    positions.push(Position::new(
+                        &holding.stock_no,
+                        company_map.security_type(&holding.stock_no),
+                    ));
+                    positions.len() - 1
+                }
+            };
+            positions[index].apply(holding)?;
+        }
+        positions.sort_by(|a, b| a.stock_no.cmp(&b.stock_no));
+
+        Ok(Portfolio { positions })
+    }
+
+    /// 以本地資料中最新的收盤價評價
+    pub fn mark_to_market(&mut self) {
+        for position in &mut self.positions {
+            if let Some(daily) = monthly_data::latest_stored_daily_data(&position.stock_no) {
+                position.last_date = Some(daily.date);
+                position.last_close = Some(daily.close);
+            }
+        }
+
+        let total = self.total_market_value();
+        for position in &mut self.positions {
+            position.weight = if total == 0.0 {
+                0.0
+            } else {
+                position.market_value() / total
+            };
+        }
+    }
+
+    /// 目前仍持有的部位
+    pub fn open_positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.iter().filter(|p| p.shares > 0)
+    }
+
+    pub fn total_cost(&self) -> f64 {
+        self.open_positions().map(|p| p.total_cost).sum()
+    }
+
+    pub fn total_market_value(&self) -> f64 {
+        self.open_positions().map(|p| p.market_value()).sum()
+    }
+
+    pub fn total_unrealized_pnl(&self) -> f64 {
+        self.open_positions().map(|p| p.unrealized_pnl()).sum()
+    }
+
+    pub fn total_realized_pnl(&self) -> f64 {
+        self.positions.iter().map(|p| p.realized_pnl).sum()
+    }
+
+    /// 依產業別加總市值權重，由大到小排序
+    pub fn industry_weights(&self, company_map: &CompanyMap) -> Vec<(String, f64)> {
+        self.weights_by(|stock_no| company_map.get_industry(stock_no))
+    }
+
+    /// 依類股加總市值權重，由大到小排序
+    pub fn sector_weights(&self, company_map: &CompanyMap) -> Vec<(String, f64)> {
+        self.weights_by(|stock_no| company_map.get_sector(stock_no))
+    }
+
+    fn weights_by<F>(&self, group: F) -> Vec<(String, f64)>
+    where
+        F: Fn(&str) -> String,
+    {
+        let total = self.total_market_value();
+        let mut weights: HashMap<String, f64> = HashMap::new();
+        for position in self.open_positions() {
+            *weights.entry(group(&position.stock_no)).or_default() += position.market_value();
+        }
+
+        let mut ret: Vec<(String, f64)> = weights
+            .into_iter()
+            .map(|(industry, value)| (industry, if total == 0.0 { 0.0 } else { value / total }))
+            .collect();
+        ret.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_all(position: &mut Position, lines: &[&str]) {
+        for line in lines {
+            position.apply(&Holding::parse(line).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn average_cost_includes_buy_fees() {
+        let mut position = Position::new("2330", SecurityType::Stock);
+        apply_all(
+            &mut position,
+            &["2330,1000,100,20260105", "2330,1000,110,20260106"],
+        );
+        assert_eq!(position.shares, 2000);
+        // 手續費 142 + 156
+        assert!((position.avg_cost() - 105.149).abs() < 1e-9);
+    }
+
+    #[test]
+    fn partial_sell_keeps_average_cost() {
+        let mut position = Position::new("2330", SecurityType::Stock);
+        apply_all(
+            &mut position,
+            &["2330,1000,100,20260105,142", "2330,-400,110,20260106,62"],
+        );
+        assert_eq!(position.shares, 600);
+        assert!((position.avg_cost() - 100.142).abs() < 1e-9);
+        // 44,000 - 手續費 62 - 交易稅 132 - 成本 40,056.8
+        assert!((position.realized_pnl - 3_749.2).abs() < 1e-6);
+
+        apply_all(&mut position, &["2330,400,90,20260107,51"]);
+        assert_eq!(position.shares, 1000);
+        assert!((position.avg_cost() - 96.1362).abs() < 1e-9);
+    }
+
+    #[test]
+    fn etf_sell_uses_etf_tax() {
+        let mut position = Position::new("0050", SecurityType::Etf);
+        apply_all(
+            &mut position,
+            &["0050,1000,100,20260105,142", "0050,-1000,100,20260106,142"],
+        );
+        // 100,000 - 手續費 142 - 交易稅 100 - 成本 100,142
+        assert!((position.realized_pnl + 384.0).abs() < 1e-6);
+        assert_eq!(position.total_cost, 0.0);
+    }
+
+    #[test]
+    fn rejects_selling_more_than_held() {
+        let mut position = Position::new("2330", SecurityType::Stock);
+        apply_all(&mut position, &["2330,1000,100,20260105"]);
+        let sell = Holding::parse("2330,-1001,100,20260106").unwrap();
+        assert!(position.apply(&sell).is_err());
+        assert_eq!(position.shares, 1000);
+    }
+}
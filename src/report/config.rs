@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::screener::saved;
+
+/// 每日報告設定檔，範例:
+/// {
+///   "screens": ["強勢股", "close > ma(60) and volume > 2 * avg_volume(20)"],
+///   "chart_top": 3
+/// }
+/// screens 可以是已儲存的名稱或條件式，沒有設定檔時使用所有已儲存的條件
+pub const REPORT_FILE: &str = "report.json";
+
+/// 每個條件預設附上前幾名的 K 線圖
+const DEFAULT_CHART_TOP: usize = 3;
+
+#[derive(Debug, Deserialize)]
+pub struct ReportConfig {
+    #[serde(default)]
+    pub screens: Vec<String>,
+    #[serde(default = "default_chart_top")]
+    pub chart_top: usize,
+}
+
+fn default_chart_top() -> usize {
+    DEFAULT_CHART_TOP
+}
+
+impl ReportConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        if !path.as_ref().exists() {
+            return Ok(ReportConfig {
+                screens: Vec::new(),
+                chart_top: DEFAULT_CHART_TOP,
+            });
+        }
+        let file = File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// (名稱, 條件式)；條件式本身當作名稱
+    pub fn resolved_screens(&self) -> Vec<(String, String)> {
+        if self.screens.is_empty() {
+            return saved::load()
+                .into_iter()
+                .map(|screen| (screen.name, screen.expression))
+                .collect();
+        }
+        self.screens
+            .iter()
+            .map(|input| match saved::find(input) {
+                Some(screen) => (screen.name, screen.expression),
+                None => (input.clone(), input.clone()),
+            })
+            .collect()
+    }
+}
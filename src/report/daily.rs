@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::analysis::sector::Mover;
+use crate::chart::candlestick::{self, ChartOptions};
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::{DailyData, MonthlyData};
+use crate::output::record::{Column, Record};
+use crate::report::config::ReportConfig;
+use crate::report::render;
+use crate::screener;
+use crate::scripts;
+use crate::twse::company_map::CompanyMap;
+
+pub const REPORT_DIR: &str = "reports";
+
+/// 報告內 K 線圖涵蓋的月份數 (含當月)
+const REPORT_CHART_MONTHS: i32 = 3;
+/// 漲跌幅排行列出的檔數
+const TOP_MOVERS: usize = 5;
+
+/// 當日市場概況，只統計 company_map 內有當日資料的股票
+pub struct MarketSummary {
+    pub total: usize,
+    pub up: usize,
+    pub down: usize,
+    pub flat: usize,
+    pub volume: u64, // 張
+    pub amount: u64, // 元
+    pub gainers: Vec<Mover>,
+    pub losers: Vec<Mover>,
+}
+
+pub struct Chart {
+    pub stock_no: String,
+    pub svg: String,
+}
+
+/// 一個選股條件的結果，欄位與值已轉好，方便輸出成 Markdown/HTML
+pub struct ScreenSection {
+    pub name: String,
+    pub expression: String,
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+    pub charts: Vec<Chart>,
+    pub error: Option<String>,
+}
+
+pub struct DailyReport {
+    pub date: String,     // YYYYMMDD
+    pub roc_date: String, // 民國日期
+    pub summary: MarketSummary,
+    pub sections: Vec<ScreenSection>,
+}
+
+/// 掃描範圍 (company_map) 內的當日漲跌家數、成交量與漲跌幅前幾名，與 sectors 相同
+fn market_summary(company_map: &CompanyMap, year_month: &str, roc_date: &str) -> MarketSummary {
+    let mut days: Vec<(String, DailyData)> = Vec::new();
+    for company in &company_map.stock_map {
+        let Some(monthly_data) = MonthlyData::from_storage(&company.stock_no, year_month) else {
+            continue;
+        };
+        if let Some(daily) = monthly_data
+            .daily_data
+            .into_iter()
+            .find(|d| d.date == roc_date)
+        {
+            days.push((company.stock_no.clone(), daily));
+        }
+    }
+
+    let mut movers: Vec<Mover> = days
+        .iter()
+        .filter_map(|(stock_no, daily)| Mover::new(company_map, stock_no, daily))
+        .collect();
+    movers.sort_by(|a, b| b.change_rate.total_cmp(&a.change_rate));
+    let losers: Vec<Mover> = movers
+        .iter()
+        .rev()
+        .take_while(|m| m.change_rate < 0.0)
+        .take(TOP_MOVERS)
+        .cloned()
+        .collect();
+    movers.retain(|m| m.change_rate > 0.0);
+    movers.truncate(TOP_MOVERS);
+
+    MarketSummary {
+        total: days.len(),
+        up: days.iter().filter(|(_, d)| d.change > 0.0).count(),
+        down: days.iter().filter(|(_, d)| d.change < 0.0).count(),
+        flat: days.iter().filter(|(_, d)| d.change == 0.0).count(),
+        volume: days
+            .iter()
+            .map(|(_, d)| common::divide_by_1000(d.volume))
+            .sum(),
+        amount: days.iter().map(|(_, d)| d.amount).sum(),
+        gainers: movers,
+        losers,
+    }
+}
+
+async fn stock_chart(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month_to: &str,
+    roc_date: &str,
+) -> Option<Chart> {
+    let year_month_from = common::shift_year_month(year_month_to, 1 - REPORT_CHART_MONTHS)?;
+    let (mut daily_data, start) =
+        candlestick::load(company_map, stock_no, &year_month_from, year_month_to).await;
+    daily_data.retain(|d| d.date.as_str() <= roc_date);
+    if start >= daily_data.len() {
+        return None;
+    }
+
+    let title = format!("{stock_no} {}", company_map.get_name(stock_no));
+    let options = ChartOptions {
+        width: 900,
+        height: 560,
+        ..ChartOptions::default()
+    };
+    Some(Chart {
+        stock_no: stock_no.to_string(),
+        svg: candlestick::render_svg(&title, &daily_data, start, &options),
+    })
+}
+
+/// 執行設定中的每個選股條件並整理成報告
+pub async fn build(
+    company_map: &CompanyMap,
+    config: &ReportConfig,
+    date: &str,
+) -> Result<DailyReport, Box<dyn Error>> {
+    let (Some(year_month), Some(roc_date)) =
+        (common::year_month_of(date), common::to_roc_date(date))
+    else {
+        return Err(format!("無效的日期: {date}").into());
+    };
+
+    let mut sections = Vec::new();
+    for (name, expression) in config.resolved_screens() {
+        common::log(&format!("報告: 執行 {name}"));
+        let mut section = ScreenSection {
+            name,
+            expression,
+            columns: scripts::screener::ScreenResult::columns(),
+            rows: Vec::new(),
+            charts: Vec::new(),
+            error: None,
+        };
+
+        let expr = match screener::parser::parse(&section.expression) {
+            Ok(expr) => expr,
+            Err(e) => {
+                section.error = Some(format!("條件錯誤: {e}"));
+                sections.push(section);
+                continue;
+            }
+        };
+
+        let results = scripts::screener::screen_date_all_companies(company_map, &expr, date).await;
+        for result in results.iter().take(config.chart_top) {
+            if let Some(chart) =
+                stock_chart(company_map, &result.stock_no, year_month, &roc_date).await
+            {
+                section.charts.push(chart);
+            }
+        }
+        section.rows = results.iter().map(|r| r.values(company_map)).collect();
+        sections.push(section);
+    }
+
+    Ok(DailyReport {
+        date: date.to_string(),
+        summary: market_summary(company_map, year_month, &roc_date),
+        roc_date,
+        sections,
+    })
+}
+
+/// 寫出 reports/<日期>.md、reports/<日期>.html，Markdown 引用的 K 線圖放在 reports/<日期>/
+pub fn write(report: &DailyReport) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let dir = config::path(REPORT_DIR);
+    fs::create_dir_all(&dir)?;
+
+    let has_charts = report.sections.iter().any(|s| !s.charts.is_empty());
+    if has_charts {
+        let chart_dir = dir.join(&report.date);
+        fs::create_dir_all(&chart_dir)?;
+        for chart in report.sections.iter().flat_map(|s| &s.charts) {
+            fs::write(
+                chart_dir.join(format!("{}.svg", chart.stock_no)),
+                &chart.svg,
+            )?;
+        }
+    }
+
+    let markdown_path = dir.join(format!("{}.md", report.date));
+    fs::write(&markdown_path, render::markdown(report))?;
+    let html_path = dir.join(format!("{}.html", report.date));
+    fs::write(&html_path, render::html(report))?;
+    Ok(vec![markdown_path, html_path])
+}
@@ -0,0 +1,231 @@
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::analysis::sector::Mover;
+use crate::chart::svg;
+use crate::common;
+use crate::output::record::Column;
+use crate::output::render::format_cell;
+use crate::output::table::{Align, Color};
+use crate::report::daily::{DailyReport, MarketSummary};
+
+const HTML_STYLE: &str = "body{font-family:'Noto Sans CJK TC','PingFang TC','Microsoft JhengHei',sans-serif;margin:24px;color:#222}\
+table{border-collapse:collapse;margin:8px 0 16px}\
+th,td{border:1px solid #ddd;padding:4px 8px;white-space:nowrap}\
+th{background:#f4f4f4}\
+td.num{text-align:right}\
+.up{color:#d9342b}.down{color:#2b9e4b}\
+.expr{color:#666;font-family:monospace}\
+.charts svg{max-width:100%;height:auto;margin:4px 0}";
+
+fn change_rate_text(rate: f64) -> String {
+    format!("{:+.2}%", rate * 100.0)
+}
+
+fn summary_lines(summary: &MarketSummary) -> Vec<String> {
+    vec![
+        format!(
+            "上漲 {} 家、下跌 {} 家、平盤 {} 家 (共 {} 家)",
+            summary.up, summary.down, summary.flat, summary.total
+        ),
+        format!(
+            "成交量 {} 張、成交金額 {} 元",
+            common::format_commas(summary.volume),
+            common::format_commas(summary.amount)
+        ),
+    ]
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn markdown_movers(ret: &mut String, title: &str, movers: &[Mover]) {
+    if movers.is_empty() {
+        return;
+    }
+    let _ = writeln!(ret, "**{title}**\n");
+    let _ = writeln!(ret, "| 股號 | 公司名稱 | 收盤價 | 漲跌幅 |");
+    let _ = writeln!(ret, "| --- | --- | ---: | ---: |");
+    for mover in movers {
+        let _ = writeln!(
+            ret,
+            "| {} | {} | {:.2} | {} |",
+            mover.stock_no,
+            markdown_escape(&mover.name),
+            mover.close,
+            change_rate_text(mover.change_rate)
+        );
+    }
+    ret.push('\n');
+}
+
+/// Markdown 版本，K 線圖以相對路徑引用 reports/<日期>/<股號>.svg
+pub fn markdown(report: &DailyReport) -> String {
+    let mut ret = String::new();
+    let _ = writeln!(ret, "# 每日報告 {}\n", report.roc_date);
+
+    let _ = writeln!(ret, "## 市場概況\n");
+    for line in summary_lines(&report.summary) {
+        let _ = writeln!(ret, "- {line}");
+    }
+    ret.push('\n');
+    markdown_movers(&mut ret, "漲幅前五", &report.summary.gainers);
+    markdown_movers(&mut ret, "跌幅前五", &report.summary.losers);
+
+    for section in &report.sections {
+        let _ = writeln!(ret, "## {}\n", section.name);
+        let _ = writeln!(ret, "`{}`\n", section.expression);
+        if let Some(error) = &section.error {
+            let _ = writeln!(ret, "{error}\n");
+            continue;
+        }
+        if section.rows.is_empty() {
+            let _ = writeln!(ret, "沒有符合的股票\n");
+            continue;
+        }
+
+        let _ = writeln!(ret, "共 {} 檔\n", section.rows.len());
+        let titles: Vec<&str> = section.columns.iter().map(|c| c.title).collect();
+        let aligns: Vec<&str> = section
+            .columns
+            .iter()
+            .map(|c| match c.align {
+                Align::Left => "---",
+                Align::Right => "---:",
+            })
+            .collect();
+        let _ = writeln!(ret, "| {} |", titles.join(" | "));
+        let _ = writeln!(ret, "| {} |", aligns.join(" | "));
+        for row in &section.rows {
+            let cells: Vec<String> = section
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| markdown_escape(&format_cell(column.kind, value)))
+                .collect();
+            let _ = writeln!(ret, "| {} |", cells.join(" | "));
+        }
+        ret.push('\n');
+
+        for chart in &section.charts {
+            let _ = writeln!(ret, "![{0}]({1}/{0}.svg)\n", chart.stock_no, report.date);
+        }
+    }
+    ret
+}
+
+fn html_class(column: &Column, value: &Value) -> String {
+    let mut classes = Vec::new();
+    if column.align == Align::Right {
+        classes.push("num");
+    }
+    if column.colored {
+        match value.as_f64().and_then(Color::from_sign) {
+            Some(Color::Up) => classes.push("up"),
+            Some(Color::Down) => classes.push("down"),
+            None => {}
+        }
+    }
+    if classes.is_empty() {
+        String::new()
+    } else {
+        format!(r#" class="{}""#, classes.join(" "))
+    }
+}
+
+fn html_movers(ret: &mut String, title: &str, movers: &[Mover]) {
+    if movers.is_empty() {
+        return;
+    }
+    let _ = writeln!(ret, "<h3>{title}</h3>");
+    let _ = writeln!(
+        ret,
+        "<table><tr><th>股號</th><th>公司名稱</th><th>收盤價</th><th>漲跌幅</th></tr>"
+    );
+    for mover in movers {
+        let class = if mover.change_rate > 0.0 {
+            "up"
+        } else {
+            "down"
+        };
+        let _ = writeln!(
+            ret,
+            r#"<tr><td>{}</td><td>{}</td><td class="num">{:.2}</td><td class="num {class}">{}</td></tr>"#,
+            svg::escape(&mover.stock_no),
+            svg::escape(&mover.name),
+            mover.close,
+            change_rate_text(mover.change_rate)
+        );
+    }
+    let _ = writeln!(ret, "</table>");
+}
+
+/// 單一檔案的 HTML 版本，K 線圖直接內嵌 SVG
+pub fn html(report: &DailyReport) -> String {
+    let mut ret = String::new();
+    let _ = writeln!(ret, "<!DOCTYPE html>");
+    let _ = writeln!(
+        ret,
+        r#"<html lang="zh-Hant"><head><meta charset="utf-8"><title>每日報告 {0}</title><style>{HTML_STYLE}</style></head><body>"#,
+        report.roc_date
+    );
+    let _ = writeln!(ret, "<h1>每日報告 {}</h1>", report.roc_date);
+
+    let _ = writeln!(ret, "<h2>市場概況</h2><ul>");
+    for line in summary_lines(&report.summary) {
+        let _ = writeln!(ret, "<li>{line}</li>");
+    }
+    let _ = writeln!(ret, "</ul>");
+    html_movers(&mut ret, "漲幅前五", &report.summary.gainers);
+    html_movers(&mut ret, "跌幅前五", &report.summary.losers);
+
+    for section in &report.sections {
+        let _ = writeln!(ret, "<h2>{}</h2>", svg::escape(&section.name));
+        let _ = writeln!(
+            ret,
+            r#"<p class="expr">{}</p>"#,
+            svg::escape(&section.expression)
+        );
+        if let Some(error) = &section.error {
+            let _ = writeln!(ret, "<p>{}</p>", svg::escape(error));
+            continue;
+        }
+        if section.rows.is_empty() {
+            let _ = writeln!(ret, "<p>沒有符合的股票</p>");
+            continue;
+        }
+
+        let _ = writeln!(ret, "<p>共 {} 檔</p>", section.rows.len());
+        let _ = write!(ret, "<table><tr>");
+        for column in &section.columns {
+            let _ = write!(ret, "<th>{}</th>", column.title);
+        }
+        let _ = writeln!(ret, "</tr>");
+        for row in &section.rows {
+            let _ = write!(ret, "<tr>");
+            for (column, value) in section.columns.iter().zip(row) {
+                let _ = write!(
+                    ret,
+                    "<td{}>{}</td>",
+                    html_class(column, value),
+                    svg::escape(&format_cell(column.kind, value))
+                );
+            }
+            let _ = writeln!(ret, "</tr>");
+        }
+        let _ = writeln!(ret, "</table>");
+
+        if !section.charts.is_empty() {
+            let _ = writeln!(ret, r#"<div class="charts">"#);
+            for chart in &section.charts {
+                ret.push_str(&chart.svg);
+            }
+            let _ = writeln!(ret, "</div>");
+        }
+    }
+
+    let _ = writeln!(ret, "</body></html>");
+    ret
+}
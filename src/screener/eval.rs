@@ -0,0 +1,373 @@
+use std::cell::OnceCell;
+
+use ta::Next;
+use ta::indicators::ExponentialMovingAverage as Ema;
+
+use crate::analysis::breadth::BreadthPoint;
+use crate::analysis::bullish_engulfing_pattern;
+use crate::analysis::doji;
+use crate::analysis::indicators::{self, CrossType, KdValue, MacdValue};
+use crate::analysis::long_red_candle;
+use crate::data::monthly_data::DailyData;
+use crate::screener::parser::{BinaryOp, Expr, Indicator};
+
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Number(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn number(self) -> Option<f64> {
+        match self {
+            Value::Number(value) => Some(value),
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn bool(self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(value),
+            Value::Number(_) => None,
+        }
+    }
+}
+
+/// 單一股票的日資料 (依日期排序)，MACD/KD 只在用到時計算一次
+pub struct SeriesContext<'a> {
+    daily_data: &'a [DailyData],
+    shares: Option<u64>,         // 流通在外股數，沒有時市值/週轉率視為資料不足
+    breadth: &'a [BreadthPoint], // 市場寬度 (依日期排序)，沒有時寬度指標視為資料不足
+    closes: Vec<f64>,
+    macd: OnceCell<Vec<MacdValue>>,
+    kd: OnceCell<Vec<KdValue>>,
+}
+
+impl<'a> SeriesContext<'a> {
+    pub fn new(daily_data: &'a [DailyData]) -> Self {
+        SeriesContext {
+            daily_data,
+            shares: None,
+            breadth: &[],
+            closes: daily_data.iter().map(|d| d.close).collect(),
+            macd: OnceCell::new(),
+            kd: OnceCell::new(),
+        }
+    }
+
+    pub fn with_shares(mut self, shares: Option<u64>) -> Self {
+        self.shares = shares;
+        self
+    }
+
+    pub fn with_breadth(mut self, breadth: &'a [BreadthPoint]) -> Self {
+        self.breadth = breadth;
+        self
+    }
+
+    /// 第 i 個交易日的市場寬度
+    fn breadth_at(&self, i: usize) -> Option<&BreadthPoint> {
+        let date = &self.daily_data.get(i)?.date;
+        self.breadth
+            .binary_search_by(|point| point.date.cmp(date))
+            .ok()
+            .map(|index| &self.breadth[index])
+    }
+
+    pub fn index_of(&self, roc_date: &str) -> Option<usize> {
+        self.daily_data.iter().position(|d| d.date == roc_date)
+    }
+
+    /// 判斷第 index 個交易日是否符合條件；資料不足時視為不符合
+    pub fn matches(&self, expr: &Expr, index: usize) -> bool {
+        self.eval(expr, index)
+            .and_then(Value::bool)
+            .unwrap_or(false)
+    }
+
+    fn macd(&self) -> &[MacdValue] {
+        self.macd.get_or_init(|| indicators::macd(&self.closes))
+    }
+
+    fn kd(&self) -> &[KdValue] {
+        self.kd.get_or_init(|| indicators::kd(self.daily_data, 9))
+    }
+
+    fn eval(&self, expr: &Expr, i: usize) -> Option<Value> {
+        match expr {
+            Expr::Number(value) => Some(Value::Number(*value)),
+            Expr::Indicator(indicator) => self.indicator(indicator, i),
+            Expr::Neg(inner) => Some(Value::Number(-self.eval(inner, i)?.number()?)),
+            Expr::Not(inner) => Some(Value::Bool(!self.eval(inner, i)?.bool()?)),
+            Expr::Binary(BinaryOp::And, lhs, rhs) => {
+                if !self.eval(lhs, i)?.bool()? {
+                    return Some(Value::Bool(false));
+                }
+                Some(Value::Bool(self.eval(rhs, i)?.bool()?))
+            }
+            Expr::Binary(BinaryOp::Or, lhs, rhs) => {
+                if self.eval(lhs, i)?.bool()? {
+                    return Some(Value::Bool(true));
+                }
+                Some(Value::Bool(self.eval(rhs, i)?.bool()?))
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let a = self.eval(lhs, i)?.number()?;
+                let b = self.eval(rhs, i)?.number()?;
+                Some(match op {
+                    BinaryOp::Add => Value::Number(a + b),
+                    BinaryOp::Sub => Value::Number(a - b),
+                    BinaryOp::Mul => Value::Number(a * b),
+                    BinaryOp::Div => {
+                        if b == 0.0 {
+                            return None;
+                        }
+                        Value::Number(a / b)
+                    }
+                    BinaryOp::Lt => Value::Bool(a < b),
+                    BinaryOp::Le => Value::Bool(a <= b),
+                    BinaryOp::Gt => Value::Bool(a > b),
+                    BinaryOp::Ge => Value::Bool(a >= b),
+                    BinaryOp::Eq => Value::Bool((a - b).abs() < 1e-9),
+                    BinaryOp::Ne => Value::Bool((a - b).abs() >= 1e-9),
+                    BinaryOp::And | BinaryOp::Or => unreachable!(),
+                })
+            }
+        }
+    }
+
+    fn indicator(&self, indicator: &Indicator, i: usize) -> Option<Value> {
+        let daily = self.daily_data.get(i)?;
+        let number = |value: f64| Some(Value::Number(value));
+        let bool = |value: bool| Some(Value::Bool(value));
+
+        match indicator {
+            Indicator::Open => number(daily.open),
+            Indicator::High => number(daily.high),
+            Indicator::Low => number(daily.low),
+            Indicator::Close => number(daily.close),
+            Indicator::Volume => number(daily.volume as f64 / 1000.0),
+            Indicator::Amount => number(daily.amount as f64),
+            Indicator::Change => number(daily.change),
+            Indicator::ChangePct => {
+                let prev_close = daily.close - daily.change;
+                if prev_close <= 0.0 {
+                    return None;
+                }
+                number(daily.change / prev_close * 100.0)
+            }
+            Indicator::Transactions => number(daily.transactions as f64),
+            Indicator::MarketCap => number(self.shares? as f64 * daily.close / 1e8),
+            Indicator::TurnoverRate => {
+                let shares = self.shares.filter(|s| *s > 0)?;
+                number(daily.volume as f64 / shares as f64 * 100.0)
+            }
+            Indicator::Ma(n) => {
+                if i + 1 < *n {
+                    return None;
+                }
+                number(self.closes[i + 1 - n..=i].iter().sum::<f64>() / *n as f64)
+            }
+            Indicator::Ema(n) => {
+                if i + 1 < *n {
+                    return None;
+                }
+                let mut ema = Ema::new(*n).ok()?;
+                let mut value = 0.0;
+                for close in &self.closes[..=i] {
+                    value = ema.next(*close);
+                }
+                number(value)
+            }
+            Indicator::AvgVolume(n) => {
+                if i + 1 < *n {
+                    return None;
+                }
+                let sum: u64 = self.daily_data[i + 1 - n..=i]
+                    .iter()
+                    .map(|d| d.volume)
+                    .sum();
+                number(sum as f64 / 1000.0 / *n as f64)
+            }
+            Indicator::Highest(n) => {
+                if i < *n {
+                    return None;
+                }
+                number(
+                    self.daily_data[i - n..i]
+                        .iter()
+                        .map(|d| d.high)
+                        .fold(f64::MIN, f64::max),
+                )
+            }
+            Indicator::Lowest(n) => {
+                if i < *n {
+                    return None;
+                }
+                number(
+                    self.daily_data[i - n..i]
+                        .iter()
+                        .map(|d| d.low)
+                        .fold(f64::MAX, f64::min),
+                )
+            }
+            Indicator::MacdDif => number(self.macd()[i].dif),
+            Indicator::MacdSignal => number(self.macd()[i].signal),
+            Indicator::MacdHistogram => number(self.macd()[i].dif - self.macd()[i].signal),
+            Indicator::MacdGoldenCross | Indicator::MacdDeathCross => {
+                if i == 0 {
+                    return bool(false);
+                }
+                let macd = self.macd();
+                let cross = indicators::cross(
+                    macd[i - 1].dif,
+                    macd[i - 1].signal,
+                    macd[i].dif,
+                    macd[i].signal,
+                );
+                bool(match indicator {
+                    Indicator::MacdGoldenCross => cross == Some(CrossType::GoldenCross),
+                    _ => cross == Some(CrossType::DeathCross),
+                })
+            }
+            Indicator::KdK => number(self.kd()[i].k),
+            Indicator::KdD => number(self.kd()[i].d),
+            Indicator::KdGoldenCross | Indicator::KdDeathCross => {
+                if i == 0 {
+                    return bool(false);
+                }
+                let kd = self.kd();
+                let cross = indicators::cross(kd[i - 1].k, kd[i - 1].d, kd[i].k, kd[i].d);
+                bool(match indicator {
+                    Indicator::KdGoldenCross => cross == Some(CrossType::GoldenCross),
+                    _ => cross == Some(CrossType::DeathCross),
+                })
+            }
+            Indicator::Doji(tolerance) => bool(doji::is_doji(daily, *tolerance)),
+            Indicator::LongRed(ratio) => bool(long_red_candle::is_long_red_candle(daily, *ratio)),
+            Indicator::BullishEngulfing => bool(
+                i > 0
+                    && bullish_engulfing_pattern::is_bullish_engulfing(
+                        &self.daily_data[i - 1],
+                        daily,
+                    ),
+            ),
+            Indicator::Advances => number(self.breadth_at(i)?.advances as f64),
+            Indicator::Declines => number(self.breadth_at(i)?.declines as f64),
+            Indicator::McClellan => number(self.breadth_at(i)?.mcclellan),
+            Indicator::AboveMa(n) => number(self.breadth_at(i)?.above_ma(*n)? * 100.0),
+            Indicator::NewHighs => number(self.breadth_at(i)?.new_highs as f64),
+            Indicator::NewLows => number(self.breadth_at(i)?.new_lows as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screener::parser;
+
+    /// 收盤價依序為 closes 的日資料，每日成交 2,000 張
+    fn series(closes: &[f64]) -> Vec<DailyData> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| DailyData {
+                date: format!("115/01/{:02}", i + 1),
+                volume: 2_000_000,
+                amount: (close * 2_000_000.0) as u64,
+                open: *close,
+                high: close + 0.5,
+                low: close - 0.5,
+                close: *close,
+                change: if i == 0 { 0.0 } else { close - closes[i - 1] },
+                transactions: 100,
+                note: String::new(),
+            })
+            .collect()
+    }
+
+    fn matches(daily_data: &[DailyData], input: &str, index: usize) -> bool {
+        let expr = parser::parse(input).unwrap();
+        SeriesContext::new(daily_data).matches(&expr, index)
+    }
+
+    #[test]
+    fn compares_price_with_moving_average() {
+        let daily_data = series(&[10.0, 11.0, 12.0, 13.0, 14.0]);
+        assert!(matches(&daily_data, "close > ma(3)", 4));
+        assert!(matches(&daily_data, "ma(3) == 13", 4));
+        assert!(!matches(&daily_data, "close < ma(3)", 4));
+    }
+
+    #[test]
+    fn insufficient_data_does_not_match() {
+        let daily_data = series(&[10.0, 11.0, 12.0]);
+        assert!(!matches(&daily_data, "close > ma(3)", 1));
+        assert!(!matches(&daily_data, "not close > ma(3)", 1));
+        assert!(!matches(&daily_data, "close > 0", 3));
+    }
+
+    #[test]
+    fn highest_excludes_the_current_day() {
+        let daily_data = series(&[10.0, 11.0, 12.0, 13.0, 14.0]);
+        assert!(matches(&daily_data, "highest(3) == 13.5", 4));
+        assert!(matches(&daily_data, "close > highest(3)", 4));
+        assert!(matches(&daily_data, "lowest(3) == 10.5", 4));
+    }
+
+    #[test]
+    fn volume_is_in_lots() {
+        let daily_data = series(&[10.0, 11.0]);
+        assert!(matches(&daily_data, "volume == 2000", 1));
+        assert!(matches(&daily_data, "avg_volume(2) == 2000", 1));
+    }
+
+    #[test]
+    fn change_pct_uses_previous_close() {
+        let daily_data = series(&[10.0, 11.0]);
+        assert!(matches(&daily_data, "change_pct == 10", 1));
+    }
+
+    #[test]
+    fn logical_operators() {
+        let daily_data = series(&[10.0, 11.0]);
+        assert!(matches(&daily_data, "close > 100 or close > 1", 1));
+        assert!(!matches(&daily_data, "close > 100 and close > 1", 1));
+        assert!(matches(&daily_data, "not close > 100", 1));
+        // 左邊已決定結果時不評估右邊，資料不足也不影響
+        assert!(matches(&daily_data, "close > 1 or close > ma(60)", 1));
+    }
+
+    #[test]
+    fn division_by_zero_does_not_match() {
+        let daily_data = series(&[10.0, 11.0]);
+        assert!(!matches(&daily_data, "close / (close - close) > 0", 1));
+    }
+
+    #[test]
+    fn market_cap_needs_shares() {
+        let daily_data = series(&[10.0, 14.0]);
+        let expr = parser::parse("market_cap == 14").unwrap();
+        assert!(!SeriesContext::new(&daily_data).matches(&expr, 1));
+        assert!(
+            SeriesContext::new(&daily_data)
+                .with_shares(Some(100_000_000))
+                .matches(&expr, 1)
+        );
+    }
+
+    #[test]
+    fn breadth_without_data_does_not_match() {
+        let daily_data = series(&[10.0, 11.0]);
+        assert!(!matches(&daily_data, "breadth.advances >= 0", 1));
+    }
+
+    #[test]
+    fn finds_index_by_roc_date() {
+        let daily_data = series(&[10.0, 11.0, 12.0]);
+        let context = SeriesContext::new(&daily_data);
+        assert_eq!(context.index_of("115/01/02"), Some(1));
+        assert_eq!(context.index_of("115/01/09"), None);
+    }
+}
@@ -0,0 +1,154 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Ident(String), // 可含 "."，例如 macd.golden_cross
+    LParen,
+    RParen,
+    Comma,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                i += 1;
+            }
+            '<' | '>' | '=' | '!' | '&' | '|' => {
+                let next = chars.get(i + 1).copied();
+                let (token, len) = match (c, next) {
+                    ('<', Some('=')) => (Token::Le, 2),
+                    ('<', _) => (Token::Lt, 1),
+                    ('>', Some('=')) => (Token::Ge, 2),
+                    ('>', _) => (Token::Gt, 1),
+                    ('=', Some('=')) => (Token::Eq, 2),
+                    ('=', _) => (Token::Eq, 1),
+                    ('!', Some('=')) => (Token::Ne, 2),
+                    ('!', _) => (Token::Not, 1),
+                    ('&', Some('&')) => (Token::And, 2),
+                    ('|', Some('|')) => (Token::Or, 2),
+                    _ => return Err(format!("位置 {i}: 無法辨識的符號 '{c}'")),
+                };
+                tokens.push(token);
+                i += len;
+            }
+            _ if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text
+                    .parse()
+                    .map_err(|_| format!("位置 {start}: 無效的數字 '{text}'"))?;
+                tokens.push(Token::Number(value));
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect::<String>().to_lowercase();
+                tokens.push(match text.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(text),
+                });
+            }
+            _ => return Err(format!("位置 {i}: 無法辨識的字元 '{c}'")),
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizes_operators_and_identifiers() {
+        assert_eq!(
+            tokenize("MA(20) >= 1.5 && !kd.golden_cross").unwrap(),
+            vec![
+                Token::Ident("ma".to_string()),
+                Token::LParen,
+                Token::Number(20.0),
+                Token::RParen,
+                Token::Ge,
+                Token::Number(1.5),
+                Token::And,
+                Token::Not,
+                Token::Ident("kd.golden_cross".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keywords_are_case_insensitive() {
+        assert_eq!(
+            tokenize("a AND b Or NOT c").unwrap(),
+            vec![
+                Token::Ident("a".to_string()),
+                Token::And,
+                Token::Ident("b".to_string()),
+                Token::Or,
+                Token::Not,
+                Token::Ident("c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_characters_and_bad_numbers() {
+        assert!(tokenize("close # 1").is_err());
+        assert!(tokenize("close & 1").is_err());
+        assert!(tokenize("close > 1.2.3").is_err());
+    }
+}
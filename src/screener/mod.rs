@@ -0,0 +1,4 @@
+pub mod eval;
+pub mod lexer;
+pub mod parser;
+pub mod saved;
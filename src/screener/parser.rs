@@ -0,0 +1,543 @@
+use crate::analysis::breadth;
+use crate::config;
+use crate::screener::lexer::{self, Token};
+
+/// 可在選股條件中使用的指標
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indicator {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume, // 成交張數
+    Amount, // 成交金額
+    Change,
+    ChangePct,
+    Transactions,
+    MarketCap,    // 市值 (億元)
+    TurnoverRate, // 週轉率 (%)
+    Ma(usize),
+    Ema(usize),
+    AvgVolume(usize), // n 日平均成交張數
+    Highest(usize),   // 前 n 日 (不含當日) 最高價
+    Lowest(usize),    // 前 n 日 (不含當日) 最低價
+    MacdDif,
+    MacdSignal,
+    MacdHistogram,
+    MacdGoldenCross,
+    MacdDeathCross,
+    KdK,
+    KdD,
+    KdGoldenCross,
+    KdDeathCross,
+    Doji(f64),
+    LongRed(f64),
+    BullishEngulfing,
+    // 市場寬度 (全市場，與個股無關)，可當作多空環境過濾；騰落線的值取決於起算日，不提供
+    Advances,
+    Declines,
+    McClellan,
+    AboveMa(usize), // 站上 n 日均線的股票比例 (%)
+    NewHighs,
+    NewLows,
+}
+
+impl Indicator {
+    fn resolve(name: &str, args: &[f64]) -> Result<Self, String> {
+        let period = |default: Option<usize>| -> Result<usize, String> {
+            match (args, default) {
+                ([n], _) if *n >= 1.0 && n.fract() == 0.0 => Ok(*n as usize),
+                ([], Some(n)) => Ok(n),
+                _ => Err(format!("{name} 需要一個正整數參數")),
+            }
+        };
+        let ratio = |default: f64| -> Result<f64, String> {
+            match args {
+                [] => Ok(default),
+                [v] => Ok(*v),
+                _ => Err(format!("{name} 最多一個參數")),
+            }
+        };
+        let no_args = |indicator: Indicator| -> Result<Indicator, String> {
+            if args.is_empty() {
+                Ok(indicator)
+            } else {
+                Err(format!("{name} 不接受參數"))
+            }
+        };
+
+        match name {
+            "open" => no_args(Indicator::Open),
+            "high" => no_args(Indicator::High),
+            "low" => no_args(Indicator::Low),
+            "close" => no_args(Indicator::Close),
+            "volume" => no_args(Indicator::Volume),
+            "amount" => no_args(Indicator::Amount),
+            "change" => no_args(Indicator::Change),
+            "change_pct" => no_args(Indicator::ChangePct),
+            "transactions" => no_args(Indicator::Transactions),
+            "market_cap" => no_args(Indicator::MarketCap),
+            "turnover_rate" => no_args(Indicator::TurnoverRate),
+            "ma" => Ok(Indicator::Ma(period(None)?)),
+            "ema" => Ok(Indicator::Ema(period(None)?)),
+            "avg_volume" => Ok(Indicator::AvgVolume(period(None)?)),
+            "highest" => Ok(Indicator::Highest(period(None)?)),
+            "lowest" => Ok(Indicator::Lowest(period(None)?)),
+            "macd.dif" => no_args(Indicator::MacdDif),
+            "macd.signal" => no_args(Indicator::MacdSignal),
+            "macd.histogram" => no_args(Indicator::MacdHistogram),
+            "macd.golden_cross" => no_args(Indicator::MacdGoldenCross),
+            "macd.death_cross" => no_args(Indicator::MacdDeathCross),
+            "kd.k" => no_args(Indicator::KdK),
+            "kd.d" => no_args(Indicator::KdD),
+            "kd.golden_cross" => no_args(Indicator::KdGoldenCross),
+            "kd.death_cross" => no_args(Indicator::KdDeathCross),
+            "doji" => Ok(Indicator::Doji(ratio(
+                config::get().thresholds.doji_tolerance,
+            )?)),
+            "long_red" => Ok(Indicator::LongRed(ratio(
+                config::get().thresholds.long_red_ratio,
+            )?)),
+            "bullish_engulfing" => no_args(Indicator::BullishEngulfing),
+            "breadth.advances" => no_args(Indicator::Advances),
+            "breadth.declines" => no_args(Indicator::Declines),
+            "breadth.mcclellan" => no_args(Indicator::McClellan),
+            "breadth.above_ma" => {
+                let n = period(None)?;
+                if !breadth::MA_PERIODS.contains(&n) {
+                    return Err(format!("{name} 只支援 {:?} 日均線", breadth::MA_PERIODS));
+                }
+                Ok(Indicator::AboveMa(n))
+            }
+            "breadth.new_highs" => no_args(Indicator::NewHighs),
+            "breadth.new_lows" => no_args(Indicator::NewLows),
+            _ => Err(format!("未知的指標: {name}")),
+        }
+    }
+
+    pub fn is_breadth(&self) -> bool {
+        matches!(
+            self,
+            Indicator::Advances
+                | Indicator::Declines
+                | Indicator::McClellan
+                | Indicator::AboveMa(_)
+                | Indicator::NewHighs
+                | Indicator::NewLows
+        )
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(
+            self,
+            Indicator::MacdGoldenCross
+                | Indicator::MacdDeathCross
+                | Indicator::KdGoldenCross
+                | Indicator::KdDeathCross
+                | Indicator::Doji(_)
+                | Indicator::LongRed(_)
+                | Indicator::BullishEngulfing
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Indicator(Indicator),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Number,
+    Bool,
+}
+
+impl Expr {
+    /// 是否用到市場寬度指標，用到時才需要計算全市場資料
+    pub fn uses_breadth(&self) -> bool {
+        match self {
+            Expr::Number(_) => false,
+            Expr::Indicator(indicator) => indicator.is_breadth(),
+            Expr::Neg(inner) | Expr::Not(inner) => inner.uses_breadth(),
+            Expr::Binary(_, lhs, rhs) => lhs.uses_breadth() || rhs.uses_breadth(),
+        }
+    }
+
+    fn check(&self) -> Result<Type, String> {
+        let expect = |expr: &Expr, expected: Type| -> Result<(), String> {
+            if expr.check()? == expected {
+                Ok(())
+            } else {
+                Err(format!(
+                    "型別錯誤: 此處需要{}",
+                    if expected == Type::Bool {
+                        "條件"
+                    } else {
+                        "數值"
+                    }
+                ))
+            }
+        };
+
+        match self {
+            Expr::Number(_) => Ok(Type::Number),
+            Expr::Indicator(indicator) => Ok(if indicator.is_bool() {
+                Type::Bool
+            } else {
+                Type::Number
+            }),
+            Expr::Neg(inner) => expect(inner, Type::Number).map(|_| Type::Number),
+            Expr::Not(inner) => expect(inner, Type::Bool).map(|_| Type::Bool),
+            Expr::Binary(op, lhs, rhs) => match op {
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                    expect(lhs, Type::Number)?;
+                    expect(rhs, Type::Number)?;
+                    Ok(Type::Number)
+                }
+                BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge
+                | BinaryOp::Eq
+                | BinaryOp::Ne => {
+                    expect(lhs, Type::Number)?;
+                    expect(rhs, Type::Number)?;
+                    Ok(Type::Bool)
+                }
+                BinaryOp::And | BinaryOp::Or => {
+                    expect(lhs, Type::Bool)?;
+                    expect(rhs, Type::Bool)?;
+                    Ok(Type::Bool)
+                }
+            },
+        }
+    }
+}
+
+/// 解析選股條件，結果必須是條件 (布林值)
+/// 例如: macd.golden_cross and volume > 2 * avg_volume(20) and close > ma(60)
+pub fn parse(input: &str) -> Result<Expr, String> {
+    let tokens = lexer::tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("條件不可為空".to_string());
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("多餘的內容: {token:?}"));
+    }
+    if expr.check()? != Type::Bool {
+        return Err("選股條件的結果必須是條件，例如 close > ma(20)".to_string());
+    }
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            let rhs = self.parse_and()?;
+            lhs = Expr::Binary(BinaryOp::Or, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_not()?;
+        while self.eat(&Token::And) {
+            let rhs = self.parse_not()?;
+            lhs = Expr::Binary(BinaryOp::And, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_sum()?;
+        let op = match self.peek() {
+            Some(Token::Lt) => BinaryOp::Lt,
+            Some(Token::Le) => BinaryOp::Le,
+            Some(Token::Gt) => BinaryOp::Gt,
+            Some(Token::Ge) => BinaryOp::Ge,
+            Some(Token::Eq) => BinaryOp::Eq,
+            Some(Token::Ne) => BinaryOp::Ne,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_sum()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_product()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_product()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                if !self.eat(&Token::RParen) {
+                    return Err("缺少 ')'".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => {
+                let mut args = Vec::new();
+                if self.eat(&Token::LParen) && !self.eat(&Token::RParen) {
+                    loop {
+                        let negative = self.eat(&Token::Minus);
+                        match self.next() {
+                            Some(Token::Number(value)) => {
+                                args.push(if negative { -value } else { value })
+                            }
+                            _ => return Err(format!("{name} 的參數必須是數字")),
+                        }
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+                        if !self.eat(&Token::Comma) {
+                            return Err(format!("{name} 的參數缺少 ',' 或 ')'"));
+                        }
+                    }
+                }
+                Ok(Expr::Indicator(Indicator::resolve(&name, &args)?))
+            }
+            Some(token) => Err(format!("非預期的符號: {token:?}")),
+            None => Err("條件不完整".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(value: f64) -> Box<Expr> {
+        Box::new(Expr::Number(value))
+    }
+
+    fn indicator(indicator: Indicator) -> Box<Expr> {
+        Box::new(Expr::Indicator(indicator))
+    }
+
+    fn binary(op: BinaryOp, lhs: Box<Expr>, rhs: Box<Expr>) -> Box<Expr> {
+        Box::new(Expr::Binary(op, lhs, rhs))
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(
+            parse("close > 1 + 2 * 3").unwrap(),
+            *binary(
+                BinaryOp::Gt,
+                indicator(Indicator::Close),
+                binary(
+                    BinaryOp::Add,
+                    number(1.0),
+                    binary(BinaryOp::Mul, number(2.0), number(3.0))
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn arithmetic_is_left_associative() {
+        assert_eq!(
+            parse("close > 10 - 2 - 3").unwrap(),
+            *binary(
+                BinaryOp::Gt,
+                indicator(Indicator::Close),
+                binary(
+                    BinaryOp::Sub,
+                    binary(BinaryOp::Sub, number(10.0), number(2.0)),
+                    number(3.0)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse("macd.golden_cross or kd.golden_cross and bullish_engulfing").unwrap(),
+            *binary(
+                BinaryOp::Or,
+                indicator(Indicator::MacdGoldenCross),
+                binary(
+                    BinaryOp::And,
+                    indicator(Indicator::KdGoldenCross),
+                    indicator(Indicator::BullishEngulfing)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(
+            parse("not macd.golden_cross and kd.golden_cross").unwrap(),
+            *binary(
+                BinaryOp::And,
+                Box::new(Expr::Not(indicator(Indicator::MacdGoldenCross))),
+                indicator(Indicator::KdGoldenCross)
+            )
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            parse("close > (1 + 2) * 3").unwrap(),
+            *binary(
+                BinaryOp::Gt,
+                indicator(Indicator::Close),
+                binary(
+                    BinaryOp::Mul,
+                    binary(BinaryOp::Add, number(1.0), number(2.0)),
+                    number(3.0)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn logical_operators_require_conditions() {
+        for input in [
+            "close and macd.golden_cross",
+            "macd.golden_cross or volume",
+            "not close",
+            "macd.golden_cross > 1",
+            "-macd.golden_cross < 0",
+        ] {
+            let error = parse(input).unwrap_err();
+            assert!(error.contains("型別錯誤"), "{input}: {error}");
+        }
+    }
+
+    #[test]
+    fn result_must_be_a_condition() {
+        assert!(parse("close + 1").is_err());
+        assert!(parse("ma(20)").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_indicators() {
+        assert_eq!(parse("foo > 1").unwrap_err(), "未知的指標: foo");
+        assert_eq!(
+            parse("breadth.ad_line > 0").unwrap_err(),
+            "未知的指標: breadth.ad_line"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arity() {
+        assert!(parse("ma() > 1").is_err());
+        assert!(parse("ma(5, 10) > 1").is_err());
+        assert!(parse("ma(2.5) > 1").is_err());
+        assert!(parse("ma(0) > 1").is_err());
+        assert!(parse("close(1) > 1").is_err());
+        assert!(parse("breadth.above_ma(7) > 50").is_err());
+        assert!(parse("ma(close) > 1").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse("").is_err());
+        assert!(parse("(close > 1").is_err());
+        assert!(parse("close > 1)").is_err());
+        assert!(parse("close >").is_err());
+    }
+
+    #[test]
+    fn detects_breadth_indicators() {
+        assert!(
+            parse("close > ma(20) and breadth.above_ma(20) > 50")
+                .unwrap()
+                .uses_breadth()
+        );
+        assert!(!parse("close > ma(20)").unwrap().uses_breadth());
+    }
+}
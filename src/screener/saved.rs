@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::fs;
+
+use crate::common;
+use crate::config;
+use crate::screener::parser;
+
+/// 儲存的選股條件，一行一個: 名稱 = 條件
+pub const SCREENS_FILE: &str = "screens.txt";
+
+#[derive(Debug, Clone)]
+pub struct SavedScreen {
+    pub name: String,
+    pub expression: String,
+}
+
+fn parse_line(line: &str) -> Option<SavedScreen> {
+    let (name, expression) = line.split_once('=')?;
+    let name = name.trim();
+    // "==" 是條件的一部分，不是名稱分隔
+    if name.is_empty() || expression.starts_with('=') {
+        return None;
+    }
+    Some(SavedScreen {
+        name: name.to_string(),
+        expression: expression.trim().to_string(),
+    })
+}
+
+pub fn load() -> Vec<SavedScreen> {
+    common::read_lines_to_vec(config::path(SCREENS_FILE))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| parse_line(line))
+        .collect()
+}
+
+pub fn find(name: &str) -> Option<SavedScreen> {
+    load().into_iter().find(|screen| screen.name == name)
+}
+
+/// 條件可以是已儲存的名稱或直接輸入的條件式，回傳 (是否為已儲存的名稱, 條件式)
+pub fn resolve(input: &str) -> (bool, String) {
+    match find(input) {
+        Some(screen) => (true, screen.expression),
+        None => (false, input.to_string()),
+    }
+}
+
+/// 儲存條件 (同名則取代)，存檔前會先檢查語法
+pub fn save(name: &str, expression: &str) -> Result<(), Box<dyn Error>> {
+    if name.is_empty() || name.contains('=') {
+        return Err(format!("無效的名稱: {name}").into());
+    }
+    parser::parse(expression)?;
+
+    let path = config::path(SCREENS_FILE);
+    let content = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    let new_line = format!("{name} = {expression}");
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let clean = line.split("//").next().unwrap().trim();
+            if parse_line(clean).is_some_and(|screen| screen.name == name) {
+                replaced = true;
+                new_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(new_line);
+    }
+
+    fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
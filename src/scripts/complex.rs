@@ -1,23 +1,46 @@
+use serde::Serialize;
+use serde_json::Value;
+
 use crate::analysis;
 use crate::common;
+use crate::config;
 use crate::data::monthly_data::DailyData;
+use crate::output::record::{self, Column, ColumnKind, Record};
 use crate::scripts;
 use crate::twse::company_map::CompanyMap;
 
+#[derive(Serialize)]
 pub struct MacdGoldenVolumeLargerResult {
     pub macd_cross: analysis::macd::MacdCross,
     pub volume_result: analysis::volume::VolumeAnalysisResult,
 }
 
-impl MacdGoldenVolumeLargerResult {
-    pub fn print(&self, company_map: &CompanyMap) {
-        println!(
-            "{:<10} {:<10} {:>10} {}",
-            self.macd_cross.stock_no,
-            self.macd_cross.date,
-            common::format_commas(common::divide_by_1000(self.volume_result.daily_data.volume)),
-            company_map.get(&self.macd_cross.stock_no)
-        );
+impl Record for MacdGoldenVolumeLargerResult {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("volume", "成交張數", ColumnKind::Integer),
+            Column::new("close", "收盤價", ColumnKind::Price),
+            Column::new("dif", "DIF", ColumnKind::Price),
+            Column::new("macd_signal", "MACD", ColumnKind::Price),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        let stock_no = &self.macd_cross.stock_no;
+        vec![
+            record::text(stock_no),
+            record::text(&self.macd_cross.date),
+            Value::from(common::divide_by_1000(self.volume_result.daily_data.volume)),
+            record::number(self.volume_result.daily_data.close),
+            record::number(self.macd_cross.dif),
+            record::number(self.macd_cross.macd_signal),
+            record::text(&company_map.get_name(stock_no)),
+            record::text(&company_map.get_industry(stock_no)),
+        ]
     }
 }
 
@@ -72,6 +95,7 @@ pub async fn anal_macd_golden_volume_larger_date(
     ret_results
 }
 
+#[derive(Serialize)]
 pub struct DojiInSwingResult {
     pub stock_no: String,
     pub daily_data: DailyData,
@@ -81,6 +105,40 @@ pub struct DojiInSwingResult {
     pub meet_low: bool,
 }
 
+impl Record for DojiInSwingResult {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "日期", ColumnKind::Text),
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("close", "現價", ColumnKind::Price),
+            Column::new("highest_price", "最高價", ColumnKind::Price),
+            Column::new("lowest_price", "最低價", ColumnKind::Price),
+            Column::new("meet_high", "高", ColumnKind::Bool),
+            Column::new("meet_low", "低", ColumnKind::Bool),
+            Column::new("swing_high", "波段高標", ColumnKind::Price),
+            Column::new("swing_low", "波段低標", ColumnKind::Price),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.daily_data.date),
+            record::text(&self.stock_no),
+            record::number(self.daily_data.close),
+            record::number(self.highest_price),
+            record::number(self.lowest_price),
+            Value::Bool(self.meet_high),
+            Value::Bool(self.meet_low),
+            record::number(self.daily_data.close * config::get().thresholds.swing_high_ratio),
+            record::number(self.daily_data.close * config::get().thresholds.swing_low_ratio),
+            record::text(&company_map.get_name(&self.stock_no)),
+            record::text(&company_map.get_industry(&self.stock_no)),
+        ]
+    }
+}
+
 pub async fn anal_doji_in_swing_all_companies(
     company_map: &CompanyMap,
     year_month_from: &str,
@@ -91,6 +149,7 @@ pub async fn anal_doji_in_swing_all_companies(
 
     let results = scripts::doji::anal_date_all_companies(company_map, year_month_date).await;
 
+    let thresholds = &config::get().thresholds;
     for result in &results {
         // get range analysis
         let range_result = analysis::range::anal_range_high_low_company(
@@ -104,11 +163,11 @@ pub async fn anal_doji_in_swing_all_companies(
         let mut meet_high = false;
         let mut meet_low = false;
 
-        if range_result.highest_price >= result.daily_data.close * 1.3 {
+        if range_result.highest_price >= result.daily_data.close * thresholds.swing_high_ratio {
             meet_high = true;
         }
 
-        if range_result.lowest_price <= result.daily_data.close * 0.7 {
+        if range_result.lowest_price <= result.daily_data.close * thresholds.swing_low_ratio {
             meet_low = true;
         }
 
@@ -124,7 +183,7 @@ pub async fn anal_doji_in_swing_all_companies(
             doji_in_swing_results.push(doji_in_swing_result);
         }
     }
-    doji_in_swing_results.sort_by(|a, b| b.daily_data.volume.cmp(&a.daily_data.volume));
+    doji_in_swing_results.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
 
     doji_in_swing_results
 }
@@ -1,12 +1,230 @@
-use crate::data::monthly_data::MonthlyData;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+
+use crate::alerts;
+use crate::alerts::evaluator::AlertRun;
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::{self, DailyData, MonthlyData};
+use crate::portfolio::holdings;
 use crate::twse::company_map::CompanyMap;
 
+/// 用來判斷當日資料是否已公布的高成交量股票，不受公司清單順序影響；
+/// 都不在清單中時改用清單的前幾檔
+const PUBLISH_PROBES: [&str; 5] = ["2330", "2317", "2454", "2881", "2303"];
+
 pub async fn fetch_data_monthly_all_companies(company_map: &CompanyMap, year_month: &str) {
-    for company in &company_map.stock_map {
-        let _monthly_data = MonthlyData::new(company_map, &company.stock_no, year_month).await;
+    fetch_data_monthly_with_progress(company_map, year_month, |_, _| {}).await;
+}
+
+/// 要抓取的股票：掃描範圍，加上仍持有但不在範圍內 (例如被排除) 的股票，
+/// 讓投資組合評價有最新的收盤價
+pub fn fetch_targets(company_map: &CompanyMap) -> Vec<String> {
+    let mut targets: Vec<String> = company_map
+        .stock_map
+        .iter()
+        .map(|company| company.stock_no.clone())
+        .collect();
+
+    let path = config::path(holdings::HOLDINGS_FILE);
+    let holdings = match holdings::load_holdings(&path) {
+        Ok(holdings) => holdings,
+        Err(e) => {
+            if path.exists() {
+                common::log(&format!("讀取 {} 失敗: {e}", path.display()));
+            }
+            Vec::new()
+        }
+    };
+    for stock_no in holdings::held_stock_nos(&holdings) {
+        if company_map.position(&stock_no).is_none() && company_map.get(&stock_no).is_some() {
+            targets.push(stock_no);
+        }
+    }
+    targets
+}
+
+/// 同 fetch_data_monthly_all_companies，每檔抓取前呼叫 progress(已完成檔數, 股號)；
+/// 總檔數為 fetch_targets 的數量
+pub async fn fetch_data_monthly_with_progress<F>(
+    company_map: &CompanyMap,
+    year_month: &str,
+    mut progress: F,
+) where
+    F: FnMut(usize, &str),
+{
+    for (i, stock_no) in fetch_targets(company_map).iter().enumerate() {
+        progress(i, stock_no);
+        let _monthly_data = MonthlyData::new_or_empty(company_map, stock_no, year_month).await;
     }
 }
 
+/// 抓取單月全部股票資料後評估警示，命令列、選單與全螢幕介面共用
+pub async fn fetch_data_monthly_and_alerts<F>(
+    company_map: &CompanyMap,
+    year_month: &str,
+    progress: F,
+) -> Result<AlertRun, Box<dyn Error>>
+where
+    F: FnMut(usize, &str),
+{
+    fetch_data_monthly_with_progress(company_map, year_month, progress).await;
+    alerts::evaluator::run(company_map, year_month).await
+}
+
+/// 抓取單日資料的結果
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FetchSummary {
+    pub with_date: usize, // 有當日資料的檔數；0 表示尚未公布或休市
+    pub failed: usize,    // 抓取失敗的檔數
+    pub total: usize,
+    pub closed: bool, // 探測的股票都沒有當日資料，但已有更晚的交易日：當日休市
+}
+
+/// 本地有當日資料就直接讀取，否則重新抓取該月
+async fn load_with_date(
+    company_map: &CompanyMap,
+    stock_no: &str,
+    year_month: &str,
+    roc_date: &str,
+) -> Result<MonthlyData, Box<dyn Error + Send + Sync>> {
+    match MonthlyData::from_storage(stock_no, year_month) {
+        Some(monthly_data) if monthly_data.daily_data.iter().any(|d| d.date == roc_date) => {
+            Ok(monthly_data)
+        }
+        _ => MonthlyData::fetch(company_map, stock_no, year_month).await,
+    }
+}
+
+/// 探測的股票：PUBLISH_PROBES 中在清單內的，都不在時用清單的前幾檔
+fn publish_probes(company_map: &CompanyMap) -> Vec<&str> {
+    let probes: Vec<&str> = PUBLISH_PROBES
+        .into_iter()
+        .filter(|stock_no| company_map.position(stock_no).is_some())
+        .collect();
+    if !probes.is_empty() {
+        return probes;
+    }
+    company_map
+        .stock_map
+        .iter()
+        .take(PUBLISH_PROBES.len())
+        .map(|company| company.stock_no.as_str())
+        .collect()
+}
+
+/// 確保每檔都有 year_month_date 當日的資料，本地沒有就重新抓取該月
+pub async fn fetch_data_date_all_companies(
+    company_map: &CompanyMap,
+    year_month_date: &str,
+) -> FetchSummary {
+    let (Some(year_month), Some(roc_date)) = (
+        common::year_month_of(year_month_date),
+        common::to_roc_date(year_month_date),
+    ) else {
+        return FetchSummary::default();
+    };
+    let has_date = |monthly_data: &MonthlyData| {
+        monthly_data
+            .daily_data
+            .iter()
+            .any(|daily| daily.date == roc_date)
+    };
+
+    let targets = fetch_targets(company_map);
+    let mut summary = FetchSummary {
+        total: targets.len(),
+        ..Default::default()
+    };
+
+    // 先看探測的股票，都沒有當日資料時不逐檔抓取
+    let mut probed = Vec::new();
+    for stock_no in publish_probes(company_map) {
+        if let Ok(monthly_data) = load_with_date(company_map, stock_no, year_month, &roc_date).await
+        {
+            probed.push(monthly_data);
+        }
+    }
+    if !probed.iter().any(has_date) {
+        let next_month = common::shift_year_month(year_month, 1).unwrap_or_default();
+        summary.closed = probed.iter().any(|monthly_data| {
+            monthly_data.daily_data.iter().any(|d| d.date > roc_date)
+                || MonthlyData::from_storage(&monthly_data.stock_no, &next_month)
+                    .is_some_and(|next| !next.daily_data.is_empty())
+        });
+        return summary;
+    }
+
+    for stock_no in &targets {
+        let monthly_data = match load_with_date(company_map, stock_no, year_month, &roc_date).await
+        {
+            Ok(monthly_data) => monthly_data,
+            Err(e) => {
+                common::log(&format!(
+                    "Failed to fetch data for {stock_no}/{year_month}: {e}"
+                ));
+                summary.failed += 1;
+                continue;
+            }
+        };
+        if has_date(&monthly_data) {
+            summary.with_date += 1;
+        }
+    }
+    summary
+}
+
+/// 移除本地資料中開高低收有 0 的日資料 (停牌或 TWSE 資料有誤)，回傳被移除的 (股號, 日資料)
+/// 無法解析的檔案略過，下次讀取時會重新抓取
+pub fn clean_stored_data() -> Result<Vec<(String, DailyData)>, Box<dyn Error + Send + Sync>> {
+    // 遞迴走訪
+    fn visit_dir(
+        path: &Path,
+        removed: &mut Vec<(String, DailyData)>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Ok(entries) = fs::read_dir(path) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if p.is_dir() {
+                visit_dir(&p, removed)?;
+                continue;
+            }
+
+            let reader = std::io::BufReader::new(File::open(&p)?);
+            let Ok(mut monthly_data) = serde_json::from_reader::<_, MonthlyData>(reader) else {
+                common::log(&format!("Failed to parse {}, skipped", p.display()));
+                continue;
+            };
+            let is_valid =
+                |d: &DailyData| d.open != 0.0 && d.high != 0.0 && d.low != 0.0 && d.close != 0.0;
+            let invalid: Vec<DailyData> = monthly_data
+                .daily_data
+                .iter()
+                .filter(|d| !is_valid(d))
+                .cloned()
+                .collect();
+            if !invalid.is_empty() {
+                monthly_data.daily_data.retain(is_valid);
+                monthly_data.write_to_storage()?;
+                removed.extend(
+                    invalid
+                        .into_iter()
+                        .map(|daily| (monthly_data.stock_no.clone(), daily)),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    let mut removed = Vec::new();
+    visit_dir(Path::new(monthly_data::data_dir()), &mut removed)?;
+    Ok(removed)
+}
+
 /*
 pub async fn fetch_data_yearly(company_map: &CompanyMap, year: &str) {
     for month in 1..=12 {
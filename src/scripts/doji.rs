@@ -1,11 +1,12 @@
 use crate::analysis::doji;
+use crate::common;
 use crate::data::monthly_data::MonthlyData;
 use crate::twse::company_map::CompanyMap;
 
 /*
 pub async fn anal_month_all_compies(company_map: &CompanyMap, year_month: &str) {
     for company in &company_map.stock_map {
-        let monthly_data = MonthlyData::new(company_map, &company.stock_no, year_month).await;
+        let monthly_data = MonthlyData::new_or_empty(company_map, &company.stock_no, year_month).await;
         doji::anal_month_company(company_map, &monthly_data);
     }
 }
@@ -17,10 +18,13 @@ pub async fn anal_date_all_companies(
 ) -> Vec<doji::DojiAnalysisResult> {
     let mut ret_results = Vec::new();
 
-    let year_month = &year_month_date[0..6];
+    let Some(year_month) = common::year_month_of(year_month_date) else {
+        return ret_results;
+    };
 
     for company in &company_map.stock_map {
-        let monthly_data = MonthlyData::new(company_map, &company.stock_no, year_month).await;
+        let monthly_data =
+            MonthlyData::new_or_empty(company_map, &company.stock_no, year_month).await;
 
         let doji_results = doji::anal_date_company(company_map, &monthly_data, year_month_date);
 
@@ -29,7 +33,7 @@ pub async fn anal_date_all_companies(
         }
     }
 
-    ret_results.sort_by(|a, b| b.daily_data.volume.cmp(&a.daily_data.volume));
+    ret_results.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
 
     ret_results
 }
@@ -9,12 +9,15 @@ pub async fn anal_date_all_companies(
     year_month_date: &str,
 ) -> Vec<analysis::macd::MacdCross> {
     let mut ret_crosses = Vec::new();
+    let Some(roc_date) = common::to_roc_date(year_month_date) else {
+        return ret_crosses;
+    };
     for company in &company_map.stock_map {
         let mut macd_calculator =
             analysis::macd::MacdCalculator::new(&company.stock_no, year_month_from, year_month_to);
         let (_res, crosses) = macd_calculator.calc(company_map).await;
         for cross in &crosses {
-            if cross.date == common::to_roc_date(year_month_date) {
+            if cross.date == roc_date {
                 let clone_cross = analysis::macd::MacdCross {
                     stock_no: cross.stock_no.clone(),
                     date: cross.date.clone(),
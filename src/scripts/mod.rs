@@ -3,4 +3,6 @@ pub mod complex;
 pub mod data;
 pub mod doji;
 pub mod macd;
+pub mod screener;
+pub mod sweep;
 pub mod volume;
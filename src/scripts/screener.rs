@@ -0,0 +1,178 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::analysis::breadth::{self, BreadthPoint};
+use crate::common;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::screener::eval::SeriesContext;
+use crate::screener::parser::Expr;
+use crate::twse::company_map::CompanyMap;
+
+/// 計算指標時往前載入的月份數
+pub const SCREENER_LOOKBACK_MONTHS: i32 = 12;
+/// 結果中迷你走勢圖的交易日數
+pub const SPARKLINE_DAYS: usize = 20;
+
+#[derive(Serialize)]
+pub struct ScreenResult {
+    pub stock_no: String,
+    pub daily_data: DailyData,
+    pub recent_closes: Vec<f64>, // 含當日在內最近 SPARKLINE_DAYS 日收盤價
+}
+
+impl Record for ScreenResult {
+    fn columns() -> Vec<Column> {
+        let mut columns = record::daily_columns();
+        columns.push(Column::new(
+            "recent_closes",
+            "近20日",
+            ColumnKind::Sparkline,
+        ));
+        columns
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        let mut values = record::daily_values(company_map, &self.stock_no, &self.daily_data);
+        values.push(
+            self.recent_closes
+                .iter()
+                .map(|c| record::number(*c))
+                .collect(),
+        );
+        values
+    }
+}
+
+/// 條件用到市場寬度指標時，計算當月的市場寬度；沒用到時為空
+pub fn breadth_for(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    year_month_date: &str,
+) -> Vec<BreadthPoint> {
+    if !expr.uses_breadth() {
+        return Vec::new();
+    }
+    match common::year_month_of(year_month_date) {
+        Some(year_month) => breadth::series(company_map, year_month, year_month),
+        None => Vec::new(),
+    }
+}
+
+/// 選股需要讀取的月份區間 (往前 SCREENER_LOOKBACK_MONTHS 個月) 與民國日期
+fn lookback(year_month_date: &str) -> Option<(String, &str, String)> {
+    let year_month_to = common::year_month_of(year_month_date)?;
+    let year_month_from = common::shift_year_month(year_month_to, -SCREENER_LOOKBACK_MONTHS)?;
+    let roc_date = common::to_roc_date(year_month_date)?;
+    Some((year_month_from, year_month_to, roc_date))
+}
+
+pub async fn screen_date_company(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    breadth: &[BreadthPoint],
+    stock_no: &str,
+    year_month_date: &str,
+) -> Option<ScreenResult> {
+    let (year_month_from, year_month_to, roc_date) = lookback(year_month_date)?;
+    let daily_data = monthly_data::fetch_daily_data_range(
+        company_map,
+        stock_no,
+        &year_month_from,
+        year_month_to,
+    )
+    .await;
+    screen_daily_data(company_map, expr, breadth, stock_no, &daily_data, &roc_date)
+}
+
+/// 同 screen_date_company，只讀本地資料
+pub fn screen_date_company_local(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    breadth: &[BreadthPoint],
+    stock_no: &str,
+    year_month_date: &str,
+) -> Option<ScreenResult> {
+    let (year_month_from, year_month_to, roc_date) = lookback(year_month_date)?;
+    let daily_data = monthly_data::load_daily_data_range(stock_no, &year_month_from, year_month_to);
+    screen_daily_data(company_map, expr, breadth, stock_no, &daily_data, &roc_date)
+}
+
+/// 以已排序的日資料判斷 roc_date 當日是否符合條件
+pub fn screen_daily_data(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    breadth: &[BreadthPoint],
+    stock_no: &str,
+    daily_data: &[DailyData],
+    roc_date: &str,
+) -> Option<ScreenResult> {
+    let shares = company_map
+        .get(stock_no)
+        .and_then(|company| company.shares_outstanding());
+    let context = SeriesContext::new(daily_data)
+        .with_shares(shares)
+        .with_breadth(breadth);
+    let index = context.index_of(roc_date)?;
+    if !context.matches(expr, index) {
+        return None;
+    }
+    Some(ScreenResult {
+        stock_no: stock_no.to_string(),
+        daily_data: daily_data[index].clone(),
+        recent_closes: daily_data[(index + 1).saturating_sub(SPARKLINE_DAYS)..=index]
+            .iter()
+            .map(|d| d.close)
+            .collect(),
+    })
+}
+
+pub async fn screen_date_all_companies(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    year_month_date: &str,
+) -> Vec<ScreenResult> {
+    let breadth = breadth_for(company_map, expr, year_month_date);
+    let mut ret_results = Vec::new();
+    for company in &company_map.stock_map {
+        if let Some(result) = screen_date_company(
+            company_map,
+            expr,
+            &breadth,
+            &company.stock_no,
+            year_month_date,
+        )
+        .await
+        {
+            ret_results.push(result);
+        }
+    }
+
+    ret_results.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
+    ret_results
+}
+
+/// 同 screen_date_all_companies，只讀本地資料，本地沒有資料的股票略過
+pub fn screen_date_all_companies_local(
+    company_map: &CompanyMap,
+    expr: &Expr,
+    year_month_date: &str,
+) -> Vec<ScreenResult> {
+    let breadth = breadth_for(company_map, expr, year_month_date);
+    let mut ret_results: Vec<ScreenResult> = company_map
+        .stock_map
+        .iter()
+        .filter_map(|company| {
+            screen_date_company_local(
+                company_map,
+                expr,
+                &breadth,
+                &company.stock_no,
+                year_month_date,
+            )
+        })
+        .collect();
+
+    ret_results.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
+    ret_results
+}
@@ -0,0 +1,21 @@
+use crate::analysis::sweep::SweepSeries;
+use crate::data::monthly_data;
+use crate::twse::company_map::CompanyMap;
+
+/// 從本地資料載入所有公司的日資料，供參數掃描重複使用
+pub fn load_all_companies(
+    company_map: &CompanyMap,
+    year_month_from: &str,
+    year_month_to: &str,
+) -> Vec<SweepSeries> {
+    let mut all_series = Vec::new();
+    for company in &company_map.stock_map {
+        let daily_data =
+            monthly_data::load_daily_data_range(&company.stock_no, year_month_from, year_month_to);
+        if daily_data.is_empty() {
+            continue;
+        }
+        all_series.push(SweepSeries::new(daily_data));
+    }
+    all_series
+}
@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::common;
+use crate::server::handlers;
+use crate::twse::company_map::CompanyMap;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+pub type AppState = Arc<CompanyMap>;
+
+/// 錯誤一律回傳 {"error": "..."}
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        ApiError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+pub fn router(company_map: AppState) -> Router {
+    Router::new()
+        .route("/companies", get(handlers::companies))
+        .route("/companies/{stock_no}", get(handlers::company))
+        .route("/stocks/{stock_no}/daily", get(handlers::daily))
+        .route("/stocks/{stock_no}/indicators", get(handlers::indicators))
+        .route("/screens", get(handlers::screens))
+        .route("/screen", get(handlers::screen))
+        .with_state(company_map)
+}
+
+/// 啟動 HTTP API，直到程式結束
+pub async fn serve(company_map: CompanyMap, addr: &str) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(addr).await?;
+    common::log(&format!(
+        "HTTP API 已啟動: http://{}",
+        listener.local_addr()?
+    ));
+    axum::serve(listener, router(Arc::new(company_map))).await?;
+    Ok(())
+}
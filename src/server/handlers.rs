@@ -0,0 +1,247 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::analysis::indicators;
+use crate::common;
+use crate::data::monthly_data::{self, DailyData};
+use crate::output::render;
+use crate::screener;
+use crate::scripts;
+use crate::server::app::{ApiError, AppState};
+use crate::twse::company_map::{CompanyInfo, CompanyMap, Market, SecurityType};
+use crate::universe::scope::Scope;
+
+/// 計算指標時往前多讀的月份數，讓 MA60 在區間起點就有值
+const INDICATOR_WARMUP_MONTHS: i32 = 3;
+
+#[derive(Serialize)]
+pub struct CompanyView {
+    stock_no: String,
+    name: String,
+    industry: String,
+    sector: String,
+    market: Market,
+    security_type: SecurityType,
+    shares_outstanding: Option<u64>,
+}
+
+impl CompanyView {
+    fn new(company_map: &CompanyMap, company: &CompanyInfo) -> Self {
+        CompanyView {
+            stock_no: company.stock_no.clone(),
+            name: company.name.clone(),
+            industry: company_map.get_industry(&company.stock_no),
+            sector: company_map.get_sector(&company.stock_no),
+            market: company.market,
+            security_type: company.security_type,
+            shares_outstanding: company.shares_outstanding(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CompaniesQuery {
+    q: Option<String>,                   // 股號或名稱關鍵字
+    industry: Option<String>,            // 產業代號或名稱
+    sector: Option<String>,              // 類股
+    market: Option<Market>,              // twse 或 tpex
+    security_type: Option<SecurityType>, // stock、etf、leveraged_etf 或 inverse_etf
+}
+
+/// GET /companies?q=台積&industry=半導體業&sector=電子&market=twse&security_type=etf，q 依相符程度排序
+pub async fn companies(
+    State(company_map): State<AppState>,
+    Query(query): Query<CompaniesQuery>,
+) -> Result<Json<Vec<CompanyView>>, ApiError> {
+    let industry = match &query.industry {
+        Some(industry) => Some(
+            company_map
+                .industry_code(industry)
+                .ok_or_else(|| ApiError::bad_request(format!("找不到產業: {industry}")))?,
+        ),
+        None => None,
+    };
+
+    Ok(Json(
+        company_map
+            .search(query.q.as_deref().unwrap_or_default())
+            .into_iter()
+            .filter(|company| {
+                industry
+                    .as_ref()
+                    .is_none_or(|code| &company.industry == code)
+            })
+            .filter(|company| {
+                query
+                    .sector
+                    .as_ref()
+                    .is_none_or(|sector| company_map.get_sector(&company.stock_no) == *sector)
+            })
+            .filter(|company| query.market.is_none_or(|market| company.market == market))
+            .filter(|company| {
+                query
+                    .security_type
+                    .is_none_or(|security_type| company.security_type == security_type)
+            })
+            .map(|company| CompanyView::new(&company_map, company))
+            .collect(),
+    ))
+}
+
+fn find_company<'a>(
+    company_map: &'a CompanyMap,
+    stock_no: &str,
+) -> Result<&'a CompanyInfo, ApiError> {
+    company_map
+        .get(stock_no)
+        .ok_or_else(|| ApiError::not_found(format!("找不到股號: {stock_no}")))
+}
+
+/// GET /companies/2330
+pub async fn company(
+    State(company_map): State<AppState>,
+    Path(stock_no): Path<String>,
+) -> Result<Json<CompanyView>, ApiError> {
+    let company = find_company(&company_map, &stock_no)?;
+    Ok(Json(CompanyView::new(&company_map, company)))
+}
+
+#[derive(Deserialize)]
+pub struct RangeQuery {
+    from: String, // YYYYMM
+    to: String,   // YYYYMM
+}
+
+impl RangeQuery {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !common::is_valid_year_month(&self.from) || !common::is_valid_year_month(&self.to) {
+            return Err(ApiError::bad_request("月份格式應為 YYYYMM"));
+        }
+        if self.from > self.to {
+            return Err(ApiError::bad_request("起始月份不可晚於結束月份"));
+        }
+        Ok(())
+    }
+}
+
+/// GET /stocks/2330/daily?from=202601&to=202602，只讀本地資料
+pub async fn daily(
+    State(company_map): State<AppState>,
+    Path(stock_no): Path<String>,
+    Query(range): Query<RangeQuery>,
+) -> Result<Json<Vec<DailyData>>, ApiError> {
+    find_company(&company_map, &stock_no)?;
+    range.validate()?;
+    Ok(Json(monthly_data::load_daily_data_range(
+        &stock_no,
+        &range.from,
+        &range.to,
+    )))
+}
+
+#[derive(Serialize)]
+pub struct IndicatorRow {
+    date: String,
+    close: f64,
+    ma5: Option<f64>,
+    ma20: Option<f64>,
+    ma60: Option<f64>,
+    macd_dif: f64,
+    macd_signal: f64,
+    macd_histogram: f64,
+    k: f64,
+    d: f64,
+}
+
+/// GET /stocks/2330/indicators?from=202601&to=202602，只讀本地資料
+pub async fn indicators(
+    State(company_map): State<AppState>,
+    Path(stock_no): Path<String>,
+    Query(range): Query<RangeQuery>,
+) -> Result<Json<Vec<IndicatorRow>>, ApiError> {
+    find_company(&company_map, &stock_no)?;
+    range.validate()?;
+
+    let (Some(warmup_from), Some(first_date)) = (
+        common::shift_year_month(&range.from, -INDICATOR_WARMUP_MONTHS),
+        common::to_roc_date(&format!("{}01", range.from)),
+    ) else {
+        return Err(ApiError::bad_request("月份格式應為 YYYYMM"));
+    };
+    let daily_data = monthly_data::load_daily_data_range(&stock_no, &warmup_from, &range.to);
+    let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+    let ma5 = indicators::sma(&closes, 5);
+    let ma20 = indicators::sma(&closes, 20);
+    let ma60 = indicators::sma(&closes, 60);
+    let macd = indicators::macd(&closes);
+    let kd = indicators::kd(&daily_data, 9);
+
+    Ok(Json(
+        daily_data
+            .iter()
+            .enumerate()
+            .filter(|(_, daily)| daily.date >= first_date)
+            .map(|(i, daily)| IndicatorRow {
+                date: daily.date.clone(),
+                close: daily.close,
+                ma5: ma5[i],
+                ma20: ma20[i],
+                ma60: ma60[i],
+                macd_dif: macd[i].dif,
+                macd_signal: macd[i].signal,
+                macd_histogram: macd[i].dif - macd[i].signal,
+                k: kd[i].k,
+                d: kd[i].d,
+            })
+            .collect(),
+    ))
+}
+
+/// GET /screens，已儲存的選股條件
+pub async fn screens() -> Json<Value> {
+    Json(Value::Array(
+        screener::saved::load()
+            .into_iter()
+            .map(|screen| json!({ "name": screen.name, "expression": screen.expression }))
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ScreenQuery {
+    expr: String,           // 條件式或已儲存的名稱
+    date: String,           // YYYYMMDD
+    market: Option<Market>, // 只掃描上市或上櫃，預設兩者
+}
+
+/// GET /screen?expr=close>ma(20)&date=20260205&market=tpex，欄位與 --output json 相同；
+/// 只讀本地資料，逐檔讀檔在 blocking 執行緒執行，不占用處理其他請求的 worker
+pub async fn screen(
+    State(company_map): State<AppState>,
+    Query(query): Query<ScreenQuery>,
+) -> Result<Json<Value>, ApiError> {
+    if !common::is_valid_date(&query.date) {
+        return Err(ApiError::bad_request("日期格式應為 YYYYMMDD"));
+    }
+    let (_, expression) = screener::saved::resolve(&query.expr);
+    let expr = screener::parser::parse(&expression)
+        .map_err(|e| ApiError::bad_request(format!("條件錯誤: {e}")))?;
+
+    let scope = query.market.map_or(Scope::All, Scope::Market);
+    let scoped_map = scope
+        .apply(&company_map)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let date = query.date.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        scripts::screener::screen_date_all_companies_local(&scoped_map, &expr, &date)
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("選股失敗: {e}")))?;
+    Ok(Json(json!({
+        "expression": expression,
+        "date": query.date,
+        "results": render::to_objects(&results, &company_map),
+    })))
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::config;
+use crate::twse::company_map::{CompanyInfo, Market};
+
+/// 從 TPEx OpenAPI 抓取上櫃公司清單，欄位與上市公司相同
+pub async fn fetch_company_list() -> Result<Vec<CompanyInfo>, Box<dyn Error>> {
+    let config = config::get();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.twse.timeout_secs))
+        .build()?;
+    let companies = client
+        .get(&config.tpex.company_url)
+        .header("User-Agent", &config.twse.user_agent)
+        .send()
+        .await?
+        .json::<Vec<CompanyInfo>>()
+        .await?;
+
+    Ok(companies
+        .into_iter()
+        .map(|company| CompanyInfo {
+            market: Market::Tpex,
+            ..company
+        })
+        .collect())
+}
@@ -0,0 +1,36 @@
+use std::error::Error;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config;
+use crate::twse::company_map::{CompanyInfo, Market};
+
+#[derive(Debug, Deserialize)]
+struct Quote {
+    #[serde(rename = "SecuritiesCompanyCode")]
+    code: String,
+    #[serde(rename = "CompanyName")]
+    name: String,
+}
+
+/// 從上櫃證券每日收盤行情取出代號 00 開頭的 ETF (包含債券 ETF，例如 00679B)
+pub async fn fetch_etf_list() -> Result<Vec<CompanyInfo>, Box<dyn Error>> {
+    let config = config::get();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.twse.timeout_secs))
+        .build()?;
+    let quotes = client
+        .get(&config.tpex.etf_url)
+        .header("User-Agent", &config.twse.user_agent)
+        .send()
+        .await?
+        .json::<Vec<Quote>>()
+        .await?;
+
+    Ok(quotes
+        .iter()
+        .filter(|quote| quote.code.starts_with("00"))
+        .map(|quote| CompanyInfo::etf(&quote.code, &quote.name, Market::Tpex))
+        .collect())
+}
@@ -0,0 +1,3 @@
+pub mod company_list;
+pub mod etf_list;
+pub mod stock_data;
@@ -0,0 +1,182 @@
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::DailyData;
+
+/// TPEx 個股日成交資訊的回應，一個月一張表
+#[derive(Debug, Deserialize)]
+struct TpexResponse {
+    #[serde(default)]
+    stat: String,
+    #[serde(default)]
+    tables: Vec<TpexTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TpexTable {
+    #[serde(default)]
+    data: Vec<Vec<String>>,
+}
+
+/// "1,234" → 1234，無法解析時為預設值 (0)
+fn number<T: FromStr + Default>(text: &str) -> T {
+    price(text).unwrap_or_default()
+}
+
+/// 開高低收；停牌等沒有成交的日子為 "--"，無法解析時為 None
+fn price<T: FromStr>(text: &str) -> Option<T> {
+    text.trim().replace(',', "").parse().ok()
+}
+
+/// "115/2/3＊" → "115/02/03"，與 TWSE 的民國日期格式一致
+fn roc_date(text: &str) -> Option<String> {
+    let clean: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '/')
+        .collect();
+    let mut parts = clean.split('/').map(|part| part.parse::<u32>().ok());
+    let year = parts.next()??;
+    let month = parts.next()??;
+    let day = parts.next()??;
+    Some(format!("{year}/{month:02}/{day:02}"))
+}
+
+/// 日期, 成交張數, 成交仟元, 開盤, 最高, 最低, 收盤, 漲跌, 筆數
+/// 張與仟元換算成股與元，與 TWSE 的單位一致；日期或開高低收無法解析的列略過
+fn parse_row(row: &[String]) -> Option<DailyData> {
+    if row.len() < 9 {
+        return None;
+    }
+    Some(DailyData {
+        date: roc_date(&row[0])?,
+        volume: number::<u64>(&row[1]) * 1000,
+        amount: number::<u64>(&row[2]) * 1000,
+        open: price(&row[3])?,
+        high: price(&row[4])?,
+        low: price(&row[5])?,
+        close: price(&row[6])?,
+        change: number(&row[7]),
+        transactions: number(&row[8]),
+        note: String::new(),
+    })
+}
+
+/// 向 TPEx 抓取上櫃股票一個月的日資料
+pub async fn fetch_daily_data(
+    stock_no: &str,
+    year_month: &str,
+) -> Result<Vec<DailyData>, Box<dyn Error + Send + Sync>> {
+    if !common::is_valid_year_month(year_month) {
+        return Err(format!("無效的月份: {year_month}").into());
+    }
+    let config = config::get();
+    let date = format!("{}/{}/01", &year_month[0..4], &year_month[4..6]);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.twse.timeout_secs))
+        .build()?;
+    let body_text = client
+        .get(&config.tpex.stock_day_url)
+        .query(&[("code", stock_no), ("date", &date), ("response", "json")])
+        .header("User-Agent", &config.twse.user_agent)
+        .header("Accept", "application/json")
+        .send()
+        .await?
+        .text()
+        .await?;
+
+    if body_text.contains("<html>") {
+        return Err("被 TPEx 暫時封鎖 IP 了（回傳了 HTML）。請增加延遲時間或更換 IP。".into());
+    }
+
+    let response: TpexResponse = serde_json::from_str(&body_text)?;
+    if !response.stat.eq_ignore_ascii_case("ok") {
+        return Err(format!(
+            "TPEx 回傳錯誤狀態: {} for stock_no: {}, year_month: {}",
+            response.stat, stock_no, year_month
+        )
+        .into());
+    }
+
+    Ok(response
+        .tables
+        .iter()
+        .flat_map(|table| table.data.iter())
+        .filter_map(|row| parse_row(row))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn roc_date_pads_and_strips_marks() {
+        assert_eq!(roc_date("115/2/3").as_deref(), Some("115/02/03"));
+        assert_eq!(roc_date("115/02/03＊").as_deref(), Some("115/02/03"));
+        assert_eq!(roc_date("115/12/31").as_deref(), Some("115/12/31"));
+        assert_eq!(roc_date("115/2"), None);
+        assert_eq!(roc_date("日期"), None);
+    }
+
+    #[test]
+    fn parse_row_converts_lots_and_thousands() {
+        let daily = parse_row(&row(&[
+            "115/2/3", "1,234", "56,789", "45.10", "46.00", "44.80", "45.50", "+0.40", "321",
+        ]))
+        .unwrap();
+        assert_eq!(daily.date, "115/02/03");
+        assert_eq!(daily.volume, 1_234_000);
+        assert_eq!(daily.amount, 56_789_000);
+        assert_eq!(daily.open, 45.1);
+        assert_eq!(daily.close, 45.5);
+        assert_eq!(daily.change, 0.4);
+        assert_eq!(daily.transactions, 321);
+    }
+
+    #[test]
+    fn parse_row_rejects_short_rows_and_bad_dates() {
+        assert!(parse_row(&row(&["115/2/3", "1,234"])).is_none());
+        assert!(
+            parse_row(&row(&[
+                "合計", "1,234", "56,789", "45.10", "46.00", "44.80", "45.50", "+0.40", "321",
+            ]))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn parse_row_skips_rows_without_prices() {
+        for fields in [
+            ["115/2/3", "0", "0", "--", "--", "--", "--", "0.00", "0"],
+            [
+                "115/2/3", "1", "45", "45.10", "", "44.80", "45.50", "+0.40", "1",
+            ],
+            [
+                "115/2/3", "1", "45", "45.10", "46.00", "44.80", "N/A", "+0.40", "1",
+            ],
+        ] {
+            assert!(parse_row(&row(&fields)).is_none(), "{fields:?}");
+        }
+    }
+
+    #[test]
+    fn parse_row_defaults_unparseable_counts() {
+        let daily = parse_row(&row(&[
+            "115/2/3", "--", "--", "45.10", "46.00", "44.80", "45.50", "X0.00", "--",
+        ]))
+        .unwrap();
+        assert_eq!(daily.volume, 0);
+        assert_eq!(daily.change, 0.0);
+        assert_eq!(daily.close, 45.5);
+    }
+}
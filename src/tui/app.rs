@@ -0,0 +1,433 @@
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::widgets::ListState;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use stock::common;
+use stock::screener;
+use stock::scripts;
+use stock::twse::company_map::CompanyMap;
+
+use crate::tui::detail::Detail;
+use crate::tui::jobs::{self, BuiltinScreen, JobEvent};
+use crate::tui::results::ResultTable;
+use crate::tui::ui;
+
+const TICK: Duration = Duration::from_millis(100);
+const PAGE: isize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    List,
+    Search,
+    Results,
+    Prompt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    FetchYearMonth,
+    ScreenExpression,
+    ScreenDate(String),
+    Builtin,
+    BuiltinDate(BuiltinScreen),
+    BuiltinThreshold(BuiltinScreen, String),
+}
+
+impl PromptKind {
+    pub fn label(&self) -> String {
+        match self {
+            PromptKind::FetchYearMonth => "抓取月份 (YYYYMM): ".to_string(),
+            PromptKind::ScreenExpression => "選股條件或已儲存的名稱: ".to_string(),
+            PromptKind::ScreenDate(_) | PromptKind::BuiltinDate(_) => {
+                "選股日期 (YYYYMMDD): ".to_string()
+            }
+            PromptKind::Builtin => {
+                let screens: Vec<String> = BuiltinScreen::ALL
+                    .iter()
+                    .enumerate()
+                    .map(|(i, screen)| format!("{} {}", i + 1, screen.name()))
+                    .collect();
+                format!("內建選股 ({}): ", screens.join("  "))
+            }
+            PromptKind::BuiltinThreshold(..) => "成交量閾值 (張): ".to_string(),
+        }
+    }
+}
+
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+/// 背景工作的進度
+pub struct Job {
+    pub name: String,
+    pub done: usize,
+    pub total: usize, // 0 表示無法回報進度
+    pub current: String,
+}
+
+pub struct App {
+    pub company_map: Arc<CompanyMap>,
+    pub focus: Focus,
+    pub search: String,
+    pub filtered: Vec<usize>, // 符合搜尋的 stock_map 索引
+    pub list_state: ListState,
+    pub detail: Option<Detail>,
+    pub results: Option<ResultTable>,
+    pub prompt: Option<Prompt>,
+    pub job: Option<Job>,
+    pub status: String,
+    quit: bool,
+    sender: UnboundedSender<JobEvent>,
+    receiver: UnboundedReceiver<JobEvent>,
+}
+
+/// 全螢幕介面；背景工作在 tokio runtime 上執行，結束時還原終端機
+pub fn run(company_map: CompanyMap) -> io::Result<()> {
+    common::set_quiet(true);
+    let mut terminal = ratatui::try_init()?;
+
+    let mut app = App::new(Arc::new(company_map));
+    let result = app.run(&mut terminal);
+
+    ratatui::restore();
+    common::set_quiet(false);
+    result
+}
+
+impl App {
+    fn new(company_map: Arc<CompanyMap>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut app = App {
+            company_map,
+            focus: Focus::List,
+            search: String::new(),
+            filtered: Vec::new(),
+            list_state: ListState::default(),
+            detail: None,
+            results: None,
+            prompt: None,
+            job: None,
+            status: String::new(),
+            quit: false,
+            sender,
+            receiver,
+        };
+        app.apply_search();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| ui::draw(frame, self))?;
+
+            while let Ok(job_event) = self.receiver.try_recv() {
+                self.on_job_event(job_event);
+            }
+
+            if event::poll(TICK)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.on_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn selected_stock_no(&self) -> Option<&str> {
+        let index = *self.filtered.get(self.list_state.selected()?)?;
+        Some(&self.company_map.stock_map[index].stock_no)
+    }
+
+    /// 依股號或名稱篩選清單，依相符程度排序
+    fn apply_search(&mut self) {
+        self.filtered = self
+            .company_map
+            .search(&self.search)
+            .iter()
+            .filter_map(|company| self.company_map.position(&company.stock_no))
+            .collect();
+        self.list_state
+            .select((!self.filtered.is_empty()).then_some(0));
+        self.load_detail();
+    }
+
+    fn select_offset(&mut self, offset: isize) {
+        if self.filtered.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let next = (current + offset).clamp(0, self.filtered.len() as isize - 1);
+        self.list_state.select(Some(next as usize));
+        self.load_detail();
+    }
+
+    fn load_detail(&mut self) {
+        self.detail = self
+            .selected_stock_no()
+            .map(|stock_no| Detail::load(&self.company_map, stock_no));
+    }
+
+    /// 清除搜尋並選取指定股票
+    fn select_stock(&mut self, stock_no: &str) {
+        self.search.clear();
+        self.apply_search();
+        if let Some(position) = self
+            .filtered
+            .iter()
+            .position(|i| self.company_map.stock_map[*i].stock_no == stock_no)
+        {
+            self.list_state.select(Some(position));
+            self.load_detail();
+        }
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match self.focus {
+            Focus::List => self.on_list_key(key),
+            Focus::Search => self.on_search_key(key),
+            Focus::Results => self.on_results_key(key),
+            Focus::Prompt => self.on_prompt_key(key),
+        }
+    }
+
+    fn on_list_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select_offset(1),
+            KeyCode::PageUp => self.select_offset(-PAGE),
+            KeyCode::PageDown => self.select_offset(PAGE),
+            KeyCode::Home => self.select_offset(isize::MIN / 2),
+            KeyCode::End => self.select_offset(isize::MAX / 2),
+            KeyCode::Char('/') => self.focus = Focus::Search,
+            KeyCode::Char('f') => self.open_prompt(PromptKind::FetchYearMonth),
+            KeyCode::Char('s') => self.open_prompt(PromptKind::ScreenExpression),
+            KeyCode::Char('b') => self.open_prompt(PromptKind::Builtin),
+            KeyCode::Tab if self.results.is_some() => self.focus = Focus::Results,
+            _ => {}
+        }
+    }
+
+    fn on_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.focus = Focus::List,
+            KeyCode::Esc => {
+                self.search.clear();
+                self.apply_search();
+                self.focus = Focus::List;
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+                self.apply_search();
+            }
+            KeyCode::Up => self.select_offset(-1),
+            KeyCode::Down => self.select_offset(1),
+            KeyCode::Char(c) => {
+                self.search.push(c);
+                self.apply_search();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_results_key(&mut self, key: KeyEvent) {
+        let Some(results) = self.results.as_mut() else {
+            self.focus = Focus::List;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Tab | KeyCode::Esc => self.focus = Focus::List,
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => results.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('j') => results.select_offset(1),
+            KeyCode::PageUp => results.select_offset(-PAGE),
+            KeyCode::PageDown => results.select_offset(PAGE),
+            KeyCode::Char(c @ '1'..='9') => results.sort_by(c as usize - '1' as usize),
+            KeyCode::Enter => {
+                if let Some(stock_no) = results.selected_stock_no().map(str::to_string) {
+                    self.select_stock(&stock_no);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind) {
+        if self.job.is_some() {
+            self.status = "背景工作執行中，請稍候".to_string();
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind,
+            input: String::new(),
+        });
+        self.focus = Focus::Prompt;
+    }
+
+    fn on_prompt_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            self.focus = Focus::List;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.prompt = None;
+                self.focus = Focus::List;
+            }
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Char(c) => prompt.input.push(c),
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    self.focus = Focus::List;
+                    self.submit_prompt(prompt);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        let input = prompt.input.trim().to_string();
+        match prompt.kind {
+            PromptKind::FetchYearMonth => {
+                if !common::is_valid_year_month(&input) {
+                    self.status = "無效的月份".to_string();
+                    return;
+                }
+                let total = scripts::data::fetch_targets(&self.company_map).len();
+                self.start_job(&format!("抓取 {input}"), total);
+                jobs::spawn_fetch(self.company_map.clone(), input, self.sender.clone());
+            }
+            PromptKind::ScreenExpression => {
+                let (_, expression) = screener::saved::resolve(&input);
+                if let Err(e) = screener::parser::parse(&expression) {
+                    self.status = format!("條件錯誤: {e}");
+                    return;
+                }
+                self.open_prompt(PromptKind::ScreenDate(expression));
+            }
+            PromptKind::ScreenDate(expression) => {
+                if !common::is_valid_date(&input) {
+                    self.status = "無效的日期".to_string();
+                    return;
+                }
+                self.start_job(
+                    &format!("選股 {expression}"),
+                    self.company_map.stock_map.len(),
+                );
+                jobs::spawn_screen(
+                    self.company_map.clone(),
+                    expression,
+                    input,
+                    self.sender.clone(),
+                );
+            }
+            PromptKind::Builtin => {
+                let screen = input
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| BuiltinScreen::ALL.get(i));
+                match screen {
+                    Some(screen) => self.open_prompt(PromptKind::BuiltinDate(*screen)),
+                    None => self.status = "無效的選項".to_string(),
+                }
+            }
+            PromptKind::BuiltinDate(screen) => {
+                if !common::is_valid_date(&input) {
+                    self.status = "無效的日期".to_string();
+                    return;
+                }
+                if screen.needs_threshold() {
+                    self.open_prompt(PromptKind::BuiltinThreshold(screen, input));
+                } else {
+                    self.start_builtin(screen, input, 0);
+                }
+            }
+            PromptKind::BuiltinThreshold(screen, date) => match input.parse::<u64>() {
+                Ok(threshold) => self.start_builtin(screen, date, threshold),
+                Err(_) => self.status = "無效的閾值".to_string(),
+            },
+        }
+    }
+
+    fn start_builtin(&mut self, screen: BuiltinScreen, date: String, threshold: u64) {
+        self.start_job(&format!("選股 {}", screen.name()), 0);
+        jobs::spawn_builtin(
+            self.company_map.clone(),
+            screen,
+            date,
+            threshold,
+            self.sender.clone(),
+        );
+    }
+
+    fn start_job(&mut self, name: &str, total: usize) {
+        self.job = Some(Job {
+            name: name.to_string(),
+            done: 0,
+            total,
+            current: String::new(),
+        });
+        self.status.clear();
+    }
+
+    fn on_job_event(&mut self, job_event: JobEvent) {
+        match job_event {
+            JobEvent::Progress {
+                done,
+                total,
+                stock_no,
+            } => {
+                if let Some(job) = self.job.as_mut() {
+                    job.done = done;
+                    job.total = total;
+                    job.current = stock_no;
+                }
+            }
+            JobEvent::Fetched {
+                year_month,
+                total,
+                alerts,
+            } => {
+                self.job = None;
+                self.status = format!(
+                    "{year_month} 抓取完成，共 {total} 檔，新警示 {} 筆",
+                    alerts.rows.len()
+                );
+                if !alerts.rows.is_empty() {
+                    self.results = Some(alerts);
+                    self.focus = Focus::Results;
+                }
+                self.load_detail();
+            }
+            JobEvent::Screened(results) => {
+                self.job = None;
+                self.status = results.title.clone();
+                self.results = Some(results);
+                self.focus = Focus::Results;
+            }
+            JobEvent::Failed(message) => {
+                self.job = None;
+                self.status = message;
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::Datelike;
+
+use stock::analysis::indicators::{self, KdValue, MacdValue};
+use stock::common;
+use stock::data::monthly_data::{self, DailyData};
+use stock::twse::company_map::CompanyMap;
+
+/// 明細畫面往前載入的月份數，足夠計算 MA60
+const DETAIL_LOOKBACK_MONTHS: i32 = 6;
+/// 明細畫面列出的交易日數
+pub const DETAIL_DAYS: usize = 20;
+pub const DETAIL_MA_DAYS: [usize; 3] = [5, 20, 60];
+
+/// 單一股票的近期日資料與指標，只讀本地資料
+pub struct Detail {
+    pub stock_no: String,
+    pub name: String,
+    pub industry: String,
+    pub daily_data: Vec<DailyData>,
+    pub ma: Vec<(usize, Option<f64>)>,
+    pub macd: Option<MacdValue>,
+    pub kd: Option<KdValue>,
+}
+
+impl Detail {
+    pub fn load(company_map: &CompanyMap, stock_no: &str) -> Self {
+        let daily_data = monthly_data::latest_stored_daily_data(stock_no)
+            .and_then(|daily| common::roc_date_to_naive(&daily.date))
+            .and_then(|date| {
+                let year_month_to = format!("{:04}{:02}", date.year(), date.month());
+                let year_month_from =
+                    common::shift_year_month(&year_month_to, -DETAIL_LOOKBACK_MONTHS)?;
+                Some(monthly_data::load_daily_data_range(
+                    stock_no,
+                    &year_month_from,
+                    &year_month_to,
+                ))
+            })
+            .unwrap_or_default();
+
+        let closes: Vec<f64> = daily_data.iter().map(|d| d.close).collect();
+        let ma = DETAIL_MA_DAYS
+            .iter()
+            .map(|n| {
+                let value = (closes.len() >= *n)
+                    .then(|| closes[closes.len() - n..].iter().sum::<f64>() / *n as f64);
+                (*n, value)
+            })
+            .collect();
+
+        Detail {
+            stock_no: stock_no.to_string(),
+            name: company_map.get_name(stock_no),
+            industry: company_map.get_industry(stock_no),
+            macd: indicators::macd(&closes).last().copied(),
+            kd: indicators::kd(&daily_data, 9).last().copied(),
+            ma,
+            daily_data,
+        }
+    }
+
+    /// 最近 DETAIL_DAYS 個交易日，新的在前
+    pub fn recent(&self) -> impl Iterator<Item = &DailyData> {
+        self.daily_data.iter().rev().take(DETAIL_DAYS)
+    }
+}
@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use stock::analysis;
+use stock::analysis::macd::MacdCrossType;
+use stock::common;
+use stock::output::record::Record;
+use stock::screener;
+use stock::scripts;
+use stock::twse::company_map::CompanyMap;
+
+use crate::tui::results::ResultTable;
+
+/// 需要月份區間的內建選股 (MACD、波段) 往前讀的月份數
+const RANGE_MONTHS: i32 = 3;
+
+/// 背景工作回報給畫面的事件
+pub enum JobEvent {
+    Progress {
+        done: usize,
+        total: usize,
+        stock_no: String,
+    },
+    Fetched {
+        year_month: String,
+        total: usize,
+        alerts: ResultTable, // 新警示
+    },
+    Screened(ResultTable),
+    Failed(String),
+}
+
+/// 與選單相同的內建選股，以單一日期執行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinScreen {
+    LongRed,
+    MacdGoldenCross,
+    Volume,
+    MacdVolume,
+    Doji,
+    DojiSwing,
+    BullishEngulfing,
+}
+
+impl BuiltinScreen {
+    pub const ALL: [BuiltinScreen; 7] = [
+        BuiltinScreen::LongRed,
+        BuiltinScreen::MacdGoldenCross,
+        BuiltinScreen::Volume,
+        BuiltinScreen::MacdVolume,
+        BuiltinScreen::Doji,
+        BuiltinScreen::DojiSwing,
+        BuiltinScreen::BullishEngulfing,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BuiltinScreen::LongRed => "長紅K",
+            BuiltinScreen::MacdGoldenCross => "MACD黃金交叉",
+            BuiltinScreen::Volume => "大成交量",
+            BuiltinScreen::MacdVolume => "MACD黃金交叉且大量",
+            BuiltinScreen::Doji => "十字線",
+            BuiltinScreen::DojiSwing => "十字線波段驗證",
+            BuiltinScreen::BullishEngulfing => "陽吞噬",
+        }
+    }
+
+    /// 需要輸入成交量閾值 (張)
+    pub fn needs_threshold(self) -> bool {
+        matches!(self, BuiltinScreen::Volume | BuiltinScreen::MacdVolume)
+    }
+}
+
+/// 抓取單月全部股票資料並評估警示，每檔回報一次進度
+pub fn spawn_fetch(
+    company_map: Arc<CompanyMap>,
+    year_month: String,
+    sender: UnboundedSender<JobEvent>,
+) {
+    tokio::spawn(async move {
+        let total = scripts::data::fetch_targets(&company_map).len();
+        let result = scripts::data::fetch_data_monthly_and_alerts(
+            &company_map,
+            &year_month,
+            |i, stock_no| {
+                let _ = sender.send(JobEvent::Progress {
+                    done: i,
+                    total,
+                    stock_no: stock_no.to_string(),
+                });
+            },
+        )
+        .await
+        .map_err(|e| e.to_string());
+        let job_event = match result {
+            Ok(alert_run) => match alert_run.delivery_error() {
+                // Stdout 目標的警示直接顯示在畫面上
+                None => JobEvent::Fetched {
+                    alerts: ResultTable::new(
+                        &format!("{year_month} 新警示 ({} 筆)", alert_run.new_alerts.len()),
+                        &alert_run.new_alerts,
+                        &company_map,
+                    ),
+                    year_month,
+                    total,
+                },
+                Some(e) => JobEvent::Failed(format!("{year_month} 抓取完成，{e}")),
+            },
+            Err(e) => JobEvent::Failed(format!("{year_month} 抓取完成，警示評估失敗: {e}")),
+        };
+        let _ = sender.send(job_event);
+    });
+}
+
+/// 以自訂條件選股，每檔回報一次進度，結果依成交量排序
+pub fn spawn_screen(
+    company_map: Arc<CompanyMap>,
+    expression: String,
+    date: String,
+    sender: UnboundedSender<JobEvent>,
+) {
+    tokio::spawn(async move {
+        let expr = match screener::parser::parse(&expression) {
+            Ok(expr) => expr,
+            Err(e) => {
+                let _ = sender.send(JobEvent::Failed(format!("條件錯誤: {e}")));
+                return;
+            }
+        };
+
+        let breadth = scripts::screener::breadth_for(&company_map, &expr, &date);
+        let total = company_map.stock_map.len();
+        let mut results = Vec::new();
+        for (i, company) in company_map.stock_map.iter().enumerate() {
+            let _ = sender.send(JobEvent::Progress {
+                done: i,
+                total,
+                stock_no: company.stock_no.clone(),
+            });
+            if let Some(result) = scripts::screener::screen_date_company(
+                &company_map,
+                &expr,
+                &breadth,
+                &company.stock_no,
+                &date,
+            )
+            .await
+            {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|r| std::cmp::Reverse(r.daily_data.volume));
+
+        let title = format!("{date} {expression} ({} 檔)", results.len());
+        let _ = sender.send(JobEvent::Screened(ResultTable::new(
+            &title,
+            &results,
+            &company_map,
+        )));
+    });
+}
+
+/// 執行內建選股 (與選單/命令列相同的分析)，需要區間的往前讀 RANGE_MONTHS 個月
+pub fn spawn_builtin(
+    company_map: Arc<CompanyMap>,
+    screen: BuiltinScreen,
+    date: String,
+    threshold: u64,
+    sender: UnboundedSender<JobEvent>,
+) {
+    tokio::spawn(async move {
+        let Some(year_month_from) = common::year_month_of(&date)
+            .and_then(|year_month| common::shift_year_month(year_month, -RANGE_MONTHS))
+        else {
+            let _ = sender.send(JobEvent::Failed(format!("無效的日期: {date}")));
+            return;
+        };
+        let year_month_to = date[0..6].to_string();
+        let company_map = company_map.as_ref();
+
+        let results = match screen {
+            BuiltinScreen::LongRed => table_of(
+                screen,
+                &date,
+                &analysis::long_red_candle::anal_date_all_companies(company_map, &date).await,
+                company_map,
+            ),
+            BuiltinScreen::MacdGoldenCross => {
+                let crosses: Vec<_> = scripts::macd::anal_date_all_companies(
+                    company_map,
+                    &year_month_from,
+                    &year_month_to,
+                    &date,
+                )
+                .await
+                .into_iter()
+                .filter(|cross| cross.cross_type == MacdCrossType::GoldenCross)
+                .collect();
+                table_of(screen, &date, &crosses, company_map)
+            }
+            BuiltinScreen::Volume => table_of(
+                screen,
+                &date,
+                &scripts::volume::volume_larger_than_threshold(company_map, threshold, &date).await,
+                company_map,
+            ),
+            BuiltinScreen::MacdVolume => table_of(
+                screen,
+                &date,
+                &scripts::complex::anal_macd_golden_volume_larger_date(
+                    company_map,
+                    &year_month_from,
+                    &year_month_to,
+                    &date,
+                    threshold,
+                )
+                .await,
+                company_map,
+            ),
+            BuiltinScreen::Doji => table_of(
+                screen,
+                &date,
+                &scripts::doji::anal_date_all_companies(company_map, &date).await,
+                company_map,
+            ),
+            BuiltinScreen::DojiSwing => table_of(
+                screen,
+                &date,
+                &scripts::complex::anal_doji_in_swing_all_companies(
+                    company_map,
+                    &year_month_from,
+                    &year_month_to,
+                    &date,
+                )
+                .await,
+                company_map,
+            ),
+            BuiltinScreen::BullishEngulfing => table_of(
+                screen,
+                &date,
+                &scripts::bullish_engulfing_pattern::anal_date_all_companies(company_map, &date)
+                    .await,
+                company_map,
+            ),
+        };
+        let _ = sender.send(JobEvent::Screened(results));
+    });
+}
+
+fn table_of<R: Record>(
+    screen: BuiltinScreen,
+    date: &str,
+    records: &[R],
+    company_map: &CompanyMap,
+) -> ResultTable {
+    let title = format!("{date} {} ({} 檔)", screen.name(), records.len());
+    ResultTable::new(&title, records, company_map)
+}
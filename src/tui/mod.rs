@@ -0,0 +1,5 @@
+pub mod app;
+pub mod detail;
+pub mod jobs;
+pub mod results;
+pub mod ui;
@@ -0,0 +1,86 @@
+use std::cmp::Ordering;
+
+use ratatui::widgets::TableState;
+use serde_json::Value;
+
+use stock::output::record::{Column, Record};
+use stock::output::render;
+use stock::twse::company_map::CompanyMap;
+
+/// 可排序的結果表格，欄位沿用 Record 的定義
+pub struct ResultTable {
+    pub title: String,
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+    pub state: TableState,
+    pub sort_column: Option<usize>,
+    pub descending: bool,
+}
+
+impl ResultTable {
+    pub fn new<R: Record>(title: &str, records: &[R], company_map: &CompanyMap) -> Self {
+        let rows: Vec<Vec<Value>> = records.iter().map(|r| r.values(company_map)).collect();
+        let mut state = TableState::default();
+        if !rows.is_empty() {
+            state.select(Some(0));
+        }
+        ResultTable {
+            title: title.to_string(),
+            columns: R::columns(),
+            rows,
+            state,
+            sort_column: None,
+            descending: false,
+        }
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> String {
+        render::format_cell(self.columns[column].kind, &self.rows[row][column])
+    }
+
+    /// 依欄位排序，對同一欄再按一次則反向
+    pub fn sort_by(&mut self, column: usize) {
+        if column >= self.columns.len() {
+            return;
+        }
+        self.descending = self.sort_column == Some(column) && !self.descending;
+        self.sort_column = Some(column);
+
+        self.rows.sort_by(|a, b| compare(&a[column], &b[column]));
+        if self.descending {
+            self.rows.reverse();
+        }
+        self.state.select((!self.rows.is_empty()).then_some(0));
+    }
+
+    pub fn select_offset(&mut self, offset: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + offset).clamp(0, self.rows.len() as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    /// 選取列的股號 (需有 stock_no 欄位)
+    pub fn selected_stock_no(&self) -> Option<&str> {
+        let column = self.columns.iter().position(|c| c.key == "stock_no")?;
+        self.rows.get(self.state.selected()?)?[column].as_str()
+    }
+}
+
+/// 數字依大小、文字依字典序，空值排最後
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .unwrap_or(0.0)
+            .total_cmp(&b.as_f64().unwrap_or(0.0)),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
@@ -0,0 +1,268 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Gauge, List, ListItem, Paragraph, Row, Table};
+
+use stock::common;
+use stock::output::table::{self, Align};
+
+use crate::tui::app::{App, Focus};
+use crate::tui::detail::Detail;
+use crate::tui::results::ResultTable;
+
+const LIST_WIDTH: u16 = 28;
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let job_height = if app.job.is_some() { 3 } else { 0 };
+    let [main, job_area, status_area] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(job_height),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [list_area, right] =
+        Layout::horizontal([Constraint::Length(LIST_WIDTH), Constraint::Min(0)]).areas(main);
+
+    draw_list(frame, app, list_area);
+
+    if app.results.is_some() {
+        let [detail_area, results_area] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+        draw_detail(frame, app.detail.as_ref(), detail_area);
+        let focused = app.focus == Focus::Results;
+        if let Some(results) = app.results.as_mut() {
+            draw_results(frame, results, focused, results_area);
+        }
+    } else {
+        draw_detail(frame, app.detail.as_ref(), right);
+    }
+
+    if let Some(job) = &app.job {
+        let ratio = if job.total == 0 {
+            0.0
+        } else {
+            job.done as f64 / job.total as f64
+        };
+        let label = if job.total == 0 {
+            "執行中".to_string()
+        } else {
+            format!("{}/{} {}", job.done, job.total, job.current)
+        };
+        let gauge = Gauge::default()
+            .block(Block::bordered().title(job.name.as_str()))
+            .gauge_style(Style::new().fg(Color::Cyan))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(label);
+        frame.render_widget(gauge, job_area);
+    }
+
+    draw_status(frame, app, status_area);
+}
+
+fn border_style(focused: bool) -> Style {
+    if focused {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    }
+}
+
+/// 台股慣例：紅漲綠跌
+fn change_style(value: f64) -> Style {
+    match table::Color::from_sign(value) {
+        Some(table::Color::Up) => Style::new().fg(Color::Red),
+        Some(table::Color::Down) => Style::new().fg(Color::Green),
+        None => Style::new(),
+    }
+}
+
+fn draw_list(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .filtered
+        .iter()
+        .map(|i| {
+            let company = &app.company_map.stock_map[*i];
+            ListItem::new(format!("{:<6} {}", company.stock_no, company.name))
+        })
+        .collect();
+
+    let title = if app.search.is_empty() {
+        format!("股票 ({})", app.filtered.len())
+    } else {
+        format!("搜尋: {} ({})", app.search, app.filtered.len())
+    };
+    let list = List::new(items)
+        .block(
+            Block::bordered()
+                .title(title)
+                .border_style(border_style(matches!(
+                    app.focus,
+                    Focus::List | Focus::Search
+                ))),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.list_state);
+}
+
+fn draw_detail(frame: &mut Frame, detail: Option<&Detail>, area: Rect) {
+    let Some(detail) = detail else {
+        frame.render_widget(Block::bordered().title("明細"), area);
+        return;
+    };
+
+    let block = Block::bordered().title(format!(
+        "{} {} / {}",
+        detail.stock_no, detail.name, detail.industry
+    ));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [summary_area, table_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(inner);
+
+    let format_value =
+        |value: Option<f64>| value.map(|v| format!("{v:.2}")).unwrap_or("-".to_string());
+    let ma_line: Vec<Span> = detail
+        .ma
+        .iter()
+        .map(|(n, value)| Span::raw(format!("MA{n} {}   ", format_value(*value))))
+        .collect();
+    let summary = vec![
+        Line::from(ma_line),
+        Line::from(format!(
+            "MACD DIF {}  訊號 {}",
+            format_value(detail.macd.map(|m| m.dif)),
+            format_value(detail.macd.map(|m| m.signal)),
+        )),
+        Line::from(format!(
+            "KD K {}  D {}",
+            format_value(detail.kd.map(|kd| kd.k)),
+            format_value(detail.kd.map(|kd| kd.d)),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(summary), summary_area);
+
+    if detail.daily_data.is_empty() {
+        frame.render_widget(Paragraph::new("本地沒有資料"), table_area);
+        return;
+    }
+
+    let header = Row::new(["日期", "成交張數", "開盤", "收盤", "最高", "最低", "漲跌"])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = detail
+        .recent()
+        .map(|daily| {
+            Row::new(vec![
+                Cell::from(daily.date.clone()),
+                Cell::from(common::format_commas(common::divide_by_1000(daily.volume))),
+                Cell::from(format!("{:.2}", daily.open)),
+                Cell::from(format!("{:.2}", daily.close)).style(change_style(daily.change)),
+                Cell::from(format!("{:.2}", daily.high)),
+                Cell::from(format!("{:.2}", daily.low)),
+                Cell::from(format!("{:.2}", daily.change)).style(change_style(daily.change)),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(8),
+    ];
+    frame.render_widget(Table::new(rows, widths).header(header), table_area);
+}
+
+fn draw_results(frame: &mut Frame, results: &mut ResultTable, focused: bool, area: Rect) {
+    let header = Row::new(results.columns.iter().enumerate().map(|(i, column)| {
+        let marker = match results.sort_column {
+            Some(sorted) if sorted == i && results.descending => "▼",
+            Some(sorted) if sorted == i => "▲",
+            _ => "",
+        };
+        format!("{}{}{marker}", i + 1, column.title)
+    }))
+    .style(Style::new().add_modifier(Modifier::BOLD));
+
+    let texts: Vec<Vec<String>> = (0..results.rows.len())
+        .map(|row| {
+            (0..results.columns.len())
+                .map(|column| results.cell(row, column))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<Constraint> = results
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let width = texts
+                .iter()
+                .map(|row| table::display_width(&row[i]))
+                .chain([table::display_width(column.title) + 2])
+                .max()
+                .unwrap_or(0);
+            Constraint::Length(width as u16)
+        })
+        .collect();
+
+    let rows: Vec<Row> = texts
+        .into_iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            Row::new(cells.into_iter().enumerate().map(|(i, text)| {
+                let column = &results.columns[i];
+                let style = match results.rows[row][i].as_f64() {
+                    Some(value) if column.colored => change_style(value),
+                    _ => Style::new(),
+                };
+                match column.align {
+                    Align::Left => Cell::from(text).style(style),
+                    Align::Right => Cell::from(Line::from(text).right_aligned()).style(style),
+                }
+            }))
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::bordered()
+                .title(results.title.as_str())
+                .border_style(border_style(focused)),
+        )
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut results.state);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(prompt) = &app.prompt {
+        let label = prompt.kind.label();
+        frame.render_widget(Paragraph::new(format!("{label}{}", prompt.input)), area);
+        let x =
+            area.x + (table::display_width(&label) + table::display_width(&prompt.input)) as u16;
+        frame.set_cursor_position((x.min(area.right().saturating_sub(1)), area.y));
+        return;
+    }
+
+    let hint = match app.focus {
+        Focus::List => "↑↓ 移動  / 搜尋  s 選股  b 內建選股  f 抓取資料  Tab 結果  q 離開",
+        Focus::Search => "輸入股號或名稱  Enter 完成  Esc 清除",
+        Focus::Results => "↑↓ 移動  1-9 依欄位排序  Enter 查看明細  Tab 返回清單",
+        Focus::Prompt => "",
+    };
+    let line = if app.status.is_empty() {
+        Line::from(hint)
+    } else {
+        Line::from(vec![
+            Span::styled(app.status.as_str(), Style::new().fg(Color::Yellow)),
+            Span::raw("  "),
+            Span::raw(hint),
+        ])
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
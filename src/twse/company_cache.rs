@@ -0,0 +1,247 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::twse::company_map::{CompanyInfo, Market};
+use crate::twse::industry::{self, IndustryChange};
+
+/// 上次抓到的公司清單 (未套用排除條件)，網路不通或離線模式時使用
+pub const COMPANY_CACHE_FILE: &str = "companies.json";
+
+/// 上次 refresh-companies 回報時的公司清單，refresh 與此比對，
+/// 一般執行更新 companies.json 時不會動到，異動不會因此漏報
+pub const REPORTED_COMPANIES_FILE: &str = "companies_reported.json";
+
+/// 任一市場的公司或 ETF 數比快取少超過此比例時，視為抓取不完整
+const MAX_DROP_RATIO: f64 = 0.2;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanyCache {
+    pub fetched_at: String,
+    pub companies: Vec<CompanyInfo>,
+}
+
+impl CompanyCache {
+    pub fn load() -> Option<Self> {
+        Self::load_from(&config::path(COMPANY_CACHE_FILE))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        self.save_to(&config::path(COMPANY_CACHE_FILE))
+    }
+
+    /// 取代快取，與舊快取相比的產業別異動寫入 industry_history.json，
+    /// 不論是一般執行或 refresh-companies 更新都不會漏記
+    pub fn replace(&self) -> Result<(), Box<dyn Error>> {
+        let changes = diff(Self::load().as_ref(), self);
+        self.save()?;
+        industry::append_history(&changes.reassigned)
+    }
+
+    /// 上次回報的公司清單；還沒有時 (例如舊版升級) 為 None
+    pub fn load_reported() -> Option<Self> {
+        Self::load_from(&config::path(REPORTED_COMPANIES_FILE))
+    }
+
+    pub fn save_reported(&self) -> Result<(), Box<dyn Error>> {
+        self.save_to(&config::path(REPORTED_COMPANIES_FILE))
+    }
+
+    /// 檢查抓到的清單是否完整：markets 中每個市場的公司與 ETF 都要有資料，
+    /// 且數量不能比 previous 少超過 MAX_DROP_RATIO；不完整時不應取代快取
+    pub fn validate(
+        &self,
+        previous: Option<&CompanyCache>,
+        markets: &[Market],
+    ) -> Result<(), String> {
+        for market in markets {
+            for is_etf in [false, true] {
+                let kind = if is_etf { "ETF" } else { "公司" };
+                let count = self.count(*market, is_etf);
+                if count == 0 {
+                    return Err(format!("{market}{kind}清單為空"));
+                }
+                let Some(previous) = previous else {
+                    continue;
+                };
+                let previous_count = previous.count(*market, is_etf);
+                if (count as f64) < previous_count as f64 * (1.0 - MAX_DROP_RATIO) {
+                    return Err(format!(
+                        "{market}{kind}數從 {previous_count} 降到 {count}，可能抓取不完整"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn count(&self, market: Market, is_etf: bool) -> usize {
+        self.companies
+            .iter()
+            .filter(|company| company.market == market && company.is_etf() == is_etf)
+            .count()
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        serde_json::from_reader(std::io::BufReader::new(file)).ok()
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+/// 與上次回報相比新上市 (櫃)、下市 (櫃) 與產業別異動的公司
+#[derive(Debug)]
+pub struct CompanyChanges {
+    pub previous_fetched_at: Option<String>, // 之前沒有公司清單時為 None
+    pub listed: Vec<CompanyInfo>,
+    pub delisted: Vec<CompanyInfo>,
+    pub reassigned: Vec<IndustryChange>,
+}
+
+pub fn diff(previous: Option<&CompanyCache>, current: &CompanyCache) -> CompanyChanges {
+    let Some(previous) = previous else {
+        return CompanyChanges {
+            previous_fetched_at: None,
+            listed: Vec::new(),
+            delisted: Vec::new(),
+            reassigned: Vec::new(),
+        };
+    };
+
+    let stock_nos = |cache: &CompanyCache| -> HashSet<String> {
+        cache
+            .companies
+            .iter()
+            .map(|company| company.stock_no.clone())
+            .collect()
+    };
+    let previous_stock_nos = stock_nos(previous);
+    let current_stock_nos = stock_nos(current);
+
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let reassigned = current
+        .companies
+        .iter()
+        .filter_map(|company| {
+            let before = previous
+                .companies
+                .iter()
+                .find(|p| p.stock_no == company.stock_no)?;
+            (before.industry != company.industry).then(|| IndustryChange {
+                stock_no: company.stock_no.clone(),
+                date: today.clone(),
+                from: before.industry.clone(),
+                to: company.industry.clone(),
+            })
+        })
+        .collect();
+
+    CompanyChanges {
+        previous_fetched_at: Some(previous.fetched_at.clone()),
+        listed: current
+            .companies
+            .iter()
+            .filter(|company| !previous_stock_nos.contains(&company.stock_no))
+            .cloned()
+            .collect(),
+        delisted: previous
+            .companies
+            .iter()
+            .filter(|company| !current_stock_nos.contains(&company.stock_no))
+            .cloned()
+            .collect(),
+        reassigned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twse::company_map::{CompanyProfile, SecurityType};
+
+    /// market 市場 companies 檔公司與 etfs 檔 ETF
+    fn cache(market: Market, companies: usize, etfs: usize) -> CompanyCache {
+        let mut list: Vec<CompanyInfo> = (0..companies)
+            .map(|i| CompanyInfo {
+                stock_no: format!("{}", 1000 + i),
+                name: format!("公司{i}"),
+                industry: "24".to_string(),
+                market,
+                security_type: SecurityType::Stock,
+                profile: CompanyProfile::default(),
+            })
+            .collect();
+        list.extend((0..etfs).map(|i| CompanyInfo::etf(&format!("00{}", 50 + i), "ETF", market)));
+        CompanyCache {
+            fetched_at: String::new(),
+            companies: list,
+        }
+    }
+
+    fn merged(caches: Vec<CompanyCache>) -> CompanyCache {
+        CompanyCache {
+            fetched_at: String::new(),
+            companies: caches.into_iter().flat_map(|c| c.companies).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_complete_list() {
+        let previous = cache(Market::Twse, 100, 10);
+        assert!(
+            cache(Market::Twse, 95, 10)
+                .validate(Some(&previous), &[Market::Twse])
+                .is_ok()
+        );
+        assert!(
+            cache(Market::Twse, 1, 1)
+                .validate(None, &[Market::Twse])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_empty_market() {
+        let current = cache(Market::Twse, 100, 10);
+        assert!(
+            current
+                .validate(None, &[Market::Twse, Market::Tpex])
+                .is_err()
+        );
+        assert!(
+            cache(Market::Twse, 100, 0)
+                .validate(None, &[Market::Twse])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_sharp_drop() {
+        let previous = merged(vec![
+            cache(Market::Twse, 100, 10),
+            cache(Market::Tpex, 100, 10),
+        ]);
+        let current = merged(vec![
+            cache(Market::Twse, 100, 10),
+            cache(Market::Tpex, 50, 10),
+        ]);
+        assert!(
+            current
+                .validate(Some(&previous), &[Market::Twse, Market::Tpex])
+                .is_err()
+        );
+        // 不在設定中的市場不檢查
+        assert!(current.validate(Some(&previous), &[Market::Twse]).is_ok());
+    }
+}
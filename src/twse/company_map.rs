@@ -1,154 +1,756 @@
 use std::collections::HashMap;
 
 use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-
-use serde::Deserialize;
-
-fn read_lines_to_vec<P>(filename: P) -> io::Result<Vec<String>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    let reader = io::BufReader::new(file);
-
-    let mut result = Vec::new();
-
-    // for line in reader.lines() {
-    //     if let Ok(l) = line {
-    //         // 如果有註解，用 "//" 切開，只取前半段
-    //         let clean = l.split("//").next().unwrap().trim();
-    //         if !clean.is_empty() {
-    //             result.push(clean.to_string());
-    //         }
-    //     }
-    // }
-    for l in reader.lines().map_while(Result::ok) {
-        // 如果有註解，用 "//" 切開，只取前半段
-        let clean = l.split("//").next().unwrap().trim();
-        if !clean.is_empty() {
-            result.push(clean.to_string());
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common;
+use crate::config;
+use crate::data::monthly_data::DailyData;
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::tpex;
+use crate::twse::company_cache::{self, COMPANY_CACHE_FILE, CompanyCache, CompanyChanges};
+use crate::twse::etf_list;
+use crate::twse::industry::{self, IndustryChange, IndustrySummary, IndustryTaxonomy};
+use crate::universe::filter::{ExcludedCompany, UniverseFilter};
+
+/// 上市 (TWSE) 或上櫃 (TPEx)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Market {
+    /// 上市
+    #[default]
+    Twse,
+    /// 上櫃
+    Tpex,
+}
+
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Market::Twse => write!(f, "上市"),
+            Market::Tpex => write!(f, "上櫃"),
+        }
+    }
+}
+
+/// 證券類型；ETF 依名稱判斷是否為槓桿或反向型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum SecurityType {
+    /// 普通股
+    #[default]
+    Stock,
+    /// ETF
+    Etf,
+    /// 槓桿型 ETF (正2)
+    LeveragedEtf,
+    /// 反向型 ETF (反1)
+    InverseEtf,
+}
+
+impl SecurityType {
+    /// 依 ETF 名稱判斷類型，例如 "元大台灣50正2"、"國泰臺灣加權反1"
+    pub fn from_etf_name(name: &str) -> Self {
+        let name = name.to_uppercase();
+        if ["反1", "反一", "反向", "-1X"]
+            .iter()
+            .any(|k| name.contains(k))
+        {
+            SecurityType::InverseEtf
+        } else if ["正2", "正二", "槓桿", "2X"]
+            .iter()
+            .any(|k| name.contains(k))
+        {
+            SecurityType::LeveragedEtf
+        } else {
+            SecurityType::Etf
         }
     }
-    Ok(result)
 }
 
+impl fmt::Display for SecurityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityType::Stock => write!(f, "普通股"),
+            SecurityType::Etf => write!(f, "ETF"),
+            SecurityType::LeveragedEtf => write!(f, "槓桿型ETF"),
+            SecurityType::InverseEtf => write!(f, "反向型ETF"),
+        }
+    }
+}
+
+/// ETF 沒有產業別，統一歸在這個代號
+pub const ETF_INDUSTRY: &str = "00";
+
+/// 掃描範圍的公司清單 (依股號排序) 與股號索引；
+/// 名稱、產業、市場等查詢涵蓋完整公司清單，包含被排除的公司
 pub struct CompanyMap {
     pub stock_map: Vec<CompanyInfo>,
-    industry_map: HashMap<String, String>,
+    index: HashMap<String, usize>, // 股號 → stock_map 位置
+    taxonomy: IndustryTaxonomy,
+    history: Vec<IndustryChange>,            // 產業別異動紀錄
+    excluded: Vec<ExcludedCompany>,          // 依 [universe] 設定與忽略清單排除的公司
+    companies: HashMap<String, CompanyInfo>, // 完整公司清單 (含被排除的)，子集合也保留
+}
+
+fn by_stock_no(companies: &[CompanyInfo]) -> HashMap<String, CompanyInfo> {
+    companies
+        .iter()
+        .map(|company| (company.stock_no.clone(), company.clone()))
+        .collect()
 }
 
 impl CompanyMap {
-    pub async fn new() -> Self {
-        let industry_map = build_industry_map();
+    /// 抓取設定中各市場的公司清單並更新快取；抓取失敗時改用快取，
+    /// 抓到的清單不完整 (某市場為空或數量驟減) 時保留快取不取代
+    pub async fn new() -> Result<Self, Box<dyn Error>> {
+        let cache = match fetch_company_list().await {
+            Ok(cache) => {
+                let previous = CompanyCache::load();
+                match cache.validate(previous.as_ref(), &config::get().universe.markets) {
+                    Ok(()) => {
+                        if let Err(e) = cache.replace() {
+                            common::log(&format!("無法寫入 {COMPANY_CACHE_FILE}: {e}"));
+                        }
+                        cache
+                    }
+                    Err(e) => match previous {
+                        Some(previous) => {
+                            common::log(&format!(
+                                "公司清單不完整 ({e})，不更新 {COMPANY_CACHE_FILE}，改用 {} 的快取",
+                                previous.fetched_at
+                            ));
+                            previous
+                        }
+                        None => {
+                            common::log(&format!(
+                                "公司清單不完整 ({e})，沒有快取可用，暫時使用抓到的清單"
+                            ));
+                            cache
+                        }
+                    },
+                }
+            }
+            Err(e) => {
+                let cache = CompanyCache::load().ok_or(format!(
+                    "無法取得公司清單 ({e})，也沒有 {COMPANY_CACHE_FILE}"
+                ))?;
+                common::log(&format!(
+                    "無法取得公司清單 ({e})，改用 {} 的快取",
+                    cache.fetched_at
+                ));
+                cache
+            }
+        };
+        Ok(Self::from_companies(cache.companies))
+    }
 
-        let ignore_stocks = read_lines_to_vec("ignore_stocks.txt").unwrap();
-        let mut stock_map = fetch_twse_company_map(&ignore_stocks).await.unwrap();
+    /// 離線模式：只使用快取的公司清單
+    pub fn from_cache() -> Result<Self, Box<dyn Error>> {
+        let cache =
+            CompanyCache::load().ok_or(format!("沒有 {COMPANY_CACHE_FILE}，請先連線執行一次"))?;
+        Ok(Self::from_companies(cache.companies))
+    }
 
-        stock_map.sort_by(|a, b| a.stock_no.cmp(&b.stock_no));
+    /// 重新抓取公司清單並更新快取，回傳與上次回報相比的異動；
+    /// 還沒有回報紀錄時與舊快取比對
+    pub async fn refresh() -> Result<CompanyChanges, Box<dyn Error>> {
+        let cache = fetch_company_list().await?;
+        cache
+            .validate(
+                CompanyCache::load().as_ref(),
+                &config::get().universe.markets,
+            )
+            .map_err(|e| format!("公司清單不完整，不更新 {COMPANY_CACHE_FILE}: {e}"))?;
+        let previous = CompanyCache::load_reported().or_else(CompanyCache::load);
+        let changes = company_cache::diff(previous.as_ref(), &cache);
+        cache.replace()?;
+        cache.save_reported()?;
+        Ok(changes)
+    }
+
+    /// 以指定的公司清單建立，不套用 [universe] 條件與忽略清單
+    pub fn with_companies(companies: Vec<CompanyInfo>) -> Self {
+        let all = by_stock_no(&companies);
+        Self::build(
+            companies,
+            all,
+            IndustryTaxonomy::load(),
+            industry::load_history(),
+        )
+    }
 
+    /// 套用 [universe] 設定中不需要本地資料的條件與忽略清單，被排除的公司記錄原因；
+    /// 排除只影響掃描範圍，被排除的公司仍可查詢名稱與產業
+    fn from_companies(companies: Vec<CompanyInfo>) -> Self {
+        let filter = UniverseFilter::from_config();
+        let all = by_stock_no(&companies);
+        let mut stock_map = Vec::new();
+        let mut excluded = Vec::new();
+        for company in companies {
+            match filter.static_exclusion(&company) {
+                Some(reason) => excluded.push(ExcludedCompany { company, reason }),
+                None => stock_map.push(company),
+            }
+        }
+        excluded.sort_by(|a, b| a.company.stock_no.cmp(&b.company.stock_no));
+
+        let mut company_map = Self::build(
+            stock_map,
+            all,
+            IndustryTaxonomy::load(),
+            industry::load_history(),
+        );
+        company_map.excluded = excluded;
+        company_map
+    }
+
+    /// stock_map 排序後建立股號索引
+    fn build(
+        mut stock_map: Vec<CompanyInfo>,
+        companies: HashMap<String, CompanyInfo>,
+        taxonomy: IndustryTaxonomy,
+        history: Vec<IndustryChange>,
+    ) -> Self {
+        stock_map.sort_by(|a, b| a.stock_no.cmp(&b.stock_no));
+        let index = stock_map
+            .iter()
+            .enumerate()
+            .map(|(i, company)| (company.stock_no.clone(), i))
+            .collect();
         CompanyMap {
             stock_map,
-            industry_map,
+            index,
+            taxonomy,
+            history,
+            excluded: Vec::new(),
+            companies,
         }
     }
 
-    pub fn get(&self, stock_no: &str) -> String {
+    /// 建立時被排除的公司與原因 (filter 產生的子集合為空)
+    pub fn excluded(&self) -> &[ExcludedCompany] {
+        &self.excluded
+    }
+
+    /// 股號是否為 ETF，範圍外或被排除的也能判斷；不在公司清單 (例如已下市) 時為 false
+    pub fn is_etf(&self, stock_no: &str) -> bool {
+        self.get(stock_no).is_some_and(CompanyInfo::is_etf)
+    }
+
+    /// 股號的證券類型，範圍外或被排除的也能判斷；不在公司清單時視為普通股
+    pub fn security_type(&self, stock_no: &str) -> SecurityType {
+        self.get(stock_no)
+            .map(|company| company.security_type)
+            .unwrap_or_default()
+    }
+
+    /// 依條件篩選出子集合，供自選股/產業範圍掃描使用
+    pub fn filter<F>(&self, predicate: F) -> CompanyMap
+    where
+        F: Fn(&CompanyInfo) -> bool,
+    {
+        Self::build(
+            self.stock_map
+                .iter()
+                .filter(|company| predicate(company))
+                .cloned()
+                .collect(),
+            self.companies.clone(),
+            self.taxonomy.clone(),
+            self.history.clone(),
+        )
+    }
+
+    /// 公司清單中的公司，掃描範圍外或被排除的也找得到
+    pub fn get(&self, stock_no: &str) -> Option<&CompanyInfo> {
+        self.companies.get(stock_no)
+    }
+
+    /// 在 stock_map (掃描範圍) 中的位置
+    pub fn position(&self, stock_no: &str) -> Option<usize> {
+        self.index.get(stock_no).copied()
+    }
+
+    /// 公司簡稱完全相同
+    pub fn find_by_name(&self, name: &str) -> Option<&CompanyInfo> {
+        self.stock_map.iter().find(|company| company.name == name)
+    }
+
+    /// 股號或名稱的部分比對 (不分大小寫)，依 股號/名稱完全相同 → 開頭相同 → 包含 → 字元依序出現 排序
+    pub fn search(&self, keyword: &str) -> Vec<&CompanyInfo> {
+        let keyword = keyword.trim().to_lowercase();
+        if keyword.is_empty() {
+            return self.stock_map.iter().collect();
+        }
+
+        let mut matches: Vec<(usize, &CompanyInfo)> = self
+            .stock_map
+            .iter()
+            .filter_map(|company| {
+                let stock_no = company.stock_no.to_lowercase();
+                let name = company.name.to_lowercase();
+                let rank = if stock_no == keyword || name == keyword {
+                    0
+                } else if stock_no.starts_with(&keyword) || name.starts_with(&keyword) {
+                    1
+                } else if stock_no.contains(&keyword) || name.contains(&keyword) {
+                    2
+                } else if is_subsequence(&keyword, &name) {
+                    3
+                } else {
+                    return None;
+                };
+                Some((rank, company))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, _)| *rank); // 同一級保持股號順序
+        matches.into_iter().map(|(_, company)| company).collect()
+    }
+
+    /// 產業代號或產業名稱轉成產業代號
+    pub fn industry_code(&self, industry: &str) -> Option<String> {
+        self.taxonomy.code_of(industry).map(str::to_string)
+    }
+
+    /// 所有產業 (代號, 名稱)，依代號排序
+    pub fn industries(&self) -> Vec<(&str, &str)> {
+        self.taxonomy
+            .industries
+            .iter()
+            .map(|industry| (industry.code.as_str(), industry.name.as_str()))
+            .collect()
+    }
+
+    pub fn taxonomy(&self) -> &IndustryTaxonomy {
+        &self.taxonomy
+    }
+
+    /// 產業表中每個產業的公司數
+    pub fn industry_summary(&self) -> Vec<IndustrySummary> {
+        self.taxonomy
+            .industries
+            .iter()
+            .map(|industry| IndustrySummary {
+                industry: industry.clone(),
+                companies: self
+                    .stock_map
+                    .iter()
+                    .filter(|company| company.industry == industry.code)
+                    .count(),
+            })
+            .collect()
+    }
+
+    /// 公司清單中有、產業表沒有的產業代號與公司數，需要更新 industries.toml
+    pub fn unknown_industries(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
         for company in &self.stock_map {
-            if company.stock_no == stock_no {
-                return format!(
-                    "{}/{}",
-                    company.name,
-                    self.industry_map
-                        .get(&company.industry)
-                        .unwrap_or(&"未知產業".to_string())
-                );
+            if self.taxonomy.get(&company.industry).is_none() {
+                *counts.entry(company.industry.as_str()).or_default() += 1;
             }
         }
+        let mut unknown: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(code, count)| (code.to_string(), count))
+            .collect();
+        unknown.sort();
+        unknown
+    }
 
-        panic!("Cannot find company name for stock no: {stock_no}");
+    /// 所有類股
+    pub fn sectors(&self) -> Vec<&str> {
+        self.taxonomy.sectors()
+    }
+
+    /// 類股內的公司，找不到類股時為 None
+    pub fn by_sector(&self, sector: &str) -> Option<Vec<&CompanyInfo>> {
+        let codes = self.taxonomy.codes_in_sector(sector);
+        if codes.is_empty() {
+            return None;
+        }
+        Some(
+            self.stock_map
+                .iter()
+                .filter(|company| codes.contains(&company.industry.as_str()))
+                .collect(),
+        )
+    }
+
+    /// 依類股分組股號，類股依產業表順序，不在產業表的歸在 "未分類"
+    pub fn group_by_sector<'a>(&self, stock_nos: &[&'a str]) -> Vec<(String, Vec<&'a str>)> {
+        let mut groups: Vec<(String, Vec<&'a str>)> = Vec::new();
+        for stock_no in stock_nos {
+            let sector = self.sector(stock_no).unwrap_or("未分類").to_string();
+            match groups.iter_mut().find(|(s, _)| *s == sector) {
+                Some((_, members)) => members.push(stock_no),
+                None => groups.push((sector, vec![stock_no])),
+            }
+        }
+        let sectors = self.sectors();
+        groups.sort_by_key(|(sector, _)| {
+            sectors
+                .iter()
+                .position(|s| s == sector)
+                .unwrap_or(usize::MAX)
+        });
+        groups
+    }
+
+    /// 產業別異動紀錄，依日期排序
+    pub fn industry_history(&self, stock_no: &str) -> Vec<&IndustryChange> {
+        self.history
+            .iter()
+            .filter(|change| change.stock_no == stock_no)
+            .collect()
+    }
+
+    /// 某一天的產業代號：該日之後第一次異動前的產業，沒有異動時為目前產業
+    pub fn industry_at(&self, stock_no: &str, date: NaiveDate) -> Option<&str> {
+        let date = date.format("%Y-%m-%d").to_string();
+        self.industry_history(stock_no)
+            .into_iter()
+            .find(|change| change.date > date)
+            .map(|change| change.from.as_str())
+            .or_else(|| self.get(stock_no).map(|company| company.industry.as_str()))
+    }
+
+    /// 產業 (代號或名稱) 內的公司，找不到產業時為 None
+    pub fn by_industry(&self, industry: &str) -> Option<Vec<&CompanyInfo>> {
+        let code = self.industry_code(industry)?;
+        Some(
+            self.stock_map
+                .iter()
+                .filter(|company| company.industry == code)
+                .collect(),
+        )
+    }
+
+    pub fn name(&self, stock_no: &str) -> Option<&str> {
+        self.get(stock_no).map(|company| company.name.as_str())
+    }
+
+    pub fn industry_name(&self, stock_no: &str) -> Option<&str> {
+        self.get(stock_no)
+            .and_then(|company| self.taxonomy.get(&company.industry))
+            .map(|industry| industry.name.as_str())
+    }
+
+    pub fn sector(&self, stock_no: &str) -> Option<&str> {
+        self.get(stock_no)
+            .and_then(|company| self.taxonomy.get(&company.industry))
+            .map(|industry| industry.sector.as_str())
+    }
+
+    /// 顯示用：取得公司簡稱，找不到時回傳 "未知公司" (例如已下市但本地仍有資料)
+    pub fn get_name(&self, stock_no: &str) -> String {
+        self.name(stock_no).unwrap_or("未知公司").to_string()
+    }
+
+    /// 上市或上櫃，找不到時視為上市
+    pub fn market(&self, stock_no: &str) -> Market {
+        self.get(stock_no)
+            .map(|company| company.market)
+            .unwrap_or_default()
+    }
+
+    /// 顯示用：取得產業名稱，找不到時回傳 "未知產業"；產業表沒有的代號附上代號
+    pub fn get_industry(&self, stock_no: &str) -> String {
+        match (self.industry_name(stock_no), self.get(stock_no)) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(company)) if !company.industry.is_empty() => {
+                format!("未知產業 ({})", company.industry)
+            }
+            (None, _) => "未知產業".to_string(),
+        }
+    }
+
+    /// 顯示用：取得類股，找不到時回傳 "未分類"
+    pub fn get_sector(&self, stock_no: &str) -> String {
+        self.sector(stock_no).unwrap_or("未分類").to_string()
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// keyword 的每個字元依序出現在 text 中 (例如 "台電" 符合 "台積電")
+fn is_subsequence(keyword: &str, text: &str) -> bool {
+    let mut chars = text.chars();
+    keyword.chars().all(|k| chars.any(|c| c == k))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompanyInfo {
-    #[serde(rename = "公司代號")]
+    #[serde(rename = "公司代號", alias = "SecuritiesCompanyCode")]
     pub stock_no: String,
-    #[serde(rename = "公司簡稱")]
+    #[serde(rename = "公司簡稱", alias = "CompanyAbbreviation")]
     pub name: String,
-    #[serde(rename = "產業別")]
+    #[serde(rename = "產業別", alias = "SecuritiesIndustryCode")]
     pub industry: String,
+    #[serde(default)]
+    pub market: Market,
+    #[serde(default)]
+    pub security_type: SecurityType,
+    #[serde(flatten)]
+    pub profile: CompanyProfile,
+}
+
+/// t187ap03_L 的其他基本資料，TWSE/TPEx 都是字串
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompanyProfile {
+    #[serde(rename = "公司名稱", alias = "CompanyName")]
+    pub full_name: String,
+    #[serde(rename = "董事長", alias = "Chairman")]
+    pub chairman: String,
+    #[serde(rename = "總經理", alias = "GeneralManager")]
+    pub general_manager: String,
+    #[serde(rename = "成立日期", alias = "DateOfIncorporation")]
+    pub established_date: String, // YYYYMMDD
+    #[serde(rename = "上市日期", alias = "上櫃日期", alias = "DateOfListing")]
+    pub listing_date: String, // YYYYMMDD
+    #[serde(rename = "普通股每股面額", alias = "ParValueOfCommonStock")]
+    pub par_value: String, // 例如 "新台幣                 10.0000元"
+    #[serde(rename = "實收資本額", alias = "Paidin.Capital.NTDollars")]
+    pub paid_in_capital: String, // 元
+    #[serde(rename = "已發行普通股數或TDR原股發行股數", alias = "IssueShares")]
+    pub issued_shares: String,
+    #[serde(rename = "私募股數", alias = "PrivateStock.shares")]
+    pub private_placement_shares: String,
+    #[serde(rename = "特別股", alias = "PreferredStock.shares")]
+    pub preferred_shares: String,
+    #[serde(rename = "網址", alias = "WebAddress")]
+    pub website: String,
+}
+
+/// 去掉千分位、幣別與單位後的數字
+fn parse_number(text: &str) -> Option<f64> {
+    let number: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse().ok()
+}
+
+impl CompanyInfo {
+    /// 流通在外股數：優先使用已發行普通股數，沒有時以實收資本額 / 每股面額 (預設 10 元) 推算
+    pub fn shares_outstanding(&self) -> Option<u64> {
+        if let Some(shares) = parse_number(&self.profile.issued_shares).filter(|s| *s > 0.0) {
+            return Some(shares as u64);
+        }
+        let capital = parse_number(&self.profile.paid_in_capital).filter(|c| *c > 0.0)?;
+        let par_value = parse_number(&self.profile.par_value)
+            .filter(|p| *p > 0.0)
+            .unwrap_or(10.0);
+        Some((capital / par_value) as u64)
+    }
+
+    /// 上市/上櫃日期；民國年 (YYYMMDD) 也接受
+    pub fn listing_date(&self) -> Option<NaiveDate> {
+        let digits: String = self
+            .profile
+            .listing_date
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect();
+        let (year, rest) = digits.split_at_checked(digits.len().checked_sub(4)?)?;
+        let year: i32 = year.parse().ok()?;
+        let year = if year < 1911 { year + 1911 } else { year };
+        NaiveDate::from_ymd_opt(year, rest[..2].parse().ok()?, rest[2..].parse().ok()?)
+    }
+
+    /// ETF 清單中的一檔，沒有公司基本資料
+    pub fn etf(stock_no: &str, name: &str, market: Market) -> Self {
+        CompanyInfo {
+            stock_no: stock_no.to_string(),
+            name: name.trim().to_string(),
+            industry: ETF_INDUSTRY.to_string(),
+            market,
+            security_type: SecurityType::from_etf_name(name),
+            profile: CompanyProfile::default(),
+        }
+    }
+
+    pub fn is_etf(&self) -> bool {
+        self.security_type != SecurityType::Stock
+    }
+
+    /// 以收盤價計算的市值 (元)
+    pub fn market_cap(&self, daily: &DailyData) -> Option<f64> {
+        Some(self.shares_outstanding()? as f64 * daily.close)
+    }
+
+    /// 週轉率：成交股數 / 流通在外股數
+    pub fn turnover_rate(&self, daily: &DailyData) -> Option<f64> {
+        let shares = self.shares_outstanding().filter(|s| *s > 0)?;
+        Some(daily.volume as f64 / shares as f64)
+    }
+}
+
+impl Record for CompanyInfo {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("market", "市場", ColumnKind::Text),
+            Column::new("security_type", "類型", ColumnKind::Text),
+            Column::new("industry", "產業", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.stock_no),
+            record::text(&self.name),
+            record::text(&self.market.to_string()),
+            record::text(&self.security_type.to_string()),
+            record::text(&company_map.get_industry(&self.stock_no)),
+        ]
+    }
+}
+
+/// 抓取設定中各市場的公司與 ETF 清單 (未套用排除條件)
+async fn fetch_company_list() -> Result<CompanyCache, Box<dyn Error>> {
+    let mut companies = Vec::new();
+    for market in &config::get().universe.markets {
+        match market {
+            Market::Twse => {
+                companies.extend(fetch_twse_company_map().await?);
+                companies.extend(etf_list::fetch_etf_list().await?);
+            }
+            Market::Tpex => {
+                companies.extend(tpex::company_list::fetch_company_list().await?);
+                companies.extend(tpex::etf_list::fetch_etf_list().await?);
+            }
+        }
+    }
+    Ok(CompanyCache {
+        fetched_at: Local::now().to_rfc3339(),
+        companies,
+    })
 }
 
 /// 從 TWSE API 抓取上市公司代號 → 中文名稱
-async fn fetch_twse_company_map(
-    ignore_stocks: &[String],
-) -> Result<Vec<CompanyInfo>, Box<dyn Error>> {
-    let url = "https://openapi.twse.com.tw/v1/opendata/t187ap03_L";
-    let resp = reqwest::get(url).await?.json::<Vec<CompanyInfo>>().await?;
+async fn fetch_twse_company_map() -> Result<Vec<CompanyInfo>, Box<dyn Error>> {
+    let config = config::get();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.twse.timeout_secs))
+        .build()?;
+    let companies = client
+        .get(&config.twse.company_url)
+        .header("User-Agent", &config.twse.user_agent)
+        .send()
+        .await?
+        .json::<Vec<CompanyInfo>>()
+        .await?;
 
-    let mut ret_results = Vec::new();
+    Ok(companies)
+}
 
-    for company in resp {
-        if ignore_stocks.contains(&company.stock_no) {
-            continue;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company(stock_no: &str, name: &str, industry: &str) -> CompanyInfo {
+        CompanyInfo {
+            stock_no: stock_no.to_string(),
+            name: name.to_string(),
+            industry: industry.to_string(),
+            market: Market::Twse,
+            security_type: SecurityType::Stock,
+            profile: CompanyProfile::default(),
         }
-        if company.industry == "17" {
-            // skip 金融保險業
-            continue;
+    }
+
+    #[test]
+    fn lookups_cover_companies_outside_scope() {
+        let company_map = CompanyMap::with_companies(vec![
+            company("2330", "台積電", "24"),
+            company("2317", "鴻海", "31"),
+            CompanyInfo::etf("00632R", "元大台灣50反1", Market::Twse),
+        ]);
+        let scoped = company_map.filter(|company| company.stock_no == "2330");
+
+        assert_eq!(scoped.stock_map.len(), 1);
+        assert_eq!(scoped.position("2317"), None);
+        assert_eq!(scoped.get_name("2317"), "鴻海");
+        assert_eq!(scoped.market("2317"), Market::Twse);
+        assert!(scoped.is_etf("00632R"));
+        assert_eq!(scoped.security_type("00632R"), SecurityType::InverseEtf);
+        assert_eq!(scoped.get_name("9999"), "未知公司");
+    }
+
+    #[test]
+    fn search_ranks_exact_prefix_contains_subsequence() {
+        let company_map = CompanyMap::with_companies(vec![
+            company("1101", "台泥", "01"),
+            company("2303", "聯電", "24"),
+            company("2308", "台達電", "28"),
+            company("2330", "台積電", "24"),
+            company("3045", "台灣大", "27"),
+            company("8033", "雷虎", "31"),
+        ]);
+        let stock_nos = |keyword: &str| -> Vec<String> {
+            company_map
+                .search(keyword)
+                .iter()
+                .map(|company| company.stock_no.clone())
+                .collect()
+        };
+
+        // 股號完全相同 → 開頭相同 → 包含，同級依股號排序
+        assert_eq!(stock_nos("2330"), ["2330"]);
+        assert_eq!(stock_nos("30"), ["3045", "2303", "2308", "2330"]);
+        assert_eq!(stock_nos("33"), ["2330", "8033"]);
+        // 名稱：開頭相同 → 包含 → 字元依序出現
+        assert_eq!(stock_nos("台"), ["1101", "2308", "2330", "3045"]);
+        assert_eq!(stock_nos("電"), ["2303", "2308", "2330"]);
+        assert_eq!(stock_nos("台電"), ["2308", "2330"]);
+        assert_eq!(stock_nos("聯電"), ["2303"]);
+        assert!(stock_nos("不存在").is_empty());
+        // 空白關鍵字回傳全部
+        assert_eq!(stock_nos("  ").len(), 6);
+    }
+
+    #[test]
+    fn etf_type_from_name() {
+        for (name, expected) in [
+            ("元大台灣50", SecurityType::Etf),
+            ("元大高股息", SecurityType::Etf),
+            ("元大台灣50正2", SecurityType::LeveragedEtf),
+            ("富邦臺灣加權正二", SecurityType::LeveragedEtf),
+            ("期元大S&P500 2X", SecurityType::LeveragedEtf),
+            ("國泰臺灣加權反1", SecurityType::InverseEtf),
+            ("元大台灣50反一", SecurityType::InverseEtf),
+            ("期街口道瓊銅反向", SecurityType::InverseEtf),
+            ("期元大美債20年-1x", SecurityType::InverseEtf),
+        ] {
+            assert_eq!(SecurityType::from_etf_name(name), expected, "{name}");
         }
+    }
 
-        ret_results.push(company);
-    }
-
-    Ok(ret_results)
-}
-fn build_industry_map() -> HashMap<String, String> {
-    let industries = vec![
-        ("01", "水泥工業"),
-        ("02", "食品工業"),
-        ("03", "塑膠工業"),
-        ("04", "紡織纖維"),
-        ("05", "電機機械"),
-        ("06", "電器電纜"),
-        ("08", "玻璃陶瓷"),
-        ("09", "造紙工業"),
-        ("10", "鋼鐵工業"),
-        ("11", "橡膠工業"),
-        ("12", "汽車工業"),
-        ("13", "電子工業"),
-        ("14", "建材營造業"),
-        ("15", "航運業"),
-        ("16", "觀光餐旅"),
-        ("17", "金融保險業"),
-        ("18", "貿易百貨業"),
-        ("19", "綜合"),
-        ("20", "其他業"),
-        ("21", "化學工業"),
-        ("22", "生技醫療業"),
-        ("23", "油電燃氣業"),
-        ("24", "半導體業"),
-        ("25", "電腦及週邊設備業"),
-        ("26", "光電業"),
-        ("27", "通信網路業"),
-        ("28", "電子零組件業"),
-        ("29", "電子通路業"),
-        ("30", "資訊服務業"),
-        ("31", "其他電子業"),
-        ("32", "文化創意業"),
-        ("33", "農業科技業"),
-        ("34", "電子商務"),
-        ("35", "綠能環保"),
-        ("36", "數位雲端"),
-        ("37", "運動休閒"),
-        ("38", "居家生活"),
-    ];
-
-    // 將 Vec 轉換為 HashMap
-    industries
-        .into_iter()
-        .map(|(code, name)| (code.to_string(), name.to_string()))
-        .collect()
+    #[test]
+    fn etf_entry_uses_etf_industry() {
+        let etf = CompanyInfo::etf("00631L", " 元大台灣50正2 ", Market::Twse);
+        assert_eq!(etf.name, "元大台灣50正2");
+        assert_eq!(etf.industry, ETF_INDUSTRY);
+        assert_eq!(etf.security_type, SecurityType::LeveragedEtf);
+        assert!(etf.is_etf());
+    }
+
+    #[test]
+    fn search_ignores_case() {
+        let company_map = CompanyMap::with_companies(vec![
+            company("2330", "台積電", "24"),
+            CompanyInfo::etf("00632R", "元大台灣50反1", Market::Twse),
+        ]);
+        let found = company_map.search("00632r");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].stock_no, "00632R");
+    }
 }
@@ -0,0 +1,36 @@
+use std::error::Error;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config;
+use crate::twse::company_map::{CompanyInfo, Market};
+
+#[derive(Debug, Deserialize)]
+struct Quote {
+    #[serde(rename = "Code")]
+    code: String,
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+/// 公司清單 API 不含 ETF，改從上市證券每日收盤行情取出代號 00 開頭的 ETF
+pub async fn fetch_etf_list() -> Result<Vec<CompanyInfo>, Box<dyn Error>> {
+    let config = config::get();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.twse.timeout_secs))
+        .build()?;
+    let quotes = client
+        .get(&config.twse.etf_url)
+        .header("User-Agent", &config.twse.user_agent)
+        .send()
+        .await?
+        .json::<Vec<Quote>>()
+        .await?;
+
+    Ok(quotes
+        .iter()
+        .filter(|quote| quote.code.starts_with("00"))
+        .map(|quote| CompanyInfo::etf(&quote.code, &quote.name, Market::Twse))
+        .collect())
+}
@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common;
+use crate::config;
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::CompanyMap;
+
+/// 自訂產業表，存在時覆蓋內建的同代號項目並加入新代號，格式同 src/twse/industries.toml
+pub const INDUSTRY_FILE: &str = "industries.toml";
+
+/// 公司產業別異動紀錄，refresh-companies 時寫入
+pub const INDUSTRY_HISTORY_FILE: &str = "industry_history.json";
+
+const BUNDLED_INDUSTRIES: &str = include_str!("industries.toml");
+
+/// 匯出自訂產業表時的檔頭說明
+const INDUSTRY_FILE_HEADER: &str = "\
+# 自訂產業表：覆蓋內建的同代號項目或加入新代號，未列出的代號使用內建產業表
+# 格式:
+# [[industry]]
+# code = \"24\"
+# name = \"半導體業\"
+# sector = \"電子\"
+";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Industry {
+    pub code: String,
+    pub name: String,
+    pub sector: String, // 類股，例如 電子、傳產、金融
+}
+
+/// 產業代號 → 產業名稱與類股
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndustryTaxonomy {
+    #[serde(rename = "industry", default)]
+    pub industries: Vec<Industry>, // 依代號排序
+}
+
+impl IndustryTaxonomy {
+    /// 編譯時內建的產業表
+    pub fn bundled() -> Self {
+        toml::from_str(BUNDLED_INDUSTRIES).expect("內建產業表 src/twse/industries.toml 格式錯誤")
+    }
+
+    /// 內建產業表，再套用 industries.toml (格式錯誤時記錄並忽略)
+    pub fn load() -> Self {
+        let mut taxonomy = Self::bundled();
+        let path = config::path(INDUSTRY_FILE);
+        if path.exists() {
+            match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| toml::from_str::<Self>(&content).map_err(|e| e.to_string()))
+            {
+                Ok(overrides) => taxonomy.merge(overrides),
+                Err(e) => common::log(&format!("{} 格式錯誤，使用內建產業表: {e}", path.display())),
+            }
+        }
+        taxonomy.industries.sort_by(|a, b| a.code.cmp(&b.code));
+        taxonomy
+    }
+
+    fn merge(&mut self, other: Self) {
+        for industry in other.industries {
+            match self.industries.iter_mut().find(|i| i.code == industry.code) {
+                Some(existing) => *existing = industry,
+                None => self.industries.push(industry),
+            }
+        }
+    }
+
+    /// 與內建產業表不同或內建沒有的項目，也就是自訂產業表的內容
+    pub fn overrides(&self) -> Self {
+        let bundled = Self::bundled();
+        IndustryTaxonomy {
+            industries: self
+                .industries
+                .iter()
+                .filter(|industry| bundled.get(&industry.code) != Some(*industry))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// 寫出產業表，附上格式說明；沒有項目時只有說明，之後可直接加入 [[industry]]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut content = INDUSTRY_FILE_HEADER.to_string();
+        if !self.industries.is_empty() {
+            content.push('\n');
+            content.push_str(&toml::to_string_pretty(self)?);
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, code: &str) -> Option<&Industry> {
+        self.industries
+            .iter()
+            .find(|industry| industry.code == code)
+    }
+
+    /// 產業代號或名稱轉成產業代號
+    pub fn code_of(&self, industry: &str) -> Option<&str> {
+        self.industries
+            .iter()
+            .find(|i| i.code == industry || i.name == industry)
+            .map(|i| i.code.as_str())
+    }
+
+    /// 所有類股，依產業表中第一次出現的順序
+    pub fn sectors(&self) -> Vec<&str> {
+        let mut sectors: Vec<&str> = Vec::new();
+        for industry in &self.industries {
+            if !sectors.contains(&industry.sector.as_str()) {
+                sectors.push(&industry.sector);
+            }
+        }
+        sectors
+    }
+
+    /// 類股內的產業代號
+    pub fn codes_in_sector(&self, sector: &str) -> Vec<&str> {
+        self.industries
+            .iter()
+            .filter(|industry| industry.sector == sector)
+            .map(|industry| industry.code.as_str())
+            .collect()
+    }
+}
+
+/// 產業與範圍內的公司數
+#[derive(Debug, Clone)]
+pub struct IndustrySummary {
+    pub industry: Industry,
+    pub companies: usize,
+}
+
+impl Record for IndustrySummary {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("code", "代號", ColumnKind::Text),
+            Column::new("name", "產業", ColumnKind::Text),
+            Column::new("sector", "類股", ColumnKind::Text),
+            Column::new("companies", "公司數", ColumnKind::Integer),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.industry.code),
+            record::text(&self.industry.name),
+            record::text(&self.industry.sector),
+            Value::from(self.companies),
+        ]
+    }
+}
+
+/// 一次產業別異動
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndustryChange {
+    pub stock_no: String,
+    pub date: String, // 發現異動的日期 (YYYY-MM-DD)
+    pub from: String, // 產業代號
+    pub to: String,
+}
+
+/// 讀取產業別異動紀錄 (沒有檔案時為空)，依日期排序
+pub fn load_history() -> Vec<IndustryChange> {
+    let mut history: Vec<IndustryChange> = File::open(config::path(INDUSTRY_HISTORY_FILE))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default();
+    history.sort_by(|a, b| a.date.cmp(&b.date));
+    history
+}
+
+/// 把新的異動加到紀錄檔
+pub fn append_history(changes: &[IndustryChange]) -> Result<(), Box<dyn Error>> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut history = load_history();
+    history.extend_from_slice(changes);
+    let writer = BufWriter::new(File::create(config::path(INDUSTRY_HISTORY_FILE))?);
+    serde_json::to_writer_pretty(writer, &history)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn industry(code: &str, name: &str, sector: &str) -> Industry {
+        Industry {
+            code: code.to_string(),
+            name: name.to_string(),
+            sector: sector.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_bundled_industries() {
+        let taxonomy = IndustryTaxonomy::bundled();
+        assert!(!taxonomy.industries.is_empty());
+        assert_eq!(
+            taxonomy.get("01"),
+            Some(&industry("01", "水泥工業", "傳產"))
+        );
+        assert_eq!(
+            taxonomy.get("17"),
+            Some(&industry("17", "金融保險業", "金融"))
+        );
+        assert_eq!(taxonomy.code_of("半導體業"), Some("24"));
+        assert_eq!(taxonomy.get("24").unwrap().sector, "電子");
+
+        let sectors = taxonomy.sectors();
+        for sector in ["傳產", "電子", "金融"] {
+            assert!(sectors.contains(&sector), "缺少類股 {sector}");
+        }
+    }
+
+    #[test]
+    fn overrides_only_keep_changed_entries() {
+        let mut taxonomy = IndustryTaxonomy::bundled();
+        taxonomy.merge(IndustryTaxonomy {
+            industries: vec![
+                industry("01", "水泥", "傳產"),
+                industry("24", "半導體業", "電子"),
+                industry("99", "新產業", "其他"),
+            ],
+        });
+
+        assert_eq!(taxonomy.get("01").unwrap().name, "水泥");
+        assert_eq!(
+            taxonomy.overrides().industries,
+            [
+                industry("01", "水泥", "傳產"),
+                industry("99", "新產業", "其他")
+            ]
+        );
+        assert!(
+            IndustryTaxonomy::bundled()
+                .overrides()
+                .industries
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn saved_file_parses_back() {
+        let path = std::env::temp_dir().join(format!("industries_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let empty = IndustryTaxonomy::default();
+        empty.save(path).unwrap();
+        let loaded: IndustryTaxonomy = toml::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert!(loaded.industries.is_empty());
+
+        let overrides = IndustryTaxonomy {
+            industries: vec![industry("99", "新產業", "其他")],
+        };
+        overrides.save(path).unwrap();
+        let loaded: IndustryTaxonomy = toml::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(loaded.industries, overrides.industries);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,2 +1,5 @@
+pub mod company_cache;
 pub mod company_map;
+pub mod etf_list;
+pub mod industry;
 pub mod stock_data;
@@ -3,6 +3,8 @@ use std::error::Error;
 use serde::{Deserialize, Serialize};
 use tokio::time::Duration;
 
+use crate::common;
+use crate::config;
 use crate::twse::company_map::CompanyMap;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,60 +16,68 @@ pub struct TwseResponse {
     pub data: Option<Vec<Vec<String>>>,
 }
 
+/// TWSE 偶爾對有資料的月份回傳這些訊息，重試幾次後才當作錯誤
+const FETCH_RETRIES: usize = 3;
+
 impl TwseResponse {
-    pub async fn new(company_map: &CompanyMap, stock_no: &str, year_month: &str) -> Self {
+    pub async fn new(
+        company_map: &CompanyMap,
+        stock_no: &str,
+        year_month: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut attempts = 0;
         loop {
-            let fetch_result = fetch_stock_data(stock_no, year_month).await.unwrap();
-            let parsed: TwseResponse = serde_json::from_str(&fetch_result).unwrap();
+            attempts += 1;
+            let fetch_result = fetch_stock_data(stock_no, year_month).await?;
+            let parsed: TwseResponse = serde_json::from_str(&fetch_result)?;
+
+            if parsed.stat == "OK" {
+                return Ok(parsed);
+            }
 
-            if parsed.stat != "OK" {
-                if parsed.stat.contains("查詢日期大於")
-                    || parsed.stat.contains("查詢日期小於")
-                    || parsed.stat.contains("很抱歉，沒有符合條件的資料")
-                {
-                    println!(
-                        "TWSE 回傳訊息: {} for stock_no: {}({}), year_month: {}. Try again!",
-                        parsed.stat,
-                        stock_no,
-                        company_map.get(stock_no),
-                        year_month
-                    );
-                    continue;
-                } else {
-                    panic!(
-                        "TWSE 回傳錯誤狀態: {} for stock_no: {}({}), year_month: {}",
-                        parsed.stat,
-                        stock_no,
-                        company_map.get(stock_no),
-                        year_month
-                    );
-                }
-            } else {
-                return TwseResponse {
-                    stat: parsed.stat,
-                    title: parsed.title,
-                    data: parsed.data,
-                };
+            let retryable = parsed.stat.contains("查詢日期大於")
+                || parsed.stat.contains("查詢日期小於")
+                || parsed.stat.contains("很抱歉，沒有符合條件的資料");
+            if !retryable || attempts >= FETCH_RETRIES {
+                return Err(format!(
+                    "TWSE 回傳錯誤狀態: {} for stock_no: {}({}), year_month: {}",
+                    parsed.stat,
+                    stock_no,
+                    company_map.get_name(stock_no),
+                    year_month
+                )
+                .into());
             }
+            common::log(&format!(
+                "TWSE 回傳訊息: {} for stock_no: {}({}), year_month: {}. Try again!",
+                parsed.stat,
+                stock_no,
+                company_map.get_name(stock_no),
+                year_month
+            ));
         }
     }
 }
 
-pub async fn fetch_stock_data(stock_no: &str, year_month: &str) -> Result<String, Box<dyn Error>> {
+pub async fn fetch_stock_data(
+    stock_no: &str,
+    year_month: &str,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
     let date_str = format!("{year_month}01"); // TWSE API 需要完整日期
 
+    let twse = &config::get().twse;
     let url = format!(
-        "https://www.twse.com.tw/exchangeReport/STOCK_DAY?response=json&date={}&stockNo={}",
-        date_str, stock_no
+        "{}?response=json&date={}&stockNo={}",
+        twse.stock_day_url, date_str, stock_no
     );
 
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(twse.timeout_secs))
         .build()?;
 
     let response = client
         .get(&url)
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/144.0.0.0 Safari/537.36")
+        .header("User-Agent", &twse.user_agent)
         .header("Accept", "application/json")
         .send()
         .await?;
@@ -0,0 +1,214 @@
+use std::fmt;
+
+use chrono::{Local, NaiveDate};
+use serde_json::Value;
+
+use crate::config::{self, UniverseConfig};
+use crate::data::monthly_data;
+use crate::output::record::{self, Column, ColumnKind, Record};
+use crate::twse::company_map::{CompanyInfo, CompanyMap, Market, SecurityType};
+use crate::universe::ignore::{self, IgnoredStock};
+use crate::universe::market_cap::MarketCapRange;
+
+/// 不納入掃描範圍的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exclusion {
+    Market(Market),
+    Industry(String), // 產業代號
+    Ignored {
+        until: Option<NaiveDate>,
+        reason: String,
+    },
+    SecurityType(SecurityType),
+    NewListing(i64), // 上市天數
+    MarketCap(f64),  // 億元
+    NoMarketCap,
+    AvgVolume(u64), // 張
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exclusion::Market(market) => write!(f, "不在設定的市場 ({market})"),
+            Exclusion::Industry(code) => write!(f, "排除的產業 ({code})"),
+            Exclusion::Ignored { until, reason } => {
+                write!(f, "忽略清單")?;
+                if !reason.is_empty() {
+                    write!(f, ": {reason}")?;
+                }
+                if let Some(until) = until {
+                    write!(f, " (至 {until})")?;
+                }
+                Ok(())
+            }
+            Exclusion::SecurityType(security_type) => write!(f, "不納入的類型 ({security_type})"),
+            Exclusion::NewListing(days) => write!(f, "上市未滿 {days} 天"),
+            Exclusion::MarketCap(market_cap) => write!(f, "市值 {market_cap:.0} 億元不在範圍內"),
+            Exclusion::NoMarketCap => write!(f, "無法計算市值"),
+            Exclusion::AvgVolume(volume) => write!(f, "日均量 {volume} 張過低"),
+        }
+    }
+}
+
+/// 被排除的公司與原因
+#[derive(Debug, Clone)]
+pub struct ExcludedCompany {
+    pub company: CompanyInfo,
+    pub reason: Exclusion,
+}
+
+impl Record for ExcludedCompany {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("stock_no", "股號", ColumnKind::Text),
+            Column::new("name", "公司名稱", ColumnKind::Text),
+            Column::new("market", "市場", ColumnKind::Text),
+            Column::new("reason", "排除原因", ColumnKind::Text),
+        ]
+    }
+
+    fn values(&self, _company_map: &CompanyMap) -> Vec<Value> {
+        vec![
+            record::text(&self.company.stock_no),
+            record::text(&self.company.name),
+            record::text(&self.company.market.to_string()),
+            record::text(&self.reason.to_string()),
+        ]
+    }
+}
+
+/// 依 [universe] 設定與忽略清單決定哪些股票納入掃描。
+/// 不需要本地資料的條件 (市場、產業、忽略清單、證券類型、上市天數) 在建立 CompanyMap 時套用，
+/// 市值與日均量需要讀取日資料，只在掃描前套用，避免影響抓取資料
+pub struct UniverseFilter {
+    config: UniverseConfig,
+    ignored: Vec<IgnoredStock>,
+    today: NaiveDate,
+}
+
+impl UniverseFilter {
+    pub fn from_config() -> Self {
+        UniverseFilter {
+            config: config::get().universe.clone(),
+            ignored: ignore::load(),
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// 命令列指定的市值範圍優先於設定檔
+    pub fn with_market_cap(mut self, range: MarketCapRange) -> Self {
+        if range.min.is_some() {
+            self.config.min_market_cap = range.min;
+        }
+        if range.max.is_some() {
+            self.config.max_market_cap = range.max;
+        }
+        self
+    }
+
+    fn market_cap(&self) -> MarketCapRange {
+        MarketCapRange {
+            min: self.config.min_market_cap,
+            max: self.config.max_market_cap,
+        }
+    }
+
+    /// 不需要本地資料的排除條件
+    pub fn static_exclusion(&self, company: &CompanyInfo) -> Option<Exclusion> {
+        let universe = &self.config;
+        if !universe.markets.contains(&company.market) {
+            return Some(Exclusion::Market(company.market));
+        }
+        if let Some(ignored) = self
+            .ignored
+            .iter()
+            .find(|ignored| ignored.stock_no == company.stock_no && ignored.is_active(self.today))
+        {
+            return Some(Exclusion::Ignored {
+                until: ignored.until,
+                reason: ignored.reason.clone(),
+            });
+        }
+        if universe.exclude_industries.contains(&company.industry)
+            || (!universe.include_industries.is_empty()
+                && !universe.include_industries.contains(&company.industry))
+        {
+            return Some(Exclusion::Industry(company.industry.clone()));
+        }
+        if !universe.security_types.contains(&company.security_type) {
+            return Some(Exclusion::SecurityType(company.security_type));
+        }
+        if universe.min_listing_days > 0
+            && let Some(listing_date) = company.listing_date()
+        {
+            let days = (self.today - listing_date).num_days();
+            if days < universe.min_listing_days as i64 {
+                return Some(Exclusion::NewListing(days));
+            }
+        }
+        None
+    }
+
+    fn has_data_filters(&self) -> bool {
+        !self.market_cap().is_unbounded() || self.config.min_avg_volume.is_some()
+    }
+
+    /// 需要本地日資料的排除條件；有設定條件時，沒有資料的公司一律排除
+    pub fn data_exclusion(&self, company: &CompanyInfo) -> Option<Exclusion> {
+        if !self.has_data_filters() {
+            return None;
+        }
+        let days = self.config.avg_volume_days.max(1);
+        let daily_data = monthly_data::recent_stored_daily_data(&company.stock_no, days);
+
+        let range = self.market_cap();
+        if !range.is_unbounded() {
+            let Some(market_cap) = daily_data
+                .last()
+                .and_then(|daily| company.market_cap(daily))
+                .map(|market_cap| market_cap / 1e8)
+            else {
+                return Some(Exclusion::NoMarketCap);
+            };
+            if !range.contains(market_cap) {
+                return Some(Exclusion::MarketCap(market_cap));
+            }
+        }
+
+        if let Some(min_avg_volume) = self.config.min_avg_volume {
+            let avg_volume = if daily_data.is_empty() {
+                0
+            } else {
+                daily_data.iter().map(|daily| daily.volume).sum::<u64>()
+                    / daily_data.len() as u64
+                    / 1000
+            };
+            if avg_volume < min_avg_volume {
+                return Some(Exclusion::AvgVolume(avg_volume));
+            }
+        }
+        None
+    }
+
+    /// 套用需要本地資料的條件
+    pub fn apply(&self, company_map: &CompanyMap) -> CompanyMap {
+        if !self.has_data_filters() {
+            return company_map.filter(|_| true);
+        }
+        company_map.filter(|company| self.data_exclusion(company).is_none())
+    }
+
+    /// company_map 中因需要本地資料的條件而被排除的公司
+    pub fn data_exclusions(&self, company_map: &CompanyMap) -> Vec<ExcludedCompany> {
+        company_map
+            .stock_map
+            .iter()
+            .filter_map(|company| {
+                self.data_exclusion(company).map(|reason| ExcludedCompany {
+                    company: company.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
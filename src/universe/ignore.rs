@@ -0,0 +1,105 @@
+use std::fs;
+
+use chrono::NaiveDate;
+
+use crate::common;
+use crate::config;
+
+/// 忽略清單，一行一檔：股號 [到期日 YYYY-MM-DD] [// 原因]，例如
+/// 4590 // 長期停牌
+/// 2248 2026-12-31 // 處置股
+/// 沒有到期日表示永久忽略，過了到期日自動恢復
+pub const IGNORE_FILE: &str = "ignore_stocks.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoredStock {
+    pub stock_no: String,
+    pub until: Option<NaiveDate>,
+    pub reason: String,
+}
+
+impl IgnoredStock {
+    fn parse(line: &str) -> Option<Self> {
+        let (entry, reason) = line.split_once("//").unwrap_or((line, ""));
+        let mut fields = entry.split_whitespace();
+        let stock_no = fields.next()?.to_string();
+        let until = match fields.next() {
+            // 到期日打錯時整行略過 (仍納入掃描)，不能變成永久忽略
+            Some(date) => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    common::log(&format!(
+                        "{IGNORE_FILE}: 無效的到期日 {date}，略過 {stock_no}"
+                    ));
+                    return None;
+                }
+            },
+            None => None,
+        };
+        Some(IgnoredStock {
+            stock_no,
+            until,
+            reason: reason.trim().to_string(),
+        })
+    }
+
+    /// 到期日當天仍然忽略
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        self.until.is_none_or(|until| today <= until)
+    }
+}
+
+/// 讀取忽略清單 (沒有檔案時為空)
+pub fn load() -> Vec<IgnoredStock> {
+    fs::read_to_string(config::path(IGNORE_FILE))
+        .map(|content| content.lines().filter_map(IgnoredStock::parse).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn parses_permanent_entry() {
+        let ignored = IgnoredStock::parse("4590 // 長期停牌").unwrap();
+        assert_eq!(ignored.stock_no, "4590");
+        assert_eq!(ignored.until, None);
+        assert_eq!(ignored.reason, "長期停牌");
+        assert!(ignored.is_active(date("2099-01-01")));
+    }
+
+    #[test]
+    fn parses_expiry_date() {
+        let ignored = IgnoredStock::parse("2248 2026-12-31 // 處置股").unwrap();
+        assert_eq!(ignored.until, Some(date("2026-12-31")));
+        assert_eq!(ignored.reason, "處置股");
+
+        // 到期日當天仍然忽略，隔天恢復
+        assert!(ignored.is_active(date("2026-12-31")));
+        assert!(!ignored.is_active(date("2027-01-01")));
+    }
+
+    #[test]
+    fn parses_entry_without_reason() {
+        let ignored = IgnoredStock::parse("1101 2026-01-15").unwrap();
+        assert_eq!(ignored.until, Some(date("2026-01-15")));
+        assert_eq!(ignored.reason, "");
+    }
+
+    #[test]
+    fn skips_invalid_expiry_date() {
+        assert_eq!(IgnoredStock::parse("2248 2026/12/31 // 處置股"), None);
+        assert_eq!(IgnoredStock::parse("2248 2026-13-01"), None);
+    }
+
+    #[test]
+    fn skips_blank_and_comment_lines() {
+        assert_eq!(IgnoredStock::parse(""), None);
+        assert_eq!(IgnoredStock::parse("// 註解"), None);
+    }
+}
@@ -0,0 +1,16 @@
+/// 市值範圍 (億元)，以本地最新一筆收盤價計算，由 UniverseFilter 套用
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketCapRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl MarketCapRange {
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, market_cap: f64) -> bool {
+        self.min.is_none_or(|min| market_cap >= min) && self.max.is_none_or(|max| market_cap <= max)
+    }
+}
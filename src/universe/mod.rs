@@ -0,0 +1,5 @@
+pub mod filter;
+pub mod ignore;
+pub mod market_cap;
+pub mod scope;
+pub mod watchlist;
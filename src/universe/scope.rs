@@ -0,0 +1,58 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::twse::company_map::{CompanyMap, Market};
+use crate::universe::watchlist;
+
+/// 掃描範圍：全市場 (上市 + 上櫃)、單一市場、自選股清單、單一產業或單一類股
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope {
+    All,
+    Market(Market),
+    Watchlist(String),
+    Industry(String),
+    Sector(String),
+}
+
+impl Scope {
+    /// 回傳只包含範圍內公司的 CompanyMap
+    pub fn apply(&self, company_map: &CompanyMap) -> Result<CompanyMap, Box<dyn Error>> {
+        match self {
+            Scope::All => Ok(company_map.filter(|_| true)),
+            Scope::Market(market) => Ok(company_map.filter(|company| company.market == *market)),
+            Scope::Watchlist(name) => {
+                let stock_nos = watchlist::load(name)?;
+                Ok(company_map.filter(|company| stock_nos.contains(&company.stock_no)))
+            }
+            Scope::Industry(industry) => {
+                let code = company_map
+                    .industry_code(industry)
+                    .ok_or(format!("找不到產業: {industry}"))?;
+                Ok(company_map.filter(|company| company.industry == code))
+            }
+            Scope::Sector(sector) => {
+                let codes = company_map.taxonomy().codes_in_sector(sector);
+                if codes.is_empty() {
+                    return Err(format!(
+                        "找不到類股: {sector}，可用類股: {}",
+                        company_map.sectors().join(", ")
+                    )
+                    .into());
+                }
+                Ok(company_map.filter(|company| codes.contains(&company.industry.as_str())))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::All => write!(f, "全市場"),
+            Scope::Market(market) => write!(f, "{market}"),
+            Scope::Watchlist(name) => write!(f, "自選股 {name}"),
+            Scope::Industry(industry) => write!(f, "產業 {industry}"),
+            Scope::Sector(sector) => write!(f, "類股 {sector}"),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::common;
+use crate::config;
+
+/// 自選股清單存放目錄，每個清單一個檔案: watchlists/<名稱>.txt
+/// 一行一個股號，可用 "//" 加註解
+pub const WATCHLIST_DIR: &str = "watchlists";
+
+fn watchlist_path(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        return Err(format!("無效的清單名稱: {name}").into());
+    }
+    Ok(config::path(WATCHLIST_DIR).join(format!("{name}.txt")))
+}
+
+/// 列出所有自選股清單名稱
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(config::path(WATCHLIST_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+                .filter_map(|path| {
+                    path.file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// 讀取清單內的股號
+pub fn load(name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let path = watchlist_path(name)?;
+    if !path.exists() {
+        return Err(format!("找不到自選股清單: {name}").into());
+    }
+    Ok(common::read_lines_to_vec(path)?)
+}
+
+/// 加入股號，清單不存在時自動建立；已存在則回傳 false
+pub fn add(name: &str, stock_no: &str) -> Result<bool, Box<dyn Error>> {
+    let path = watchlist_path(name)?;
+    if path.exists() && load(name)?.iter().any(|s| s == stock_no) {
+        return Ok(false);
+    }
+
+    fs::create_dir_all(config::path(WATCHLIST_DIR))?;
+    append_line(&path, stock_no)?;
+    Ok(true)
+}
+
+/// 在檔尾加一行；手動編輯過的檔案最後一行可能沒有換行，先補上避免黏到前一個股號
+fn append_line(path: &Path, line: &str) -> Result<(), Box<dyn Error>> {
+    let needs_newline =
+        fs::read(path).is_ok_and(|content| content.last().is_some_and(|b| *b != b'\n'));
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if needs_newline {
+        writeln!(file)?;
+    }
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// 移除股號 (保留其他行與註解)；不在清單內則回傳 false
+pub fn remove(name: &str, stock_no: &str) -> Result<bool, Box<dyn Error>> {
+    let path = watchlist_path(name)?;
+    let content = fs::read_to_string(&path)?;
+
+    let mut removed = false;
+    let mut kept = Vec::new();
+    for line in content.lines() {
+        // 如果有註解，用 "//" 切開，只取前半段
+        if line.split("//").next().unwrap().trim() == stock_no {
+            removed = true;
+            continue;
+        }
+        kept.push(line);
+    }
+
+    if removed {
+        let mut new_content = kept.join("\n");
+        new_content.push('\n');
+        fs::write(path, new_content)?;
+    }
+    Ok(removed)
+}
+
+/// 刪除整個清單
+pub fn delete(name: &str) -> Result<(), Box<dyn Error>> {
+    fs::remove_file(watchlist_path(name)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_names() {
+        for name in ["", "a/b", "a\\b", "..", "list.txt"] {
+            assert!(watchlist_path(name).is_err(), "{name:?}");
+        }
+        let path = watchlist_path("半導體").unwrap();
+        assert!(path.ends_with(Path::new(WATCHLIST_DIR).join("半導體.txt")));
+    }
+
+    #[test]
+    fn append_line_keeps_lines_separate() {
+        let path = std::env::temp_dir().join(format!("watchlist_{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        append_line(&path, "2330").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "2330\n");
+
+        // 手動編輯後最後一行沒有換行
+        fs::write(&path, "2330\n2317 // 鴻海").unwrap();
+        append_line(&path, "2454").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "2330\n2317 // 鴻海\n2454\n"
+        );
+        assert_eq!(
+            common::read_lines_to_vec(&path).unwrap(),
+            ["2330", "2317", "2454"]
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+}
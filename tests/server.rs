@@ -0,0 +1,180 @@
+//! HTTP API 的路由測試：以暫存目錄當作日資料目錄，不連網也不動到本地資料
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Once};
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use stock::config::{self, Config};
+use stock::data::monthly_data::{DailyData, MonthlyData};
+use stock::server::app;
+use stock::twse::company_map::{CompanyInfo, CompanyMap, Market};
+
+static INIT: Once = Once::new();
+
+fn data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("stock-server-test-{}", std::process::id()))
+}
+
+fn daily(date: &str, open: f64, close: f64) -> DailyData {
+    DailyData {
+        date: date.to_string(),
+        volume: 30_000_000,
+        amount: (close * 30_000_000.0) as u64,
+        open,
+        high: open.max(close) + 5.0,
+        low: open.min(close) - 5.0,
+        close,
+        change: close - open,
+        transactions: 50_000,
+        note: String::new(),
+    }
+}
+
+fn company(stock_no: &str, name: &str, industry: &str) -> CompanyInfo {
+    CompanyInfo {
+        stock_no: stock_no.to_string(),
+        name: name.to_string(),
+        industry: industry.to_string(),
+        market: Market::Twse,
+        security_type: Default::default(),
+        profile: Default::default(),
+    }
+}
+
+/// 設定只能初始化一次，所有測試共用同一個暫存資料目錄；2317 沒有本地資料
+fn app() -> Router {
+    INIT.call_once(|| {
+        let dir = data_dir();
+        let _ = fs::remove_dir_all(&dir);
+        let mut config = Config::default();
+        config.data.dir = dir.to_string_lossy().to_string();
+        config::init(config);
+
+        MonthlyData {
+            stock_no: "2330".to_string(),
+            year_month: "202602".to_string(),
+            daily_data: vec![
+                daily("115/02/04", 1000.0, 990.0),
+                daily("115/02/05", 990.0, 1010.0),
+            ],
+        }
+        .write_to_storage()
+        .unwrap();
+    });
+
+    let company_map = CompanyMap::with_companies(vec![
+        company("2330", "台積電", "24"),
+        company("2317", "鴻海", "31"),
+    ]);
+    app::router(Arc::new(company_map))
+}
+
+async fn get(uri: &str) -> (StatusCode, Value) {
+    let response = app()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn companies_returns_company_views() {
+    let (status, body) = get("/companies").await;
+    assert_eq!(status, StatusCode::OK);
+    let companies = body.as_array().unwrap();
+    assert_eq!(companies.len(), 2);
+    let tsmc = companies.iter().find(|c| c["stock_no"] == "2330").unwrap();
+    assert_eq!(tsmc["name"], "台積電");
+    assert_eq!(tsmc["industry"], "半導體業");
+    assert_eq!(tsmc["sector"], "電子");
+    assert_eq!(tsmc["market"], "twse");
+    assert_eq!(tsmc["security_type"], "stock");
+    assert!(tsmc["shares_outstanding"].is_null());
+}
+
+#[tokio::test]
+async fn companies_filters_by_keyword() {
+    let (status, body) = get("/companies?q=%E9%B4%BB%E6%B5%B7").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["stock_no"], "2317");
+}
+
+#[tokio::test]
+async fn unknown_company_is_not_found() {
+    let (status, body) = get("/companies/9999").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].as_str().unwrap().contains("9999"));
+
+    let (status, _) = get("/stocks/9999/daily?from=202601&to=202602").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unknown_industry_is_bad_request() {
+    let (status, body) = get("/companies?industry=nosuch").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn daily_rejects_bad_ranges() {
+    let (status, _) = get("/stocks/2330/daily?from=2026&to=202602").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get("/stocks/2330/daily?from=202603&to=202602").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn daily_reads_local_data() {
+    let (status, body) = get("/stocks/2330/daily?from=202602&to=202602").await;
+    assert_eq!(status, StatusCode::OK);
+    let days = body.as_array().unwrap();
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[1]["date"], "115/02/05");
+    assert_eq!(days[1]["close"], 1010.0);
+}
+
+#[tokio::test]
+async fn screen_rejects_bad_expression_or_date() {
+    let (status, body) = get("/screen?expr=close%20%3E&date=20260205").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("條件錯誤"));
+
+    let (status, _) = get("/screen?expr=nosuch%20%3E%201&date=20260205").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = get("/screen?expr=close%20%3E%20open&date=2026025").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn screen_returns_matching_stocks() {
+    let (status, body) = get("/screen?expr=close%20%3E%20open&date=20260205").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["expression"], "close > open");
+    assert_eq!(body["date"], "20260205");
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["stock_no"], "2330");
+    assert_eq!(results[0]["date"], "115/02/05");
+    assert_eq!(results[0]["name"], "台積電");
+    assert_eq!(
+        results[0]["recent_closes"],
+        serde_json::json!([990.0, 1010.0])
+    );
+
+    let (status, body) = get("/screen?expr=close%20%3C%20open&date=20260205").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["results"], serde_json::json!([]));
+}